@@ -0,0 +1,868 @@
+pub mod account_store {
+    use crate::craft_launcher::core::account::microsoft_account::microsoft_account::MicrosoftAccount;
+    use crate::craft_launcher::core::account::offline_account::offline_account::OfflineAccount;
+    use crate::craft_launcher::core::account::yggdrasil_account::yggdrasil_account::YggdrasilAccount;
+    use crate::craft_launcher::core::engine::engine::Account;
+    use crate::craft_launcher::utils::file_operations::file_utils;
+    use argon2::Argon2;
+    use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+    use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+    use serde::{Deserialize, Serialize};
+    use serde_json::Value;
+    use sha2::{Digest, Sha256};
+    use std::error::Error;
+    use std::fmt;
+    use std::fs;
+    use std::io::{self, Write};
+    use std::path::{Path, PathBuf};
+
+    /// Version of the account list layout written by this crate
+    ///
+    /// 1. A bare JSON array of accounts, as written by `write_struct_to_file_as_json`
+    /// 2. An object with `schema_version`, `default` and `accounts`
+    pub const CURRENT_SCHEMA_VERSION: u64 = 2;
+
+    /// First bytes of an account store file
+    const MAGIC: &[u8; 4] = b"CLAS";
+
+    /// Version of the encrypted file layout
+    const FORMAT_VERSION: u8 = 1;
+
+    const SALT_LEN: usize = 16;
+    const NONCE_LEN: usize = 12;
+    const HEADER_LEN: usize = MAGIC.len() + 2 + SALT_LEN;
+
+    /// Shortest key file accepted, in bytes
+    pub const MIN_KEY_FILE_LEN: usize = 32;
+
+    /// Error type for the account store
+    #[derive(Debug)]
+    pub enum AccountStoreError {
+        /// The file could not be read or written
+        IoError(io::Error),
+
+        /// The passphrase or key file does not decrypt the store, or the file was modified
+        WrongKey,
+
+        /// The file is not an account store or is truncated
+        Corrupted(String),
+
+        /// The store was written by a newer version of the crate
+        UnsupportedSchema(u64),
+
+        /// The decrypted account list could not be parsed
+        ParseError(String),
+
+        /// The key file is shorter than 32 bytes
+        WeakKeyFile(usize),
+
+        /// No account has this ID
+        NotFound(String),
+    }
+
+    impl fmt::Display for AccountStoreError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                AccountStoreError::IoError(e) => write!(f, "Account store IO error: {}", e),
+                AccountStoreError::WrongKey => write!(
+                    f,
+                    "The account store could not be decrypted; the key is wrong or the file was modified"
+                ),
+                AccountStoreError::Corrupted(e) => {
+                    write!(f, "The account store is corrupted: {}", e)
+                }
+                AccountStoreError::UnsupportedSchema(version) => write!(
+                    f,
+                    "The account store uses schema {}, newer than the supported {}",
+                    version, CURRENT_SCHEMA_VERSION
+                ),
+                AccountStoreError::ParseError(e) => {
+                    write!(f, "Failed to parse the account list: {}", e)
+                }
+                AccountStoreError::WeakKeyFile(length) => write!(
+                    f,
+                    "The key file has {} bytes, at least {} are required",
+                    length, MIN_KEY_FILE_LEN
+                ),
+                AccountStoreError::NotFound(id) => write!(f, "No account has the ID {}", id),
+            }
+        }
+    }
+
+    impl Error for AccountStoreError {}
+
+    impl From<io::Error> for AccountStoreError {
+        fn from(error: io::Error) -> Self {
+            AccountStoreError::IoError(error)
+        }
+    }
+
+    /// Where the encryption key of a store comes from
+    #[derive(Clone)]
+    pub enum StoreKey {
+        /// A passphrase, stretched with Argon2id
+        Passphrase(String),
+
+        /// A file of at least 32 random bytes, e.g. from `generate_key_file`
+        KeyFile(PathBuf),
+    }
+
+    impl fmt::Debug for StoreKey {
+        /// Formats the key without revealing a passphrase
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                StoreKey::Passphrase(_) => f.write_str("Passphrase(<redacted>)"),
+                StoreKey::KeyFile(path) => f.debug_tuple("KeyFile").field(path).finish(),
+            }
+        }
+    }
+
+    impl StoreKey {
+        /// Gets the byte recorded in the file header for this kind of key
+        fn kind(&self) -> u8 {
+            match self {
+                StoreKey::Passphrase(_) => 0,
+                StoreKey::KeyFile(_) => 1,
+            }
+        }
+
+        /// Derives the 256-bit encryption key with a salt
+        fn derive(&self, salt: &[u8]) -> Result<[u8; 32], AccountStoreError> {
+            let mut key = [0u8; 32];
+            match self {
+                StoreKey::Passphrase(passphrase) => {
+                    Argon2::default()
+                        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+                        .map_err(|e| AccountStoreError::Corrupted(e.to_string()))?;
+                }
+                StoreKey::KeyFile(path) => {
+                    let contents = fs::read(path)?;
+                    if contents.len() < MIN_KEY_FILE_LEN {
+                        return Err(AccountStoreError::WeakKeyFile(contents.len()));
+                    }
+                    let mut hasher = Sha256::new();
+                    hasher.update(salt);
+                    hasher.update(&contents);
+                    key.copy_from_slice(&hasher.finalize());
+                }
+            }
+            Ok(key)
+        }
+    }
+
+    /// Writes a new key file of 32 random bytes, readable only by the owner on Unix
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Where to write the key file; an existing file is not overwritten
+    pub fn generate_key_file(path: &Path) -> Result<(), AccountStoreError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(path)?;
+        file.write_all(&rand::random::<[u8; 32]>())?;
+        file.sync_all()?;
+        Ok(())
+    }
+
+    /// An account kept in the store
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(tag = "type", rename_all = "snake_case")]
+    pub enum StoredAccount {
+        Offline(OfflineAccount),
+        Microsoft(MicrosoftAccount),
+        Yggdrasil(YggdrasilAccount),
+    }
+
+    impl StoredAccount {
+        /// Gets the ID of the account: its UUID without dashes, in lowercase
+        pub fn id(&self) -> String {
+            let uuid = match self {
+                StoredAccount::Offline(account) => &account.uuid,
+                StoredAccount::Microsoft(account) => &account.uuid,
+                StoredAccount::Yggdrasil(account) => &account.uuid,
+            };
+            uuid.replace('-', "").to_lowercase()
+        }
+
+        /// Gets the player name
+        pub fn username(&self) -> &str {
+            match self {
+                StoredAccount::Offline(account) => &account.username,
+                StoredAccount::Microsoft(account) => &account.username,
+                StoredAccount::Yggdrasil(account) => &account.username,
+            }
+        }
+
+        /// Gets the kind of account
+        pub fn kind(&self) -> AccountKind {
+            match self {
+                StoredAccount::Offline(_) => AccountKind::Offline,
+                StoredAccount::Microsoft(_) => AccountKind::Microsoft,
+                StoredAccount::Yggdrasil(_) => AccountKind::Yggdrasil,
+            }
+        }
+
+        /// Gets the launch values of the account
+        pub fn to_account(&self) -> Account {
+            match self {
+                StoredAccount::Offline(account) => account.to_account(),
+                StoredAccount::Microsoft(account) => account.to_account(),
+                StoredAccount::Yggdrasil(account) => account.to_account(),
+            }
+        }
+    }
+
+    /// Kind of a stored account
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    pub enum AccountKind {
+        Offline,
+        Microsoft,
+        Yggdrasil,
+    }
+
+    /// What can be shown about an account without exposing its tokens
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct AccountSummary {
+        /// The account ID
+        pub id: String,
+
+        /// The player name
+        pub username: String,
+
+        /// The kind of account
+        pub kind: AccountKind,
+
+        /// Whether this is the default account
+        pub is_default: bool,
+    }
+
+    /// The decrypted contents of a store
+    #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+    pub(crate) struct StoreData {
+        pub(crate) schema_version: u64,
+        pub(crate) default: Option<String>,
+        pub(crate) accounts: Vec<StoredAccount>,
+    }
+
+    /// An encrypted file of accounts with a default account
+    ///
+    /// The account list is encrypted with ChaCha20-Poly1305. Changes stay in memory
+    /// until `save`, which replaces the file atomically.
+    pub struct AccountStore {
+        path: PathBuf,
+        key_kind: u8,
+        salt: [u8; SALT_LEN],
+        key: [u8; 32],
+        data: StoreData,
+    }
+
+    impl fmt::Debug for AccountStore {
+        /// Formats the store without its key, salt or decrypted accounts
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let key_kind = match self.key_kind {
+                0 => "passphrase",
+                _ => "key file",
+            };
+            f.debug_struct("AccountStore")
+                .field("path", &self.path)
+                .field("key_kind", &key_kind)
+                .field("accounts", &self.data.accounts.len())
+                .finish_non_exhaustive()
+        }
+    }
+
+    impl AccountStore {
+        /// Opens a store, or starts an empty one if the file does not exist
+        ///
+        /// A store in an older schema is migrated and written back in the current one.
+        ///
+        /// # Arguments
+        ///
+        /// * `path` - Path to the store file
+        /// * `key` - The passphrase or key file the store is encrypted with
+        ///
+        /// # Returns
+        ///
+        /// * `Result<AccountStore, AccountStoreError>` - The store or an error
+        pub fn open(path: &Path, key: &StoreKey) -> Result<Self, AccountStoreError> {
+            let bytes = match fs::read(path) {
+                Ok(bytes) => bytes,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    let salt = rand::random::<[u8; SALT_LEN]>();
+                    return Ok(AccountStore {
+                        path: path.to_path_buf(),
+                        key_kind: key.kind(),
+                        salt,
+                        key: key.derive(&salt)?,
+                        data: StoreData {
+                            schema_version: CURRENT_SCHEMA_VERSION,
+                            ..StoreData::default()
+                        },
+                    });
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            if bytes.len() < HEADER_LEN + NONCE_LEN || &bytes[..MAGIC.len()] != MAGIC {
+                return Err(AccountStoreError::Corrupted(
+                    "not an account store".to_string(),
+                ));
+            }
+            if bytes[MAGIC.len()] != FORMAT_VERSION {
+                return Err(AccountStoreError::Corrupted(format!(
+                    "unknown format {}",
+                    bytes[MAGIC.len()]
+                )));
+            }
+            if bytes[MAGIC.len() + 1] != key.kind() {
+                return Err(AccountStoreError::WrongKey);
+            }
+
+            let mut salt = [0u8; SALT_LEN];
+            salt.copy_from_slice(&bytes[MAGIC.len() + 2..HEADER_LEN]);
+            let derived = key.derive(&salt)?;
+            let nonce = &bytes[HEADER_LEN..HEADER_LEN + NONCE_LEN];
+            let plaintext = ChaCha20Poly1305::new(Key::from_slice(&derived))
+                .decrypt(
+                    Nonce::from_slice(nonce),
+                    Payload {
+                        msg: &bytes[HEADER_LEN + NONCE_LEN..],
+                        aad: &bytes[..HEADER_LEN],
+                    },
+                )
+                .map_err(|_| AccountStoreError::WrongKey)?;
+
+            let value: Value = serde_json::from_slice(&plaintext)
+                .map_err(|e| AccountStoreError::ParseError(e.to_string()))?;
+            let (data, migrated) = migrate(value)?;
+            let store = AccountStore {
+                path: path.to_path_buf(),
+                key_kind: key.kind(),
+                salt,
+                key: derived,
+                data,
+            };
+            if migrated {
+                store.save()?;
+            }
+            Ok(store)
+        }
+
+        /// Gets the path of the store file
+        pub fn path(&self) -> &Path {
+            &self.path
+        }
+
+        /// Gets every account in the order they were added
+        pub fn accounts(&self) -> &[StoredAccount] {
+            &self.data.accounts
+        }
+
+        /// Gets the accounts without their tokens
+        pub fn summaries(&self) -> Vec<AccountSummary> {
+            self.data
+                .accounts
+                .iter()
+                .map(|account| {
+                    let id = account.id();
+                    AccountSummary {
+                        is_default: self.data.default.as_deref() == Some(id.as_str()),
+                        id,
+                        username: account.username().to_string(),
+                        kind: account.kind(),
+                    }
+                })
+                .collect()
+        }
+
+        /// Gets an account by ID
+        ///
+        /// # Arguments
+        ///
+        /// * `id` - The account ID; dashes and case are ignored
+        pub fn get(&self, id: &str) -> Option<&StoredAccount> {
+            let id = normalize_id(id);
+            self.data.accounts.iter().find(|account| account.id() == id)
+        }
+
+        /// Adds an account, replacing one with the same ID
+        ///
+        /// The first account added becomes the default.
+        ///
+        /// # Arguments
+        ///
+        /// * `account` - The account to add
+        pub fn upsert(&mut self, account: StoredAccount) {
+            let id = account.id();
+            match self.data.accounts.iter_mut().find(|a| a.id() == id) {
+                Some(existing) => *existing = account,
+                None => self.data.accounts.push(account),
+            }
+            if self.data.default.is_none() {
+                self.data.default = Some(id);
+            }
+        }
+
+        /// Removes an account
+        ///
+        /// If it was the default, the first remaining account becomes the default.
+        ///
+        /// # Arguments
+        ///
+        /// * `id` - The account ID
+        pub fn remove(&mut self, id: &str) -> Option<StoredAccount> {
+            let id = normalize_id(id);
+            let index = self.data.accounts.iter().position(|a| a.id() == id)?;
+            let account = self.data.accounts.remove(index);
+            if self.data.default.as_deref() == Some(id.as_str()) {
+                self.data.default = self.data.accounts.first().map(StoredAccount::id);
+            }
+            Some(account)
+        }
+
+        /// Gets the default account
+        pub fn default_account(&self) -> Option<&StoredAccount> {
+            self.get(self.data.default.as_deref()?)
+        }
+
+        /// Selects the default account
+        ///
+        /// # Arguments
+        ///
+        /// * `id` - The account ID
+        pub fn set_default(&mut self, id: &str) -> Result<(), AccountStoreError> {
+            let account = self
+                .get(id)
+                .ok_or_else(|| AccountStoreError::NotFound(id.to_string()))?;
+            self.data.default = Some(account.id());
+            Ok(())
+        }
+
+        /// Re-encrypts the store with another passphrase or key file on the next save
+        ///
+        /// # Arguments
+        ///
+        /// * `key` - The new key
+        pub fn change_key(&mut self, key: &StoreKey) -> Result<(), AccountStoreError> {
+            let salt = rand::random::<[u8; SALT_LEN]>();
+            self.key = key.derive(&salt)?;
+            self.salt = salt;
+            self.key_kind = key.kind();
+            Ok(())
+        }
+
+        /// Adds the accounts of a plain JSON file, e.g. one written by an older launcher
+        ///
+        /// The file is left in place; delete it once the store has been saved.
+        ///
+        /// # Arguments
+        ///
+        /// * `path` - Path to a JSON array of accounts or an unencrypted account list
+        ///
+        /// # Returns
+        ///
+        /// * `Result<usize, AccountStoreError>` - The number of accounts imported
+        pub fn import_plain_json(&mut self, path: &Path) -> Result<usize, AccountStoreError> {
+            let text = fs::read_to_string(path)?;
+            let value: Value = serde_json::from_str(&text)
+                .map_err(|e| AccountStoreError::ParseError(e.to_string()))?;
+            let (data, _) = migrate(value)?;
+            let count = data.accounts.len();
+            for account in data.accounts {
+                self.upsert(account);
+            }
+            Ok(count)
+        }
+
+        /// Encrypts the store and atomically replaces the file
+        ///
+        /// The file is written next to the store and renamed over it, so a crash
+        /// leaves either the old or the new store.
+        pub fn save(&self) -> Result<(), AccountStoreError> {
+            let plaintext = serde_json::to_vec(&self.data)
+                .map_err(|e| AccountStoreError::ParseError(e.to_string()))?;
+
+            let mut header = Vec::with_capacity(HEADER_LEN);
+            header.extend_from_slice(MAGIC);
+            header.push(FORMAT_VERSION);
+            header.push(self.key_kind);
+            header.extend_from_slice(&self.salt);
+
+            let nonce = rand::random::<[u8; NONCE_LEN]>();
+            let ciphertext = ChaCha20Poly1305::new(Key::from_slice(&self.key))
+                .encrypt(
+                    Nonce::from_slice(&nonce),
+                    Payload {
+                        msg: &plaintext,
+                        aad: &header,
+                    },
+                )
+                .map_err(|_| AccountStoreError::Corrupted("encryption failed".to_string()))?;
+
+            let mut bytes = header;
+            bytes.extend_from_slice(&nonce);
+            bytes.extend_from_slice(&ciphertext);
+            file_utils::write_atomically(&self.path, &bytes)?;
+            Ok(())
+        }
+    }
+
+    /// Brings an account list up to the current schema
+    ///
+    /// # Returns
+    ///
+    /// * The list and whether it was migrated
+    pub(crate) fn migrate(mut value: Value) -> Result<(StoreData, bool), AccountStoreError> {
+        let mut migrated = false;
+        loop {
+            let version = if value.is_array() {
+                1
+            } else {
+                value["schema_version"].as_u64().ok_or_else(|| {
+                    AccountStoreError::ParseError("missing schema_version".to_string())
+                })?
+            };
+            match version {
+                1 => {
+                    let default = value
+                        .as_array()
+                        .and_then(|accounts| accounts.first())
+                        .and_then(|account| account["uuid"].as_str())
+                        .map(normalize_id);
+                    value = serde_json::json!({
+                        "schema_version": 2,
+                        "default": default,
+                        "accounts": value,
+                    });
+                    migrated = true;
+                }
+                CURRENT_SCHEMA_VERSION => {
+                    let data = serde_json::from_value(value)
+                        .map_err(|e| AccountStoreError::ParseError(e.to_string()))?;
+                    return Ok((data, migrated));
+                }
+                version => return Err(AccountStoreError::UnsupportedSchema(version)),
+            }
+        }
+    }
+
+    /// Normalizes an account ID: no dashes, lowercase
+    fn normalize_id(id: &str) -> String {
+        id.replace('-', "").to_lowercase()
+    }
+
+    /// Converts a C string argument; None if it is null or not UTF-8
+    unsafe fn c_str<'a>(value: *const libc::c_char) -> Option<&'a str> {
+        if value.is_null() {
+            return None;
+        }
+        unsafe { std::ffi::CStr::from_ptr(value) }.to_str().ok()
+    }
+
+    /// Hands a string to C through an output parameter
+    unsafe fn write_c_string(
+        value: String,
+        out_data: *mut *mut libc::c_char,
+        out_len: *mut libc::size_t,
+    ) -> libc::c_int {
+        if out_data.is_null() || out_len.is_null() {
+            return -1;
+        }
+        let len = value.len();
+        let Ok(value) = std::ffi::CString::new(value) else {
+            return -1;
+        };
+        unsafe {
+            *out_data = value.into_raw();
+            *out_len = len;
+        }
+        0
+    }
+
+    /**
+     * C language version of AccountStore::open with a passphrase.
+     *
+     * @param path Path to the store file
+     * @param passphrase The passphrase
+     * @return A store handle to free with account_store_free_c, or null on failure
+     *
+     * # Safety
+     *
+     * `path` and `passphrase` must be null or valid NUL-terminated strings.
+     */
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn account_store_open_c(
+        path: *const libc::c_char,
+        passphrase: *const libc::c_char,
+    ) -> *mut AccountStore {
+        let (Some(path), Some(passphrase)) = (unsafe { c_str(path) }, unsafe { c_str(passphrase) })
+        else {
+            return std::ptr::null_mut();
+        };
+        match AccountStore::open(
+            Path::new(path),
+            &StoreKey::Passphrase(passphrase.to_string()),
+        ) {
+            Ok(store) => Box::into_raw(Box::new(store)),
+            Err(_) => std::ptr::null_mut(),
+        }
+    }
+
+    /**
+     * C language version of AccountStore::open with a key file.
+     *
+     * @param path Path to the store file
+     * @param key_file Path to the key file
+     * @return A store handle to free with account_store_free_c, or null on failure
+     *
+     * # Safety
+     *
+     * `path` and `key_file` must be null or valid NUL-terminated strings.
+     */
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn account_store_open_with_key_file_c(
+        path: *const libc::c_char,
+        key_file: *const libc::c_char,
+    ) -> *mut AccountStore {
+        let (Some(path), Some(key_file)) = (unsafe { c_str(path) }, unsafe { c_str(key_file) })
+        else {
+            return std::ptr::null_mut();
+        };
+        match AccountStore::open(Path::new(path), &StoreKey::KeyFile(PathBuf::from(key_file))) {
+            Ok(store) => Box::into_raw(Box::new(store)),
+            Err(_) => std::ptr::null_mut(),
+        }
+    }
+
+    /**
+     * Frees a store handle without saving it.
+     *
+     * # Safety
+     *
+     * `store` must be null or a handle from an account_store_open function that has
+     * not been freed.
+     */
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn account_store_free_c(store: *mut AccountStore) {
+        if !store.is_null() {
+            drop(unsafe { Box::from_raw(store) });
+        }
+    }
+
+    /**
+     * Frees a string returned by an account_store function.
+     *
+     * # Safety
+     *
+     * `value` must be null or a string returned through an `out_data` parameter of
+     * this module that has not been freed.
+     */
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn account_store_string_free_c(value: *mut libc::c_char) {
+        if !value.is_null() {
+            drop(unsafe { std::ffi::CString::from_raw(value) });
+        }
+    }
+
+    /**
+     * Lists the accounts as a JSON array of summaries, without any tokens.
+     *
+     * @param store The store handle
+     * @param out_data Receives the JSON; free it with account_store_string_free_c
+     * @param out_len Receives the length of the JSON
+     * @return 0 on success, -1 on failure
+     *
+     * # Safety
+     *
+     * `store` must be a live handle and the output pointers must be writable.
+     */
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn account_store_list_c(
+        store: *const AccountStore,
+        out_data: *mut *mut libc::c_char,
+        out_len: *mut libc::size_t,
+    ) -> libc::c_int {
+        let Some(store) = (unsafe { store.as_ref() }) else {
+            return -1;
+        };
+        match serde_json::to_string(&store.summaries()) {
+            Ok(json) => unsafe { write_c_string(json, out_data, out_len) },
+            Err(_) => -1,
+        }
+    }
+
+    /**
+     * Exports one account as JSON, including its tokens.
+     *
+     * Only call this when the secrets are meant to leave the store, e.g. to launch
+     * the game from another process.
+     *
+     * @param store The store handle
+     * @param id The account ID
+     * @param out_data Receives the JSON; free it with account_store_string_free_c
+     * @param out_len Receives the length of the JSON
+     * @return 0 on success, -1 on failure
+     *
+     * # Safety
+     *
+     * `store` must be a live handle, `id` a valid NUL-terminated string and the
+     * output pointers writable.
+     */
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn account_store_export_account_c(
+        store: *const AccountStore,
+        id: *const libc::c_char,
+        out_data: *mut *mut libc::c_char,
+        out_len: *mut libc::size_t,
+    ) -> libc::c_int {
+        let (Some(store), Some(id)) = (unsafe { store.as_ref() }, unsafe { c_str(id) }) else {
+            return -1;
+        };
+        let Some(account) = store.get(id) else {
+            return -1;
+        };
+        match serde_json::to_string(account) {
+            Ok(json) => unsafe { write_c_string(json, out_data, out_len) },
+            Err(_) => -1,
+        }
+    }
+
+    /**
+     * Adds an offline account.
+     *
+     * @param store The store handle
+     * @param username The player name
+     * @return 0 on success, -1 on failure (including an invalid name)
+     *
+     * # Safety
+     *
+     * `store` must be a live handle and `username` a valid NUL-terminated string.
+     */
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn account_store_add_offline_c(
+        store: *mut AccountStore,
+        username: *const libc::c_char,
+    ) -> libc::c_int {
+        let (Some(store), Some(username)) = (unsafe { store.as_mut() }, unsafe { c_str(username) })
+        else {
+            return -1;
+        };
+        match OfflineAccount::new(username) {
+            Ok(account) => {
+                store.upsert(StoredAccount::Offline(account));
+                0
+            }
+            Err(_) => -1,
+        }
+    }
+
+    /**
+     * Selects the default account.
+     *
+     * @param store The store handle
+     * @param id The account ID
+     * @return 0 on success, -1 on failure
+     *
+     * # Safety
+     *
+     * `store` must be a live handle and `id` a valid NUL-terminated string.
+     */
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn account_store_set_default_c(
+        store: *mut AccountStore,
+        id: *const libc::c_char,
+    ) -> libc::c_int {
+        let (Some(store), Some(id)) = (unsafe { store.as_mut() }, unsafe { c_str(id) }) else {
+            return -1;
+        };
+        match store.set_default(id) {
+            Ok(()) => 0,
+            Err(_) => -1,
+        }
+    }
+
+    /**
+     * Removes an account.
+     *
+     * @param store The store handle
+     * @param id The account ID
+     * @return 0 on success, -1 if there is no such account
+     *
+     * # Safety
+     *
+     * `store` must be a live handle and `id` a valid NUL-terminated string.
+     */
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn account_store_remove_c(
+        store: *mut AccountStore,
+        id: *const libc::c_char,
+    ) -> libc::c_int {
+        let (Some(store), Some(id)) = (unsafe { store.as_mut() }, unsafe { c_str(id) }) else {
+            return -1;
+        };
+        match store.remove(id) {
+            Some(_) => 0,
+            None => -1,
+        }
+    }
+
+    /**
+     * Encrypts the store and atomically replaces its file.
+     *
+     * @param store The store handle
+     * @return 0 on success, -1 on failure
+     *
+     * # Safety
+     *
+     * `store` must be a live handle.
+     */
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn account_store_save_c(store: *const AccountStore) -> libc::c_int {
+        let Some(store) = (unsafe { store.as_ref() }) else {
+            return -1;
+        };
+        match store.save() {
+            Ok(()) => 0,
+            Err(_) => -1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::account_store::{AccountStoreError, CURRENT_SCHEMA_VERSION, migrate};
+    use serde_json::json;
+
+    /// Tests migrating a v1 account array to the current schema
+    #[test]
+    fn test_migrate() {
+        let (data, migrated) = migrate(json!([
+            {"type": "offline", "username": "Steve", "uuid": "5627DD98-E6BE-3C21-B8A8-E92344183641"}
+        ]))
+        .unwrap();
+        assert!(migrated);
+        assert_eq!(data.schema_version, CURRENT_SCHEMA_VERSION);
+        assert_eq!(
+            data.default.as_deref(),
+            Some("5627dd98e6be3c21b8a8e92344183641")
+        );
+        assert_eq!(data.accounts.len(), 1);
+
+        let (_, migrated) =
+            migrate(json!({"schema_version": 2, "default": null, "accounts": []})).unwrap();
+        assert!(!migrated);
+        assert!(matches!(
+            migrate(json!({"accounts": []})),
+            Err(AccountStoreError::ParseError(_))
+        ));
+    }
+}
@@ -0,0 +1,698 @@
+pub mod microsoft_account {
+    use crate::craft_launcher::core::engine::engine::Account;
+    use reqwest::blocking::{Client, RequestBuilder};
+    use serde::de::DeserializeOwned;
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+    use std::error::Error;
+    use std::fmt;
+    use std::thread;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    /// OAuth scope that grants Xbox Live sign-in and a refresh token
+    pub const MICROSOFT_SCOPE: &str = "XboxLive.signin offline_access";
+
+    /// XSTS error: the Microsoft account has no Xbox profile
+    pub const XERR_NO_XBOX_PROFILE: u64 = 2148916233;
+
+    /// XSTS error: Xbox Live is not available in the account's country
+    pub const XERR_REGION_BLOCKED: u64 = 2148916235;
+
+    /// XSTS errors: the account needs adult verification (South Korea)
+    pub const XERR_ADULT_VERIFICATION: [u64; 2] = [2148916236, 2148916237];
+
+    /// XSTS error: the account is a child account outside a family group
+    pub const XERR_CHILD_ACCOUNT: u64 = 2148916238;
+
+    /// Seconds added to the polling interval when the server answers `slow_down` (RFC 8628)
+    pub const SLOW_DOWN_INCREMENT: u64 = 5;
+
+    /// URLs of every service the sign-in talks to
+    ///
+    /// Point them at a local server to exercise the flow in tests.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct MicrosoftEndpoints {
+        /// OAuth device authorization endpoint
+        pub device_code_url: String,
+
+        /// OAuth token endpoint, used for polling and refreshing
+        pub token_url: String,
+
+        /// Xbox Live user authentication
+        pub xbox_live_url: String,
+
+        /// Xbox Live XSTS authorization
+        pub xsts_url: String,
+
+        /// Minecraft services login with an Xbox identity token
+        pub minecraft_login_url: String,
+
+        /// Minecraft services entitlements of the player
+        pub entitlements_url: String,
+
+        /// Minecraft services profile of the player
+        pub profile_url: String,
+    }
+
+    impl Default for MicrosoftEndpoints {
+        fn default() -> Self {
+            MicrosoftEndpoints {
+                device_code_url:
+                    "https://login.microsoftonline.com/consumers/oauth2/v2.0/devicecode".to_string(),
+                token_url: "https://login.microsoftonline.com/consumers/oauth2/v2.0/token"
+                    .to_string(),
+                xbox_live_url: "https://user.auth.xboxlive.com/user/authenticate".to_string(),
+                xsts_url: "https://xsts.auth.xboxlive.com/xsts/authorize".to_string(),
+                minecraft_login_url:
+                    "https://api.minecraftservices.com/authentication/login_with_xbox".to_string(),
+                entitlements_url: "https://api.minecraftservices.com/entitlements/mcstore"
+                    .to_string(),
+                profile_url: "https://api.minecraftservices.com/minecraft/profile".to_string(),
+            }
+        }
+    }
+
+    /// Error type for Microsoft sign-in
+    #[derive(Debug)]
+    pub enum MicrosoftAuthError {
+        /// A request could not be sent or its response not read
+        NetworkingError(String),
+
+        /// A service answered with an unexpected status
+        UnexpectedStatus {
+            url: String,
+            status: u16,
+            body: String,
+        },
+
+        /// A response could not be parsed
+        ParseError(String),
+
+        /// The user declined the sign-in
+        AuthorizationDeclined,
+
+        /// The device code expired before the user signed in
+        DeviceCodeExpired,
+
+        /// The refresh token is invalid or expired; the user has to sign in again
+        InvalidGrant(String),
+
+        /// The Microsoft account has no Xbox profile yet
+        NoXboxProfile,
+
+        /// Xbox Live is not available in the account's country
+        XboxRegionBlocked,
+
+        /// The account needs adult verification on the Xbox website
+        AdultVerificationRequired,
+
+        /// The account is a child account that must be added to a family
+        ChildAccount,
+
+        /// XSTS refused with another error code
+        XboxError(u64),
+
+        /// The account does not own Minecraft: Java Edition
+        NotOwned,
+
+        /// The account owns the game but has not created a profile (player name) yet
+        NoProfile,
+    }
+
+    impl fmt::Display for MicrosoftAuthError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                MicrosoftAuthError::NetworkingError(e) => {
+                    write!(f, "Sign-in request failed: {}", e)
+                }
+                MicrosoftAuthError::UnexpectedStatus { url, status, body } => {
+                    write!(f, "{} answered with HTTP {}: {}", url, status, body)
+                }
+                MicrosoftAuthError::ParseError(e) => {
+                    write!(f, "Failed to parse sign-in response: {}", e)
+                }
+                MicrosoftAuthError::AuthorizationDeclined => write!(f, "The sign-in was declined"),
+                MicrosoftAuthError::DeviceCodeExpired => {
+                    write!(f, "The sign-in code expired before it was used")
+                }
+                MicrosoftAuthError::InvalidGrant(e) => {
+                    write!(f, "The session expired, please sign in again: {}", e)
+                }
+                MicrosoftAuthError::NoXboxProfile => write!(
+                    f,
+                    "This Microsoft account has no Xbox profile; create one at xbox.com"
+                ),
+                MicrosoftAuthError::XboxRegionBlocked => {
+                    write!(f, "Xbox Live is not available in this account's country")
+                }
+                MicrosoftAuthError::AdultVerificationRequired => {
+                    write!(f, "This account needs adult verification on xbox.com")
+                }
+                MicrosoftAuthError::ChildAccount => write!(
+                    f,
+                    "This is a child account; an adult has to add it to a Microsoft family"
+                ),
+                MicrosoftAuthError::XboxError(code) => {
+                    write!(f, "Xbox Live refused the sign-in with error {}", code)
+                }
+                MicrosoftAuthError::NotOwned => {
+                    write!(f, "This account does not own Minecraft: Java Edition")
+                }
+                MicrosoftAuthError::NoProfile => write!(
+                    f,
+                    "This account has no Minecraft profile yet; choose a name at minecraft.net"
+                ),
+            }
+        }
+    }
+
+    impl Error for MicrosoftAuthError {}
+
+    impl From<reqwest::Error> for MicrosoftAuthError {
+        fn from(error: reqwest::Error) -> Self {
+            MicrosoftAuthError::NetworkingError(error.to_string())
+        }
+    }
+
+    /// A code the user enters at the verification page to sign in
+    #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+    pub struct DeviceCode {
+        /// Code the launcher polls the token endpoint with
+        pub device_code: String,
+
+        /// Code the user enters
+        pub user_code: String,
+
+        /// Page the user enters the code at
+        pub verification_uri: String,
+
+        /// Seconds until the code expires
+        pub expires_in: u64,
+
+        /// Seconds to wait between polls
+        #[serde(default = "default_interval")]
+        pub interval: u64,
+
+        /// Instructions to show to the user
+        #[serde(default)]
+        pub message: String,
+    }
+
+    fn default_interval() -> u64 {
+        5
+    }
+
+    /// Microsoft OAuth tokens
+    #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+    pub struct MicrosoftToken {
+        /// Token for Xbox Live
+        pub access_token: String,
+
+        /// Token to sign in again without the user
+        #[serde(default)]
+        pub refresh_token: Option<String>,
+
+        /// Seconds until the access token expires
+        #[serde(default)]
+        pub expires_in: u64,
+    }
+
+    /// State of a device code sign-in after one poll
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum DeviceCodePoll {
+        /// The user has not entered the code yet
+        Pending,
+
+        /// The user has not entered the code yet and polling has to slow down
+        SlowDown,
+
+        /// The user signed in
+        Complete(MicrosoftToken),
+    }
+
+    /// A Microsoft account signed in to Minecraft
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct MicrosoftAccount {
+        /// The player name
+        pub username: String,
+
+        /// The player UUID without dashes
+        pub uuid: String,
+
+        /// Minecraft services access token
+        pub access_token: String,
+
+        /// When the access token expires, in seconds since the Unix epoch
+        pub expires_at: u64,
+
+        /// Microsoft refresh token
+        pub refresh_token: Option<String>,
+
+        /// The Xbox user ID
+        pub xuid: Option<String>,
+
+        /// The OAuth client ID the account signed in with
+        pub client_id: String,
+    }
+
+    impl MicrosoftAccount {
+        /// Checks whether the access token has expired, or expires within a minute
+        pub fn is_expired(&self) -> bool {
+            unix_time() + 60 >= self.expires_at
+        }
+
+        /// Gets the launch values of the account
+        pub fn to_account(&self) -> Account {
+            let mut account = Account::new(&self.username, &self.uuid, &self.access_token, "msa");
+            account.xuid = self.xuid.clone();
+            account.client_id = Some(self.client_id.clone());
+            account
+        }
+    }
+
+    /// An Xbox Live or XSTS token with the user hash
+    #[derive(Debug)]
+    struct XboxToken {
+        token: String,
+        user_hash: String,
+        xuid: Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    struct XboxResponse {
+        #[serde(rename = "Token")]
+        token: String,
+        #[serde(rename = "DisplayClaims")]
+        display_claims: XboxDisplayClaims,
+    }
+
+    #[derive(Deserialize)]
+    struct XboxDisplayClaims {
+        xui: Vec<XboxUserInfo>,
+    }
+
+    #[derive(Deserialize)]
+    struct XboxUserInfo {
+        uhs: String,
+        #[serde(default)]
+        xid: Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    struct MinecraftLoginResponse {
+        access_token: String,
+        expires_in: u64,
+    }
+
+    #[derive(Deserialize)]
+    struct EntitlementsResponse {
+        #[serde(default)]
+        items: Vec<EntitlementItem>,
+    }
+
+    #[derive(Deserialize)]
+    struct EntitlementItem {
+        name: String,
+    }
+
+    #[derive(Deserialize)]
+    struct ProfileResponse {
+        id: String,
+        name: String,
+    }
+
+    #[derive(Deserialize)]
+    struct OAuthError {
+        error: String,
+        #[serde(default)]
+        error_description: String,
+    }
+
+    /// Signs players in with the Microsoft device code flow
+    ///
+    /// The chain is: device code → Microsoft token → Xbox Live → XSTS → Minecraft
+    /// services login → entitlements → profile.
+    #[derive(Debug, Clone)]
+    pub struct MicrosoftAuthenticator {
+        client_id: String,
+        endpoints: MicrosoftEndpoints,
+        client: Client,
+    }
+
+    impl MicrosoftAuthenticator {
+        /// Creates an authenticator for an Azure application.
+        ///
+        /// # Arguments
+        ///
+        /// * `client_id` - The application (client) ID registered for Minecraft sign-in
+        pub fn new(client_id: &str) -> Self {
+            MicrosoftAuthenticator {
+                client_id: client_id.to_string(),
+                endpoints: MicrosoftEndpoints::default(),
+                client: Client::new(),
+            }
+        }
+
+        /// Sets the URLs of the services.
+        ///
+        /// # Arguments
+        ///
+        /// * `endpoints` - The service URLs
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_endpoints(mut self, endpoints: MicrosoftEndpoints) -> Self {
+            self.endpoints = endpoints;
+            self
+        }
+
+        /// Requests a code for the user to enter at the verification page
+        pub fn request_device_code(&self) -> Result<DeviceCode, MicrosoftAuthError> {
+            let request = self.client.post(&self.endpoints.device_code_url).form(&[
+                ("client_id", self.client_id.as_str()),
+                ("scope", MICROSOFT_SCOPE),
+            ]);
+            send_json(request, &self.endpoints.device_code_url)
+        }
+
+        /// Checks once whether the user has entered the code
+        ///
+        /// # Arguments
+        ///
+        /// * `code` - The device code
+        ///
+        /// # Returns
+        ///
+        /// * `Result<DeviceCodePoll, MicrosoftAuthError>` - The tokens, whether to keep
+        ///   polling, or an error
+        pub fn poll_device_code(
+            &self,
+            code: &DeviceCode,
+        ) -> Result<DeviceCodePoll, MicrosoftAuthError> {
+            let request = self.client.post(&self.endpoints.token_url).form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+                ("client_id", self.client_id.as_str()),
+                ("device_code", code.device_code.as_str()),
+            ]);
+            self.send_token_request(request)
+        }
+
+        /// Polls until the user has entered the code or it expires
+        ///
+        /// The interval of the code grows by `SLOW_DOWN_INCREMENT` seconds every time
+        /// the server asks to slow down.
+        ///
+        /// # Arguments
+        ///
+        /// * `code` - The device code
+        pub fn wait_for_device_code(
+            &self,
+            code: &DeviceCode,
+        ) -> Result<MicrosoftToken, MicrosoftAuthError> {
+            let deadline = unix_time() + code.expires_in;
+            let mut interval = code.interval;
+            loop {
+                match self.poll_device_code(code)? {
+                    DeviceCodePoll::Complete(token) => return Ok(token),
+                    DeviceCodePoll::SlowDown => interval += SLOW_DOWN_INCREMENT,
+                    DeviceCodePoll::Pending => {}
+                }
+                if unix_time() >= deadline {
+                    return Err(MicrosoftAuthError::DeviceCodeExpired);
+                }
+                thread::sleep(Duration::from_secs(interval));
+            }
+        }
+
+        /// Gets new Microsoft tokens with a refresh token
+        ///
+        /// # Arguments
+        ///
+        /// * `refresh_token` - The refresh token of an earlier sign-in
+        pub fn refresh_token(
+            &self,
+            refresh_token: &str,
+        ) -> Result<MicrosoftToken, MicrosoftAuthError> {
+            let request = self.client.post(&self.endpoints.token_url).form(&[
+                ("grant_type", "refresh_token"),
+                ("client_id", self.client_id.as_str()),
+                ("refresh_token", refresh_token),
+                ("scope", MICROSOFT_SCOPE),
+            ]);
+            self.token_request(request)
+        }
+
+        /// Signs in to Minecraft with Microsoft tokens
+        ///
+        /// # Arguments
+        ///
+        /// * `token` - The Microsoft tokens
+        ///
+        /// # Returns
+        ///
+        /// * `Result<MicrosoftAccount, MicrosoftAuthError>` - The signed-in account or an error
+        pub fn login(
+            &self,
+            token: &MicrosoftToken,
+        ) -> Result<MicrosoftAccount, MicrosoftAuthError> {
+            let xbox = self.xbox_live(&token.access_token)?;
+            let xsts = self.xsts(&xbox.token)?;
+
+            let identity_token = format!("XBL3.0 x={};{}", xsts.user_hash, xsts.token);
+            let request = self
+                .client
+                .post(&self.endpoints.minecraft_login_url)
+                .header("Content-Type", "application/json")
+                .body(json!({ "identityToken": identity_token }).to_string());
+            let login: MinecraftLoginResponse =
+                send_json(request, &self.endpoints.minecraft_login_url)?;
+
+            let entitlements: EntitlementsResponse = send_json(
+                self.client
+                    .get(&self.endpoints.entitlements_url)
+                    .bearer_auth(&login.access_token),
+                &self.endpoints.entitlements_url,
+            )?;
+            let owns_game = entitlements
+                .items
+                .iter()
+                .any(|item| item.name == "product_minecraft" || item.name == "game_minecraft");
+            if !owns_game {
+                return Err(MicrosoftAuthError::NotOwned);
+            }
+
+            let request = self
+                .client
+                .get(&self.endpoints.profile_url)
+                .bearer_auth(&login.access_token);
+            let profile: ProfileResponse = match send_json(request, &self.endpoints.profile_url) {
+                Err(MicrosoftAuthError::UnexpectedStatus { status: 404, .. }) => {
+                    return Err(MicrosoftAuthError::NoProfile);
+                }
+                result => result?,
+            };
+
+            Ok(MicrosoftAccount {
+                username: profile.name,
+                uuid: profile.id.replace('-', ""),
+                access_token: login.access_token,
+                expires_at: unix_time() + login.expires_in,
+                refresh_token: token.refresh_token.clone(),
+                xuid: xsts.xuid.or(xbox.xuid),
+                client_id: self.client_id.clone(),
+            })
+        }
+
+        /// Runs the whole device code flow
+        ///
+        /// # Arguments
+        ///
+        /// * `show_code` - Called with the code to show to the user before polling
+        pub fn authenticate(
+            &self,
+            show_code: impl FnOnce(&DeviceCode),
+        ) -> Result<MicrosoftAccount, MicrosoftAuthError> {
+            let code = self.request_device_code()?;
+            show_code(&code);
+            let token = self.wait_for_device_code(&code)?;
+            self.login(&token)
+        }
+
+        /// Signs an account in again with its refresh token
+        ///
+        /// # Arguments
+        ///
+        /// * `account` - The account to refresh
+        pub fn refresh(
+            &self,
+            account: &MicrosoftAccount,
+        ) -> Result<MicrosoftAccount, MicrosoftAuthError> {
+            let refresh_token = account.refresh_token.as_deref().ok_or_else(|| {
+                MicrosoftAuthError::InvalidGrant("the account has no refresh token".to_string())
+            })?;
+            let mut token = self.refresh_token(refresh_token)?;
+            // Microsoft does not always rotate the refresh token
+            if token.refresh_token.is_none() {
+                token.refresh_token = Some(refresh_token.to_string());
+            }
+            self.login(&token)
+        }
+
+        /// Sends a token request that is not part of a device code sign-in
+        fn token_request(
+            &self,
+            request: RequestBuilder,
+        ) -> Result<MicrosoftToken, MicrosoftAuthError> {
+            match self.send_token_request(request)? {
+                DeviceCodePoll::Complete(token) => Ok(token),
+                state => Err(MicrosoftAuthError::ParseError(format!(
+                    "unexpected {:?} answer to a token request",
+                    state
+                ))),
+            }
+        }
+
+        /// Sends a token request and maps OAuth errors
+        ///
+        /// The device code states `authorization_pending` and `slow_down` are returned
+        /// as `Pending` and `SlowDown`.
+        fn send_token_request(
+            &self,
+            request: RequestBuilder,
+        ) -> Result<DeviceCodePoll, MicrosoftAuthError> {
+            let response = request.send()?;
+            let status = response.status();
+            let body = response.text()?;
+            if status.is_success() {
+                return serde_json::from_str(&body)
+                    .map(DeviceCodePoll::Complete)
+                    .map_err(|e| MicrosoftAuthError::ParseError(e.to_string()));
+            }
+
+            match serde_json::from_str::<OAuthError>(&body) {
+                Ok(error) => match error.error.as_str() {
+                    "authorization_pending" => Ok(DeviceCodePoll::Pending),
+                    "slow_down" => Ok(DeviceCodePoll::SlowDown),
+                    "authorization_declined" | "access_denied" => {
+                        Err(MicrosoftAuthError::AuthorizationDeclined)
+                    }
+                    "expired_token" => Err(MicrosoftAuthError::DeviceCodeExpired),
+                    _ => Err(MicrosoftAuthError::InvalidGrant(format!(
+                        "{}: {}",
+                        error.error, error.error_description
+                    ))),
+                },
+                Err(_) => Err(MicrosoftAuthError::UnexpectedStatus {
+                    url: self.endpoints.token_url.clone(),
+                    status: status.as_u16(),
+                    body,
+                }),
+            }
+        }
+
+        /// Authenticates with Xbox Live
+        fn xbox_live(&self, access_token: &str) -> Result<XboxToken, MicrosoftAuthError> {
+            let body = json!({
+                "Properties": {
+                    "AuthMethod": "RPS",
+                    "SiteName": "user.auth.xboxlive.com",
+                    "RpsTicket": format!("d={}", access_token)
+                },
+                "RelyingParty": "http://auth.xboxlive.com",
+                "TokenType": "JWT"
+            });
+            let request = self
+                .client
+                .post(&self.endpoints.xbox_live_url)
+                .header("Content-Type", "application/json")
+                .header("Accept", "application/json")
+                .body(body.to_string());
+            let response: XboxResponse = send_json(request, &self.endpoints.xbox_live_url)?;
+            parse_xbox_token(response)
+        }
+
+        /// Authorizes the Xbox Live token for Minecraft services
+        fn xsts(&self, xbox_token: &str) -> Result<XboxToken, MicrosoftAuthError> {
+            let body = json!({
+                "Properties": {
+                    "SandboxId": "RETAIL",
+                    "UserTokens": [xbox_token]
+                },
+                "RelyingParty": "rp://api.minecraftservices.com/",
+                "TokenType": "JWT"
+            });
+            let request = self
+                .client
+                .post(&self.endpoints.xsts_url)
+                .header("Content-Type", "application/json")
+                .header("Accept", "application/json")
+                .body(body.to_string());
+
+            match send_json::<XboxResponse>(request, &self.endpoints.xsts_url) {
+                Ok(response) => parse_xbox_token(response),
+                Err(MicrosoftAuthError::UnexpectedStatus {
+                    status: 401, body, ..
+                }) => {
+                    let code = serde_json::from_str::<serde_json::Value>(&body)
+                        .ok()
+                        .and_then(|error| error["XErr"].as_u64())
+                        .unwrap_or(0);
+                    Err(match code {
+                        XERR_NO_XBOX_PROFILE => MicrosoftAuthError::NoXboxProfile,
+                        XERR_REGION_BLOCKED => MicrosoftAuthError::XboxRegionBlocked,
+                        XERR_CHILD_ACCOUNT => MicrosoftAuthError::ChildAccount,
+                        code if XERR_ADULT_VERIFICATION.contains(&code) => {
+                            MicrosoftAuthError::AdultVerificationRequired
+                        }
+                        code => MicrosoftAuthError::XboxError(code),
+                    })
+                }
+                Err(e) => Err(e),
+            }
+        }
+    }
+
+    /// Takes the token and the first user's hash out of an Xbox response
+    fn parse_xbox_token(response: XboxResponse) -> Result<XboxToken, MicrosoftAuthError> {
+        let user = response
+            .display_claims
+            .xui
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                MicrosoftAuthError::ParseError("Xbox response has no user claims".to_string())
+            })?;
+        Ok(XboxToken {
+            token: response.token,
+            user_hash: user.uhs,
+            xuid: user.xid,
+        })
+    }
+
+    /// Sends a request and parses a successful JSON response
+    fn send_json<T: DeserializeOwned>(
+        request: RequestBuilder,
+        url: &str,
+    ) -> Result<T, MicrosoftAuthError> {
+        let response = request.send()?;
+        let status = response.status();
+        let body = response.text()?;
+        if !status.is_success() {
+            return Err(MicrosoftAuthError::UnexpectedStatus {
+                url: url.to_string(),
+                status: status.as_u16(),
+                body,
+            });
+        }
+        serde_json::from_str(&body).map_err(|e| MicrosoftAuthError::ParseError(e.to_string()))
+    }
+
+    /// Gets the current time in seconds since the Unix epoch
+    fn unix_time() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0)
+    }
+}
@@ -0,0 +1,11 @@
+/// Offline accounts with vanilla's name-based UUIDs
+pub mod offline_account;
+
+/// Microsoft device code sign-in through Xbox Live to Minecraft services
+pub mod microsoft_account;
+
+/// Encrypted on-disk store of accounts with a default account
+pub mod account_store;
+
+/// Accounts of Yggdrasil-compatible authentication servers, loaded through authlib-injector
+pub mod yggdrasil_account;
@@ -0,0 +1,213 @@
+pub mod offline_account {
+    use crate::craft_launcher::core::engine::engine::Account;
+    use md5::{Digest, Md5};
+    use serde::{Deserialize, Serialize};
+    use std::error::Error;
+    use std::fmt;
+
+    /// Shortest player name the game accepts
+    pub const MIN_USERNAME_LENGTH: usize = 3;
+
+    /// Longest player name the game accepts
+    pub const MAX_USERNAME_LENGTH: usize = 16;
+
+    /// `${user_type}` of offline accounts
+    pub const OFFLINE_USER_TYPE: &str = "legacy";
+
+    /// Error type for invalid player names
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum UsernameError {
+        /// The name is shorter than 3 characters
+        TooShort(usize),
+
+        /// The name is longer than 16 characters
+        TooLong(usize),
+
+        /// The name contains a character other than letters, digits and `_`
+        InvalidCharacter(char),
+    }
+
+    impl fmt::Display for UsernameError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                UsernameError::TooShort(length) => write!(
+                    f,
+                    "Username is {} characters long, at least {} are required",
+                    length, MIN_USERNAME_LENGTH
+                ),
+                UsernameError::TooLong(length) => write!(
+                    f,
+                    "Username is {} characters long, at most {} are allowed",
+                    length, MAX_USERNAME_LENGTH
+                ),
+                UsernameError::InvalidCharacter(c) => {
+                    write!(f, "Username contains the invalid character {:?}", c)
+                }
+            }
+        }
+    }
+
+    impl Error for UsernameError {}
+
+    /// Checks that a player name is 3 to 16 letters, digits or underscores
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The player name
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), UsernameError>` - Ok if the game accepts the name
+    pub fn validate_username(username: &str) -> Result<(), UsernameError> {
+        if let Some(c) = username
+            .chars()
+            .find(|c| !c.is_ascii_alphanumeric() && *c != '_')
+        {
+            return Err(UsernameError::InvalidCharacter(c));
+        }
+        let length = username.len();
+        if length < MIN_USERNAME_LENGTH {
+            return Err(UsernameError::TooShort(length));
+        }
+        if length > MAX_USERNAME_LENGTH {
+            return Err(UsernameError::TooLong(length));
+        }
+        Ok(())
+    }
+
+    /// Computes the UUID vanilla gives a player in offline mode
+    ///
+    /// This is Java's `UUID.nameUUIDFromBytes("OfflinePlayer:<name>")`: the MD5 hash
+    /// of the name with the version set to 3 and the IETF variant.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The player name; it is case-sensitive
+    ///
+    /// # Returns
+    ///
+    /// * The UUID with dashes (e.g. "5627dd98-e6be-3c21-b8a8-e92344183641" for Steve)
+    pub fn offline_uuid(username: &str) -> String {
+        let mut hasher = Md5::new();
+        hasher.update(format!("OfflinePlayer:{}", username).as_bytes());
+        let mut bytes: [u8; 16] = hasher.finalize().into();
+        bytes[6] = (bytes[6] & 0x0f) | 0x30;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+        let hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+        format!(
+            "{}-{}-{}-{}-{}",
+            &hex[0..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..32]
+        )
+    }
+
+    /// An account that plays without authentication
+    ///
+    /// Only servers in offline mode and singleplayer accept it.
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct OfflineAccount {
+        /// The player name
+        pub username: String,
+
+        /// The player UUID with dashes
+        pub uuid: String,
+    }
+
+    impl OfflineAccount {
+        /// Creates an offline account with the UUID vanilla would give the name
+        ///
+        /// # Arguments
+        ///
+        /// * `username` - The player name
+        ///
+        /// # Returns
+        ///
+        /// * `Result<OfflineAccount, UsernameError>` - The account, or an error if the
+        ///   game would not accept the name
+        pub fn new(username: &str) -> Result<Self, UsernameError> {
+            validate_username(username)?;
+            Ok(OfflineAccount {
+                username: username.to_string(),
+                uuid: offline_uuid(username),
+            })
+        }
+
+        /// Sets a UUID other than the derived one, e.g. to keep the inventory of an
+        /// account whose name changed.
+        ///
+        /// # Arguments
+        ///
+        /// * `uuid` - The player UUID, with or without dashes
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_uuid(mut self, uuid: &str) -> Self {
+            self.uuid = uuid.to_string();
+            self
+        }
+
+        /// Gets the launch values of the account
+        ///
+        /// The access token is the UUID, since the game only requires it to be set.
+        /// `${user_properties}` is `{}`, which legacy versions need to be valid JSON.
+        pub fn to_account(&self) -> Account {
+            let uuid = self.uuid.replace('-', "");
+            Account::new(&self.username, &uuid, &uuid, OFFLINE_USER_TYPE)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::offline_account::{OfflineAccount, UsernameError, offline_uuid, validate_username};
+
+    /// Tests that UUIDs match the ones vanilla derives
+    #[test]
+    fn test_offline_uuid() {
+        assert_eq!(
+            offline_uuid("Notch"),
+            "b50ad385-829d-3141-a216-7e7d7539ba7f"
+        );
+        assert_eq!(
+            offline_uuid("Steve"),
+            "5627dd98-e6be-3c21-b8a8-e92344183641"
+        );
+        assert_ne!(offline_uuid("steve"), offline_uuid("Steve"));
+    }
+
+    /// Tests username validation
+    #[test]
+    fn test_validate_username() {
+        assert_eq!(validate_username("Steve_01"), Ok(()));
+        assert_eq!(validate_username("ab"), Err(UsernameError::TooShort(2)));
+        assert_eq!(
+            validate_username("a_very_long_name_1"),
+            Err(UsernameError::TooLong(18))
+        );
+        assert_eq!(
+            validate_username("Steve Jr"),
+            Err(UsernameError::InvalidCharacter(' '))
+        );
+        assert_eq!(
+            validate_username("Stéve"),
+            Err(UsernameError::InvalidCharacter('é'))
+        );
+        assert!(OfflineAccount::new("").is_err());
+    }
+
+    /// Tests the launch values of an offline account
+    #[test]
+    fn test_to_account() {
+        let account = OfflineAccount::new("Steve").unwrap().to_account();
+        assert_eq!(account.username, "Steve");
+        assert_eq!(account.uuid, "5627dd98e6be3c21b8a8e92344183641");
+        assert_eq!(account.access_token, account.uuid);
+        assert_eq!(account.user_type, "legacy");
+        assert_eq!(account.user_properties, "{}");
+    }
+}
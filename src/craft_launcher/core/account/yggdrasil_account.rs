@@ -0,0 +1,479 @@
+pub mod yggdrasil_account {
+    use crate::craft_launcher::core::engine::engine::Account;
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use reqwest::StatusCode;
+    use reqwest::blocking::Client;
+    use serde::{Deserialize, Serialize};
+    use serde_json::{Value, json};
+    use std::error::Error;
+    use std::fmt;
+    use std::path::{Path, PathBuf};
+
+    /// `${user_type}` authlib-injector expects
+    pub const YGGDRASIL_USER_TYPE: &str = "mojang";
+
+    /// Error type for Yggdrasil authentication
+    #[derive(Debug)]
+    pub enum YggdrasilError {
+        /// A request could not be sent or its response not read
+        NetworkingError(String),
+
+        /// The server answered with an unexpected status
+        UnexpectedStatus {
+            url: String,
+            status: u16,
+            body: String,
+        },
+
+        /// A response could not be parsed
+        ParseError(String),
+
+        /// The credentials or the token were rejected, with the server's message
+        Forbidden(String),
+
+        /// The user has no profile on the server
+        NoProfile,
+
+        /// The user has several profiles and none was chosen; holds their names
+        ProfileSelectionRequired(Vec<String>),
+
+        /// The user has no profile with this name
+        ProfileNotFound(String),
+    }
+
+    impl fmt::Display for YggdrasilError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                YggdrasilError::NetworkingError(e) => {
+                    write!(f, "Yggdrasil request failed: {}", e)
+                }
+                YggdrasilError::UnexpectedStatus { url, status, body } => {
+                    write!(f, "{} answered with HTTP {}: {}", url, status, body)
+                }
+                YggdrasilError::ParseError(e) => {
+                    write!(f, "Failed to parse Yggdrasil response: {}", e)
+                }
+                YggdrasilError::Forbidden(message) => {
+                    write!(f, "The authentication server refused: {}", message)
+                }
+                YggdrasilError::NoProfile => {
+                    write!(f, "This user has no profile on the authentication server")
+                }
+                YggdrasilError::ProfileSelectionRequired(names) => write!(
+                    f,
+                    "This user has several profiles, choose one of: {}",
+                    names.join(", ")
+                ),
+                YggdrasilError::ProfileNotFound(name) => {
+                    write!(f, "This user has no profile named {}", name)
+                }
+            }
+        }
+    }
+
+    impl Error for YggdrasilError {}
+
+    impl From<reqwest::Error> for YggdrasilError {
+        fn from(error: reqwest::Error) -> Self {
+            YggdrasilError::NetworkingError(error.to_string())
+        }
+    }
+
+    /// A player profile of a Yggdrasil user
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct YggdrasilProfile {
+        /// The player UUID without dashes
+        pub id: String,
+
+        /// The player name
+        pub name: String,
+    }
+
+    /// Response of `authenticate` and `refresh`
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct SessionResponse {
+        access_token: String,
+        client_token: String,
+        #[serde(default)]
+        available_profiles: Vec<YggdrasilProfile>,
+        selected_profile: Option<YggdrasilProfile>,
+    }
+
+    /// Error body of a Yggdrasil server
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ErrorResponse {
+        error: String,
+        error_message: Option<String>,
+    }
+
+    /// An account signed in to a Yggdrasil-compatible authentication server
+    ///
+    /// The game is pointed at the server by authlib-injector, which `to_account`
+    /// adds as a Java agent.
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct YggdrasilAccount {
+        /// The API root of the server, without a trailing slash
+        pub server_url: String,
+
+        /// The player name
+        pub username: String,
+
+        /// The player UUID without dashes
+        pub uuid: String,
+
+        /// The access token
+        pub access_token: String,
+
+        /// The client token the access token is bound to
+        pub client_token: String,
+
+        /// The server metadata as JSON, handed to authlib-injector so it does not
+        /// fetch it again at startup
+        pub metadata: Option<String>,
+
+        /// Path to authlib-injector.jar
+        pub agent_jar: PathBuf,
+    }
+
+    impl YggdrasilAccount {
+        /// Gets the JVM arguments that load authlib-injector for this server
+        ///
+        /// # Returns
+        ///
+        /// * `-javaagent:<agent_jar>=<server_url>`, followed by the prefetched
+        ///   metadata if there is any
+        pub fn jvm_args(&self) -> Vec<String> {
+            let mut args = vec![format!(
+                "-javaagent:{}={}",
+                self.agent_jar.display(),
+                self.server_url
+            )];
+            if let Some(metadata) = &self.metadata {
+                args.push(format!(
+                    "-Dauthlibinjector.yggdrasil.prefetched={}",
+                    STANDARD.encode(metadata)
+                ));
+            }
+            args
+        }
+
+        /// Gets the launch values of the account, including the authlib-injector agent
+        pub fn to_account(&self) -> Account {
+            let mut account = Account::new(
+                &self.username,
+                &self.uuid,
+                &self.access_token,
+                YGGDRASIL_USER_TYPE,
+            );
+            account.jvm_args = self.jvm_args();
+            account
+        }
+    }
+
+    /// Signs in to a Yggdrasil-compatible authentication server
+    #[derive(Debug, Clone)]
+    pub struct YggdrasilAuthenticator {
+        server_url: String,
+        agent_jar: PathBuf,
+        client_token: String,
+        client: Client,
+    }
+
+    impl YggdrasilAuthenticator {
+        /// Creates an authenticator with a random client token.
+        ///
+        /// # Arguments
+        ///
+        /// * `server_url` - The API root of the server, e.g. "https://example.com/api/yggdrasil"
+        /// * `agent_jar` - Path to authlib-injector.jar
+        pub fn new(server_url: &str, agent_jar: &Path) -> Self {
+            let client_token: String = rand::random::<[u8; 16]>()
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect();
+            YggdrasilAuthenticator {
+                server_url: server_url.trim_end_matches('/').to_string(),
+                agent_jar: agent_jar.to_path_buf(),
+                client_token,
+                client: Client::new(),
+            }
+        }
+
+        /// Sets the client token, e.g. to keep the one of earlier sessions.
+        ///
+        /// # Arguments
+        ///
+        /// * `client_token` - The client token
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_client_token(mut self, client_token: &str) -> Self {
+            self.client_token = client_token.to_string();
+            self
+        }
+
+        /// Gets the API root of the server
+        pub fn server_url(&self) -> &str {
+            &self.server_url
+        }
+
+        /// Fetches the server metadata from the API root
+        ///
+        /// # Returns
+        ///
+        /// * `Result<String, YggdrasilError>` - The metadata as compact JSON
+        pub fn fetch_metadata(&self) -> Result<String, YggdrasilError> {
+            let response = self.client.get(&self.server_url).send()?;
+            let status = response.status();
+            let body = response.text()?;
+            if !status.is_success() {
+                return Err(YggdrasilError::UnexpectedStatus {
+                    url: self.server_url.clone(),
+                    status: status.as_u16(),
+                    body,
+                });
+            }
+            let metadata: Value = serde_json::from_str(&body)
+                .map_err(|e| YggdrasilError::ParseError(e.to_string()))?;
+            if !metadata.is_object() {
+                return Err(YggdrasilError::ParseError(
+                    "server metadata is not an object".to_string(),
+                ));
+            }
+            Ok(metadata.to_string())
+        }
+
+        /// Signs in with the profile the server selects, or the only one the user has
+        ///
+        /// # Arguments
+        ///
+        /// * `login` - The user name or email address
+        /// * `password` - The password
+        pub fn authenticate(
+            &self,
+            login: &str,
+            password: &str,
+        ) -> Result<YggdrasilAccount, YggdrasilError> {
+            self.authenticate_with_profile(login, password, None)
+        }
+
+        /// Signs in and selects a profile
+        ///
+        /// When the server does not select a profile, the one named `profile` is
+        /// selected with a refresh; without a name the user must have exactly one.
+        ///
+        /// # Arguments
+        ///
+        /// * `login` - The user name or email address
+        /// * `password` - The password
+        /// * `profile` - The player name to play as
+        ///
+        /// # Returns
+        ///
+        /// * `Result<YggdrasilAccount, YggdrasilError>` - The account or an error
+        pub fn authenticate_with_profile(
+            &self,
+            login: &str,
+            password: &str,
+            profile: Option<&str>,
+        ) -> Result<YggdrasilAccount, YggdrasilError> {
+            let metadata = self.fetch_metadata()?;
+            let session: SessionResponse = self.post_json(
+                "/authserver/authenticate",
+                &json!({
+                    "agent": {"name": "Minecraft", "version": 1},
+                    "username": login,
+                    "password": password,
+                    "clientToken": self.client_token,
+                    "requestUser": true
+                }),
+            )?;
+
+            if let Some(selected) = &session.selected_profile
+                && profile.is_none_or(|name| selected.name == name)
+            {
+                return Ok(self.account(session, metadata));
+            }
+            let chosen = match profile {
+                Some(name) => session
+                    .available_profiles
+                    .iter()
+                    .find(|p| p.name == name)
+                    .ok_or_else(|| YggdrasilError::ProfileNotFound(name.to_string()))?,
+                None => match session.available_profiles.as_slice() {
+                    [] => return Err(YggdrasilError::NoProfile),
+                    [only] => only,
+                    profiles => {
+                        return Err(YggdrasilError::ProfileSelectionRequired(
+                            profiles.iter().map(|p| p.name.clone()).collect(),
+                        ));
+                    }
+                },
+            };
+            let session: SessionResponse = self.post_json(
+                "/authserver/refresh",
+                &json!({
+                    "accessToken": session.access_token,
+                    "clientToken": session.client_token,
+                    "requestUser": true,
+                    "selectedProfile": chosen
+                }),
+            )?;
+            Ok(self.account(session, metadata))
+        }
+
+        /// Gets a new access token for an account and fetches the metadata again
+        ///
+        /// # Arguments
+        ///
+        /// * `account` - The account to refresh
+        pub fn refresh(
+            &self,
+            account: &YggdrasilAccount,
+        ) -> Result<YggdrasilAccount, YggdrasilError> {
+            let metadata = self.fetch_metadata()?;
+            let session: SessionResponse = self.post_json(
+                "/authserver/refresh",
+                &json!({
+                    "accessToken": account.access_token,
+                    "clientToken": account.client_token,
+                    "requestUser": true
+                }),
+            )?;
+            let mut refreshed = self.account(session, metadata);
+            if refreshed.uuid.is_empty() {
+                refreshed.uuid = account.uuid.clone();
+                refreshed.username = account.username.clone();
+            }
+            Ok(refreshed)
+        }
+
+        /// Checks whether the access token of an account can still be used
+        ///
+        /// # Arguments
+        ///
+        /// * `account` - The account to check
+        ///
+        /// # Returns
+        ///
+        /// * `Result<bool, YggdrasilError>` - False if the server rejected the token
+        pub fn validate(&self, account: &YggdrasilAccount) -> Result<bool, YggdrasilError> {
+            let body = json!({
+                "accessToken": account.access_token,
+                "clientToken": account.client_token
+            });
+            match self.post("/authserver/validate", &body) {
+                Ok(_) => Ok(true),
+                Err(YggdrasilError::Forbidden(_)) => Ok(false),
+                Err(e) => Err(e),
+            }
+        }
+
+        /// Invalidates every access token of a user
+        ///
+        /// # Arguments
+        ///
+        /// * `login` - The user name or email address
+        /// * `password` - The password
+        pub fn signout(&self, login: &str, password: &str) -> Result<(), YggdrasilError> {
+            self.post(
+                "/authserver/signout",
+                &json!({"username": login, "password": password}),
+            )?;
+            Ok(())
+        }
+
+        /// Builds an account from a session response
+        fn account(&self, session: SessionResponse, metadata: String) -> YggdrasilAccount {
+            let profile = session.selected_profile.unwrap_or(YggdrasilProfile {
+                id: String::new(),
+                name: String::new(),
+            });
+            YggdrasilAccount {
+                server_url: self.server_url.clone(),
+                username: profile.name,
+                uuid: profile.id.replace('-', ""),
+                access_token: session.access_token,
+                client_token: session.client_token,
+                metadata: Some(metadata),
+                agent_jar: self.agent_jar.clone(),
+            }
+        }
+
+        /// Posts JSON to an endpoint and parses the JSON response
+        fn post_json<T: serde::de::DeserializeOwned>(
+            &self,
+            path: &str,
+            body: &Value,
+        ) -> Result<T, YggdrasilError> {
+            let text = self.post(path, body)?;
+            serde_json::from_str(&text).map_err(|e| YggdrasilError::ParseError(e.to_string()))
+        }
+
+        /// Posts JSON to an endpoint
+        ///
+        /// # Returns
+        ///
+        /// * The response body, or `Forbidden` if the server answered with a
+        ///   ForbiddenOperationException
+        fn post(&self, path: &str, body: &Value) -> Result<String, YggdrasilError> {
+            let url = format!("{}{}", self.server_url, path);
+            let response = self
+                .client
+                .post(&url)
+                .header("Content-Type", "application/json")
+                .body(body.to_string())
+                .send()?;
+            let status = response.status();
+            let text = response.text()?;
+            if status.is_success() {
+                return Ok(text);
+            }
+            if let Ok(error) = serde_json::from_str::<ErrorResponse>(&text)
+                && (error.error == "ForbiddenOperationException" || status == StatusCode::FORBIDDEN)
+            {
+                return Err(YggdrasilError::Forbidden(
+                    error.error_message.unwrap_or(error.error),
+                ));
+            }
+            Err(YggdrasilError::UnexpectedStatus {
+                url,
+                status: status.as_u16(),
+                body: text,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::yggdrasil_account::YggdrasilAccount;
+    use std::path::PathBuf;
+
+    /// Tests the authlib-injector arguments of an account
+    #[test]
+    fn test_to_account() {
+        let account = YggdrasilAccount {
+            server_url: "https://auth.example.com/api/yggdrasil".to_string(),
+            username: "Steve".to_string(),
+            uuid: "5627dd98e6be3c21b8a8e92344183641".to_string(),
+            access_token: "token".to_string(),
+            client_token: "client".to_string(),
+            metadata: Some("{}".to_string()),
+            agent_jar: PathBuf::from("/launcher/authlib-injector.jar"),
+        };
+        let launch = account.to_account();
+        assert_eq!(launch.user_type, "mojang");
+        assert_eq!(
+            launch.jvm_args,
+            [
+                "-javaagent:/launcher/authlib-injector.jar=https://auth.example.com/api/yggdrasil",
+                "-Dauthlibinjector.yggdrasil.prefetched=e30="
+            ]
+        );
+    }
+}
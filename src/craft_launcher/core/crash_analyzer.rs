@@ -0,0 +1,542 @@
+pub mod crash_analyzer {
+    use crate::craft_launcher::core::game_output::game_output::GameLogRecord;
+    use regex::Regex;
+    use std::fmt;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::sync::LazyLock;
+    use std::time::SystemTime;
+
+    /// Lines of evidence kept per cause and source
+    const MAX_EVIDENCE_LINES: usize = 5;
+
+    /// Known causes of a crash
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum CrashCause {
+        /// The game or a mod needs a different Java version
+        WrongJavaVersion,
+
+        /// The JVM ran out of heap or could not reserve it
+        OutOfMemory,
+
+        /// A mod depends on a mod that is not installed
+        MissingMod,
+
+        /// The same mod is installed more than once
+        DuplicateMod,
+
+        /// A mixin could not be applied
+        MixinFailure,
+
+        /// OpenGL could not be initialized or the graphics driver crashed
+        GraphicsDriver,
+
+        /// A jar file is damaged
+        CorruptedLibrary,
+
+        /// The JVM itself crashed and wrote an `hs_err_pid*.log`
+        JvmCrash,
+
+        /// The game crashed for a reason that is not recognized
+        Unknown,
+    }
+
+    impl fmt::Display for CrashCause {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let name = match self {
+                CrashCause::WrongJavaVersion => "wrong Java version",
+                CrashCause::OutOfMemory => "out of memory",
+                CrashCause::MissingMod => "missing mod",
+                CrashCause::DuplicateMod => "duplicate mod",
+                CrashCause::MixinFailure => "mixin failure",
+                CrashCause::GraphicsDriver => "graphics driver error",
+                CrashCause::CorruptedLibrary => "corrupted library",
+                CrashCause::JvmCrash => "JVM crash",
+                CrashCause::Unknown => "unknown",
+            };
+            write!(f, "{}", name)
+        }
+    }
+
+    /// Where a line of evidence was found
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum EvidenceSource {
+        /// The captured game output
+        Log,
+
+        /// A file in `crash-reports/`
+        CrashReport(PathBuf),
+
+        /// An `hs_err_pid*.log` written by the JVM
+        JvmErrorLog(PathBuf),
+    }
+
+    /// A line that points at a cause
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Evidence {
+        /// Where the line was found
+        pub source: EvidenceSource,
+
+        /// The line, trimmed
+        pub line: String,
+    }
+
+    /// A cause of the crash with the lines that point at it
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Diagnosis {
+        /// The cause
+        pub cause: CrashCause,
+
+        /// One sentence describing what went wrong
+        pub summary: String,
+
+        /// Lines that point at the cause
+        pub evidence: Vec<Evidence>,
+
+        /// What the user can do about it
+        pub suggestion: String,
+    }
+
+    /// The result of analyzing an exited game
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct CrashAnalysis {
+        /// The exit code of the game, None if it was killed by a signal
+        pub exit_code: Option<i32>,
+
+        /// Whether the game crashed rather than exiting normally
+        pub crashed: bool,
+
+        /// Crash reports written during the session
+        pub crash_reports: Vec<PathBuf>,
+
+        /// JVM error logs written during the session
+        pub jvm_error_logs: Vec<PathBuf>,
+
+        /// Causes found, most specific first; empty if the game did not crash
+        pub diagnoses: Vec<Diagnosis>,
+    }
+
+    impl CrashAnalysis {
+        /// Gets the most likely cause, if the game crashed
+        pub fn primary(&self) -> Option<&Diagnosis> {
+            self.diagnoses.first()
+        }
+    }
+
+    /// A pattern that identifies a cause
+    struct CauseRule {
+        cause: CrashCause,
+        pattern: Regex,
+        summary: &'static str,
+        suggestion: &'static str,
+    }
+
+    /// Patterns of known causes, most specific first
+    static RULES: LazyLock<Vec<CauseRule>> = LazyLock::new(|| {
+        let rule = |cause, pattern: &str, summary, suggestion| CauseRule {
+            cause,
+            pattern: Regex::new(pattern).unwrap(),
+            summary,
+            suggestion,
+        };
+        vec![
+            rule(
+                CrashCause::WrongJavaVersion,
+                r"UnsupportedClassVersionError|compiled by a more recent version of the Java Runtime|Unsupported class file major version|ClassLoaders\$AppClassLoader cannot be cast to class java\.net\.URLClassLoader|requires (?:Java|java) \d+",
+                "The game or a mod needs a different Java version than the one it was started with.",
+                "Select the Java version the game version requires, or let the launcher install it.",
+            ),
+            rule(
+                CrashCause::OutOfMemory,
+                r"java\.lang\.OutOfMemoryError|Could not reserve enough space for|insufficient memory for the Java Runtime Environment|Initial heap size set to a larger value than the maximum heap size",
+                "The game ran out of memory or could not reserve the memory it was given.",
+                "Raise the maximum memory (-Xmx) of the instance, or lower it if the system does not have that much free memory.",
+            ),
+            rule(
+                CrashCause::DuplicateMod,
+                r"(?i)found duplicate mods|DuplicateModsFoundException|duplicate mod id|is provided by multiple mods|Mod .+ is duplicated",
+                "The same mod is installed more than once.",
+                "Remove the older copies of the duplicated mod from the mods folder.",
+            ),
+            rule(
+                CrashCause::MissingMod,
+                r"(?i)which is missing!|Missing or unsupported mandatory dependencies|Missing mods:|MissingModsException|requires .+ of .+, which is missing|Mod .+ requires .+ but it is not installed",
+                "A mod depends on another mod that is not installed or has the wrong version.",
+                "Install the missing dependencies listed in the evidence, in the versions they require.",
+            ),
+            rule(
+                CrashCause::MixinFailure,
+                r"Mixin(?:TransformerError|ApplyError|PrepareError|InitialisationError)|InvalidMixinException|InvalidInjectionException|Mixin apply (?:for mod \S+ )?failed|Critical injection failure",
+                "A mod failed to patch the game with a mixin.",
+                "Update or remove the mod named in the evidence; it is probably incompatible with this game version or another mod.",
+            ),
+            rule(
+                CrashCause::GraphicsDriver,
+                r"Pixel format not accelerated|GLFW error 6554[2-4]|does not appear to support OpenGL|No OpenGL context found|Couldn't set pixel format|atio6axx\.dll|ig[0-9a-z]+icd(?:32|64)\.dll|nvoglv(?:32|64)\.dll|libnvidia-glcore|(?:radeonsi|iris|i965)_dri\.so",
+                "OpenGL could not be initialized or the graphics driver crashed.",
+                "Update the graphics driver, and make sure the game runs on the dedicated GPU.",
+            ),
+            rule(
+                CrashCause::CorruptedLibrary,
+                r"java\.util\.zip\.ZipException|invalid LOC header|zip END header not found|error in opening zip file|zip file is empty|SHA1 digest error|Invalid signature file digest|Could not find or load main class",
+                "A library or the game jar is damaged or incomplete.",
+                "Verify the instance files so damaged libraries are downloaded again.",
+            ),
+        ]
+    });
+
+    /// Matches a Java class file version, used to name the Java version a class needs
+    static CLASS_FILE_VERSION: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"class file (?:major )?version (\d+)").unwrap());
+
+    /// Matches the first line of a Java exception
+    static EXCEPTION_LINE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"^(?:Exception in thread .+ |Caused by: )?[\w$]+(?:\.[\w$]+)+(?:Exception|Error)(?::|$)")
+            .unwrap()
+    });
+
+    /// Matches the signal and problematic frame lines of a JVM error log
+    static JVM_CRASH_LINE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"^#\s+(?:SIG[A-Z]+ \(|EXCEPTION_[A-Z_]+ \(|[CVJj]\s+\[)").unwrap()
+    });
+
+    /// Looks at an exited game and tells why it crashed
+    ///
+    /// Only files written after `since` are considered, so old crash reports of the
+    /// same instance are not reported again.
+    #[derive(Debug, Clone)]
+    pub struct CrashAnalyzer {
+        game_dir: PathBuf,
+        since: Option<SystemTime>,
+        log: Vec<String>,
+        max_log_lines: usize,
+    }
+
+    impl CrashAnalyzer {
+        /// Creates an analyzer for an instance
+        ///
+        /// # Arguments
+        ///
+        /// * `game_dir` - The game directory the instance was launched in
+        pub fn new(game_dir: impl AsRef<Path>) -> Self {
+            CrashAnalyzer {
+                game_dir: game_dir.as_ref().to_path_buf(),
+                since: None,
+                log: Vec::new(),
+                max_log_lines: 500,
+            }
+        }
+
+        /// Only considers crash reports and JVM error logs written at or after a time.
+        ///
+        /// # Arguments
+        ///
+        /// * `since` - Usually the time the game was launched
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_since(mut self, since: SystemTime) -> Self {
+            self.since = Some(since);
+            self
+        }
+
+        /// Sets how many lines of the captured log are kept.
+        ///
+        /// # Arguments
+        ///
+        /// * `lines` - The number of lines at the end of the log to analyze
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_max_log_lines(mut self, lines: usize) -> Self {
+            self.max_log_lines = lines;
+            self.trim_log();
+            self
+        }
+
+        /// Adds lines of captured game output
+        pub fn add_log_lines<I, S>(&mut self, lines: I) -> &mut Self
+        where
+            I: IntoIterator<Item = S>,
+            S: AsRef<str>,
+        {
+            self.log
+                .extend(lines.into_iter().map(|line| line.as_ref().to_string()));
+            self.trim_log();
+            self
+        }
+
+        /// Adds a record of captured game output, including its stack trace
+        pub fn add_record(&mut self, record: &GameLogRecord) -> &mut Self {
+            let mut lines: Vec<&str> = record.message.lines().collect();
+            if let Some(throwable) = &record.throwable {
+                lines.extend(throwable.lines());
+            }
+            self.add_log_lines(lines)
+        }
+
+        /// Analyzes the exited game
+        ///
+        /// # Arguments
+        ///
+        /// * `exit_code` - The exit code of the game, None if it was killed by a signal
+        ///
+        /// A game killed by a signal, e.g. through `GameProcess::kill`, only counts as
+        /// crashed if it left a crash report or JVM error log, or its log matches a
+        /// known cause.
+        ///
+        /// # Returns
+        ///
+        /// * The analysis; its diagnoses are empty if the game exited normally
+        pub fn analyze(&self, exit_code: Option<i32>) -> CrashAnalysis {
+            let crash_reports = self.new_files(&self.game_dir.join("crash-reports"), |name| {
+                name.ends_with(".txt")
+            });
+            let jvm_error_logs = self.new_files(&self.game_dir, |name| {
+                name.starts_with("hs_err_pid") && name.ends_with(".log")
+            });
+
+            let has_crash_files = !crash_reports.is_empty() || !jvm_error_logs.is_empty();
+            let mut analysis = CrashAnalysis {
+                exit_code,
+                crashed: has_crash_files || exit_code.is_some_and(|code| code != 0),
+                crash_reports,
+                jvm_error_logs,
+                diagnoses: Vec::new(),
+            };
+            if exit_code.is_none() && !has_crash_files {
+                let diagnoses = diagnose(&[(EvidenceSource::Log, self.log.clone())]);
+                if diagnoses
+                    .iter()
+                    .any(|diagnosis| diagnosis.cause != CrashCause::Unknown)
+                {
+                    analysis.crashed = true;
+                    analysis.diagnoses = diagnoses;
+                }
+                return analysis;
+            }
+            if !analysis.crashed {
+                return analysis;
+            }
+
+            let mut sources: Vec<(EvidenceSource, Vec<String>)> =
+                vec![(EvidenceSource::Log, self.log.clone())];
+            for path in &analysis.crash_reports {
+                sources.push((EvidenceSource::CrashReport(path.clone()), read_lines(path)));
+            }
+            for path in &analysis.jvm_error_logs {
+                sources.push((EvidenceSource::JvmErrorLog(path.clone()), read_lines(path)));
+            }
+
+            analysis.diagnoses = diagnose(&sources);
+            analysis
+        }
+
+        /// Lists files in a directory written after `since`, oldest first
+        fn new_files(&self, dir: &Path, matches: impl Fn(&str) -> bool) -> Vec<PathBuf> {
+            let Ok(entries) = fs::read_dir(dir) else {
+                return Vec::new();
+            };
+            let mut files: Vec<(SystemTime, PathBuf)> = entries
+                .flatten()
+                .filter(|entry| matches(&entry.file_name().to_string_lossy()))
+                .filter_map(|entry| {
+                    let modified = entry.metadata().ok()?.modified().ok()?;
+                    Some((modified, entry.path()))
+                })
+                .filter(|(modified, _)| self.since.is_none_or(|since| *modified >= since))
+                .collect();
+            files.sort();
+            files.into_iter().map(|(_, path)| path).collect()
+        }
+
+        /// Drops lines beyond the end of the log tail
+        fn trim_log(&mut self) {
+            if self.log.len() > self.max_log_lines {
+                let excess = self.log.len() - self.max_log_lines;
+                self.log.drain(..excess);
+            }
+        }
+    }
+
+    /// Classifies the lines of every source
+    ///
+    /// # Arguments
+    ///
+    /// * `sources` - Lines of the log, crash reports and JVM error logs
+    ///
+    /// # Returns
+    ///
+    /// * The diagnoses, most specific first; a JVM crash or unknown cause if no
+    ///   pattern matched
+    pub fn diagnose(sources: &[(EvidenceSource, Vec<String>)]) -> Vec<Diagnosis> {
+        let mut diagnoses = Vec::new();
+
+        for rule in RULES.iter() {
+            let evidence = collect_evidence(sources, |line| rule.pattern.is_match(line));
+            if evidence.is_empty() {
+                continue;
+            }
+            let mut summary = rule.summary.to_string();
+            if rule.cause == CrashCause::WrongJavaVersion
+                && let Some(major) = required_java_version(&evidence)
+            {
+                summary = format!("A class needs Java {} or newer to run.", major);
+            }
+            diagnoses.push(Diagnosis {
+                cause: rule.cause,
+                summary,
+                evidence,
+                suggestion: rule.suggestion.to_string(),
+            });
+        }
+        if !diagnoses.is_empty() {
+            return diagnoses;
+        }
+
+        let jvm_crash = collect_evidence_from(
+            sources,
+            |source| matches!(source, EvidenceSource::JvmErrorLog(_)),
+            |line| JVM_CRASH_LINE.is_match(line),
+        );
+        if !jvm_crash.is_empty() {
+            diagnoses.push(Diagnosis {
+                cause: CrashCause::JvmCrash,
+                summary: "The Java virtual machine crashed in native code.".to_string(),
+                evidence: jvm_crash,
+                suggestion: "Check the problematic frame in the JVM error log; updating Java or the graphics driver often helps.".to_string(),
+            });
+            return diagnoses;
+        }
+
+        diagnoses.push(Diagnosis {
+            cause: CrashCause::Unknown,
+            summary: "The game crashed for a reason that was not recognized.".to_string(),
+            evidence: collect_evidence(sources, |line| {
+                line.starts_with("Description:") || EXCEPTION_LINE.is_match(line)
+            }),
+            suggestion: "Read the crash report or the end of the log for the first exception."
+                .to_string(),
+        });
+        diagnoses
+    }
+
+    /// Collects matching lines from every source
+    fn collect_evidence(
+        sources: &[(EvidenceSource, Vec<String>)],
+        matches: impl Fn(&str) -> bool,
+    ) -> Vec<Evidence> {
+        collect_evidence_from(sources, |_| true, matches)
+    }
+
+    /// Collects matching lines from the sources a filter accepts
+    fn collect_evidence_from(
+        sources: &[(EvidenceSource, Vec<String>)],
+        accepts: impl Fn(&EvidenceSource) -> bool,
+        matches: impl Fn(&str) -> bool,
+    ) -> Vec<Evidence> {
+        let mut evidence = Vec::new();
+        for (source, lines) in sources.iter().filter(|(source, _)| accepts(source)) {
+            let found = lines
+                .iter()
+                .map(|line| line.trim())
+                .filter(|line| matches(line));
+            let mut seen: Vec<&str> = Vec::new();
+            for line in found {
+                if seen.contains(&line) {
+                    continue;
+                }
+                seen.push(line);
+                evidence.push(Evidence {
+                    source: source.clone(),
+                    line: line.to_string(),
+                });
+                if seen.len() == MAX_EVIDENCE_LINES {
+                    break;
+                }
+            }
+        }
+        evidence
+    }
+
+    /// Gets the Java version named by a class file version in the evidence
+    fn required_java_version(evidence: &[Evidence]) -> Option<u32> {
+        evidence.iter().find_map(|evidence| {
+            let captures = CLASS_FILE_VERSION.captures(&evidence.line)?;
+            let class_version: u32 = captures[1].parse().ok()?;
+            class_version.checked_sub(44).filter(|major| *major >= 1)
+        })
+    }
+
+    /// Reads the lines of a file, empty if it cannot be read
+    fn read_lines(path: &Path) -> Vec<String> {
+        fs::read(path)
+            .map(|bytes| {
+                String::from_utf8_lossy(&bytes)
+                    .lines()
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::crash_analyzer::{CrashAnalyzer, CrashCause, EvidenceSource, diagnose};
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    /// Tests naming the Java version from a class file version
+    #[test]
+    fn test_wrong_java_version() {
+        let diagnoses = diagnose(&[(
+            EvidenceSource::Log,
+            lines(
+                "Error: LinkageError occurred while loading main class net.minecraft.client.main.Main\n\
+                 \tjava.lang.UnsupportedClassVersionError: net/minecraft/client/main/Main has been compiled by a more recent version of the Java Runtime (class file version 65.0), this version of the Java Runtime only recognizes class file versions up to 52.0",
+            ),
+        )]);
+        assert_eq!(diagnoses[0].cause, CrashCause::WrongJavaVersion);
+        assert_eq!(
+            diagnoses[0].summary,
+            "A class needs Java 21 or newer to run."
+        );
+        assert_eq!(diagnoses[0].evidence.len(), 1);
+    }
+
+    /// Tests that a normal exit is not a crash
+    #[test]
+    fn test_normal_exit() {
+        let mut analyzer = CrashAnalyzer::new("/nonexistent/game");
+        analyzer.add_log_lines(["[12:00:00] [Render thread/INFO]: Stopping!"]);
+        let analysis = analyzer.analyze(Some(0));
+        assert!(!analysis.crashed);
+        assert!(analysis.diagnoses.is_empty());
+    }
+
+    /// Tests the fallback for crashes without a known cause
+    #[test]
+    fn test_unknown_cause() {
+        let mut analyzer = CrashAnalyzer::new("/nonexistent/game").with_max_log_lines(2);
+        analyzer.add_log_lines([
+            "java.lang.IllegalStateException: dropped from the tail",
+            "Exception in thread \"main\" java.lang.IllegalArgumentException: bad",
+            "\tat net.minecraft.client.main.Main.main(Main.java:1)",
+        ]);
+        let analysis = analyzer.analyze(Some(1));
+        assert!(analysis.crashed);
+        let diagnosis = analysis.primary().unwrap();
+        assert_eq!(diagnosis.cause, CrashCause::Unknown);
+        assert_eq!(diagnosis.evidence.len(), 1);
+        assert!(
+            diagnosis.evidence[0]
+                .line
+                .contains("IllegalArgumentException")
+        );
+    }
+}
@@ -1,2 +1,1251 @@
-mod engine {
+pub mod engine {
+    use crate::craft_launcher::core::assets::assets_parser::assets_parser::AssetsIndex;
+    use crate::craft_launcher::core::disposable::Disposable;
+    use crate::craft_launcher::core::game_output::game_output::{GameLogRecord, stream_output};
+    use crate::craft_launcher::core::launch_features::launch_features::{
+        LaunchFeatures, UnsupportedFeature,
+    };
+    use crate::craft_launcher::core::launch_hooks::launch_hooks::{
+        HookError, HookStage, run_hooks,
+    };
+    use crate::craft_launcher::core::process_registry::process_registry::{
+        InstanceLock, ProcessRegistry, RegistryError,
+    };
+    use crate::craft_launcher::core::version::base_version::LoggingClient;
+    use crate::craft_launcher::core::version::classpath::classpath::{
+        ClasspathError, ClasspathOptions, build_classpath,
+    };
+    use crate::craft_launcher::core::version::launch_arguments::launch_arguments::{
+        ArgumentPlaceholders, flatten_arguments, take_module_path,
+    };
+    use crate::craft_launcher::core::version::library_conflict::library_conflict::ConflictPolicy;
+    use crate::craft_launcher::core::version::library_installer::library_installer::{
+        LibraryInstallError, LibraryInstaller,
+    };
+    use crate::craft_launcher::core::version::modern::modern_vanilla::modern_vanilla::DownloadEntry;
+    use crate::craft_launcher::core::version::rules::rules::{RuleContext, rules_allow};
+    use crate::craft_launcher::core::version::version_resolver::version_resolver::{
+        ResolvedVersion, resolve_version,
+    };
+    use crate::craft_launcher::java::arguments_builder::arguments_builder::{
+        JavaArgumentsBuilder, PreparedCommand, java_args, remove_stale_argument_files,
+    };
+    use crate::craft_launcher::java::arguments_merger::arguments_merger::java_args_merger;
+    use crate::craft_launcher::java::java_discovery::java_discovery::{
+        JavaDiscovery, JavaRuntime, JavaSource, inspect_java_home,
+    };
+    use crate::craft_launcher::java::java_selector::java_selector::{
+        JavaRequirement, JavaSelection, JavaSelectionError, JavaSelectionOptions, select_java,
+    };
+    use crate::craft_launcher::java::library_extractor::library_extractor::{
+        LibraryExtractionError, extract_native_libraries_from,
+    };
+    use crate::craft_launcher::java::runtime_installer::runtime_installer::{
+        JavaRuntimeInstaller, RuntimeInstallError,
+    };
+    use crate::craft_launcher::utils::networking::networking;
+    use std::collections::BTreeMap;
+    use std::error::Error;
+    use std::fmt;
+    use std::fs;
+    use std::io;
+    use std::path::{Component, Path, PathBuf};
+    use std::process::{Child, ChildStderr, ChildStdout, ExitStatus, Stdio};
+    use std::sync::mpsc::Receiver;
+
+    /// Base URL asset objects are downloaded from
+    pub const RESOURCES_URL: &str = "https://resources.download.minecraft.net";
+
+    /// A game installation to launch: a version plus per-instance settings
+    #[derive(Debug, Clone, Default)]
+    pub struct Instance {
+        /// Root directory holding `versions`, `libraries`, `assets` and `runtime`
+        pub root_dir: PathBuf,
+
+        /// The version ID to launch (e.g. "1.20.1" or "fabric-loader-0.16.14-1.20.1")
+        pub version_id: String,
+
+        /// Working directory of the game (saves, mods, options.txt)
+        pub game_dir: PathBuf,
+
+        /// Java selection overrides of this instance
+        pub java: JavaSelectionOptions,
+
+        /// Initial heap size in megabytes (`-Xms`)
+        pub min_memory_mb: Option<u32>,
+
+        /// Maximum heap size in megabytes (`-Xmx`)
+        pub max_memory_mb: Option<u32>,
+
+        /// Extra JVM arguments; they override the version's arguments with the same key
+        pub jvm_args: Vec<String>,
+
+        /// Extra game arguments appended after the version's arguments
+        pub game_args: Vec<String>,
+
+        /// Environment overrides of the game process; None values are removed
+        pub env: BTreeMap<String, Option<String>>,
+
+        /// Wrapper commands the game is run through, outermost first
+        /// (e.g. `gamemoderun`, `mangohud`, `prime-run`)
+        pub wrappers: Vec<Vec<String>>,
+
+        /// Commands run before the game is spawned; a failure aborts the launch
+        pub pre_launch_hooks: Vec<Vec<String>>,
+
+        /// Commands run after the game has exited; `${exit_code}` is also available
+        pub post_exit_hooks: Vec<Vec<String>>,
+    }
+
+    impl Instance {
+        /// Creates an instance whose game directory is the root directory.
+        ///
+        /// # Arguments
+        ///
+        /// * `root_dir` - Path to the root directory where game data is stored
+        /// * `version_id` - The version ID to launch
+        pub fn new(root_dir: &Path, version_id: &str) -> Self {
+            Instance {
+                root_dir: root_dir.to_path_buf(),
+                version_id: version_id.to_string(),
+                game_dir: root_dir.to_path_buf(),
+                ..Instance::default()
+            }
+        }
+
+        /// Sets the game directory.
+        ///
+        /// # Arguments
+        ///
+        /// * `game_dir` - Working directory of the game
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_game_dir(mut self, game_dir: &Path) -> Self {
+            self.game_dir = game_dir.to_path_buf();
+            self
+        }
+
+        /// Sets the Java selection overrides.
+        ///
+        /// # Arguments
+        ///
+        /// * `java` - Java executable, major version and strict mode overrides
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_java(mut self, java: JavaSelectionOptions) -> Self {
+            self.java = java;
+            self
+        }
+
+        /// Sets the heap sizes.
+        ///
+        /// # Arguments
+        ///
+        /// * `min_memory_mb` - Initial heap size in megabytes
+        /// * `max_memory_mb` - Maximum heap size in megabytes
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_memory(mut self, min_memory_mb: u32, max_memory_mb: u32) -> Self {
+            self.min_memory_mb = Some(min_memory_mb);
+            self.max_memory_mb = Some(max_memory_mb);
+            self
+        }
+
+        /// Adds extra JVM arguments.
+        ///
+        /// # Arguments
+        ///
+        /// * `args` - The JVM arguments to add
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn add_jvm_args<I, S>(mut self, args: I) -> Self
+        where
+            I: IntoIterator<Item = S>,
+            S: Into<String>,
+        {
+            self.jvm_args.extend(args.into_iter().map(Into::into));
+            self
+        }
+
+        /// Adds extra game arguments.
+        ///
+        /// # Arguments
+        ///
+        /// * `args` - The game arguments to add
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn add_game_args<I, S>(mut self, args: I) -> Self
+        where
+            I: IntoIterator<Item = S>,
+            S: Into<String>,
+        {
+            self.game_args.extend(args.into_iter().map(Into::into));
+            self
+        }
+
+        /// Sets an environment variable of the game process.
+        ///
+        /// The last `with_env` or `remove_env` call for a variable wins.
+        ///
+        /// # Arguments
+        ///
+        /// * `name` - The variable name
+        /// * `value` - The variable value
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_env(mut self, name: &str, value: &str) -> Self {
+            self.env.insert(name.to_string(), Some(value.to_string()));
+            self
+        }
+
+        /// Removes an environment variable the game process would inherit.
+        ///
+        /// The last `with_env` or `remove_env` call for a variable wins.
+        ///
+        /// # Arguments
+        ///
+        /// * `name` - The variable name
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn remove_env(mut self, name: &str) -> Self {
+            self.env.insert(name.to_string(), None);
+            self
+        }
+
+        /// Adds a wrapper command the game is run through.
+        ///
+        /// Placeholders such as `${instance_dir}` are substituted in its arguments.
+        ///
+        /// # Arguments
+        ///
+        /// * `command` - The wrapper program followed by its arguments
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn add_wrapper<I, S>(mut self, command: I) -> Self
+        where
+            I: IntoIterator<Item = S>,
+            S: Into<String>,
+        {
+            self.wrappers
+                .push(command.into_iter().map(Into::into).collect());
+            self
+        }
+
+        /// Adds a command run before the game is spawned.
+        ///
+        /// # Arguments
+        ///
+        /// * `command` - The program followed by its arguments
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn add_pre_launch_hook<I, S>(mut self, command: I) -> Self
+        where
+            I: IntoIterator<Item = S>,
+            S: Into<String>,
+        {
+            self.pre_launch_hooks
+                .push(command.into_iter().map(Into::into).collect());
+            self
+        }
+
+        /// Adds a command run after the game has exited.
+        ///
+        /// # Arguments
+        ///
+        /// * `command` - The program followed by its arguments
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn add_post_exit_hook<I, S>(mut self, command: I) -> Self
+        where
+            I: IntoIterator<Item = S>,
+            S: Into<String>,
+        {
+            self.post_exit_hooks
+                .push(command.into_iter().map(Into::into).collect());
+            self
+        }
+    }
+
+    /// The player identity passed to the game
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Account {
+        /// The player name (`${auth_player_name}`)
+        pub username: String,
+
+        /// The player UUID without dashes (`${auth_uuid}`)
+        pub uuid: String,
+
+        /// The access token (`${auth_access_token}`)
+        pub access_token: String,
+
+        /// The account type (`${user_type}`): "msa", "mojang" or "legacy"
+        pub user_type: String,
+
+        /// The Xbox user ID (`${auth_xuid}`)
+        pub xuid: Option<String>,
+
+        /// The client ID (`${clientid}`)
+        pub client_id: Option<String>,
+
+        /// Legacy user properties as JSON (`${user_properties}`)
+        pub user_properties: String,
+
+        /// JVM arguments the account needs, such as the authlib-injector agent
+        pub jvm_args: Vec<String>,
+    }
+
+    impl Account {
+        /// Creates an account.
+        ///
+        /// # Arguments
+        ///
+        /// * `username` - The player name
+        /// * `uuid` - The player UUID
+        /// * `access_token` - The access token
+        /// * `user_type` - The account type ("msa", "mojang" or "legacy")
+        pub fn new(username: &str, uuid: &str, access_token: &str, user_type: &str) -> Self {
+            Account {
+                username: username.to_string(),
+                uuid: uuid.replace('-', ""),
+                access_token: access_token.to_string(),
+                user_type: user_type.to_string(),
+                xuid: None,
+                client_id: None,
+                user_properties: "{}".to_string(),
+                jvm_args: Vec::new(),
+            }
+        }
+    }
+
+    /// Options that apply to a single launch
+    #[derive(Debug, Clone)]
+    pub struct LaunchOptions {
+        /// Platform and launcher features that rules are evaluated against
+        pub context: RuleContext,
+
+        /// How libraries declared by both a parent and a child version are resolved
+        pub conflict_policy: ConflictPolicy,
+
+        /// Download the client jar, libraries and assets that are missing or corrupt
+        pub install_files: bool,
+
+        /// Install the Mojang runtime of the version when no suitable Java is found
+        pub install_java: bool,
+
+        /// Java runtimes to choose from; discovered on the host when None
+        pub java_runtimes: Option<Vec<JavaRuntime>>,
+
+        /// URL of the Java runtime index used when installing Java
+        pub java_runtime_index_url: Option<String>,
+
+        /// Base URL asset objects are downloaded from
+        pub resources_url: String,
+
+        /// Pass JVM arguments through an `@argfile`
+        pub use_argument_file: bool,
+
+        /// Refuse to launch an instance that is already running, using the lock files
+        /// of `ProcessRegistry` in the root directory
+        pub use_process_registry: bool,
+
+        /// Download the version's Log4j config and pass it to the game, so its
+        /// output is printed as XML events
+        pub use_log_config: bool,
+
+        /// Keep the argument file after the game exits
+        pub debug: bool,
+
+        /// Quick Play, window size and demo mode requested for this launch
+        pub features: LaunchFeatures,
+
+        /// Value of `${launcher_name}`
+        pub launcher_name: String,
+
+        /// Value of `${launcher_version}`
+        pub launcher_version: String,
+    }
+
+    impl Default for LaunchOptions {
+        fn default() -> Self {
+            LaunchOptions {
+                context: RuleContext::current(),
+                conflict_policy: ConflictPolicy::ChildWins,
+                install_files: true,
+                install_java: true,
+                java_runtimes: None,
+                java_runtime_index_url: None,
+                resources_url: RESOURCES_URL.to_string(),
+                use_argument_file: false,
+                use_log_config: true,
+                use_process_registry: true,
+                debug: false,
+                features: LaunchFeatures::default(),
+                launcher_name: env!("CARGO_PKG_NAME").to_string(),
+                launcher_version: env!("CARGO_PKG_VERSION").to_string(),
+            }
+        }
+    }
+
+    /// Error type for launching the game
+    #[derive(Debug)]
+    pub enum LaunchError {
+        /// The version or one of its parents could not be read
+        VersionResolution(String),
+
+        /// A client jar or asset could not be installed
+        Install(String),
+
+        /// A library could not be installed
+        LibraryInstall(LibraryInstallError),
+
+        /// Native libraries could not be extracted
+        Natives(LibraryExtractionError),
+
+        /// No suitable Java runtime was found
+        Java(JavaSelectionError),
+
+        /// The Java runtime of the version could not be installed
+        JavaInstall(RuntimeInstallError),
+
+        /// Classpath entries are missing
+        Classpath(ClasspathError),
+
+        /// The instance is already running, or its lock could not be taken
+        Registry(RegistryError),
+
+        /// A pre-launch hook failed
+        Hook(HookError),
+
+        /// A requested Quick Play target cannot be opened by this version
+        UnsupportedFeature(UnsupportedFeature),
+
+        /// A file system operation or the process spawn failed
+        IoError(io::Error),
+    }
+
+    impl fmt::Display for LaunchError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                LaunchError::VersionResolution(e) => write!(f, "Failed to resolve version: {}", e),
+                LaunchError::Install(e) => write!(f, "Failed to install game files: {}", e),
+                LaunchError::LibraryInstall(e) => write!(f, "{}", e),
+                LaunchError::Natives(e) => write!(f, "Failed to extract natives: {}", e),
+                LaunchError::Java(e) => write!(f, "{}", e),
+                LaunchError::JavaInstall(e) => write!(f, "Failed to install Java: {}", e),
+                LaunchError::Classpath(e) => write!(f, "{}", e),
+                LaunchError::Registry(e) => write!(f, "{}", e),
+                LaunchError::Hook(e) => write!(f, "{}", e),
+                LaunchError::UnsupportedFeature(e) => write!(f, "{}", e),
+                LaunchError::IoError(e) => write!(f, "Launch IO error: {}", e),
+            }
+        }
+    }
+
+    impl Error for LaunchError {}
+
+    impl From<io::Error> for LaunchError {
+        fn from(error: io::Error) -> Self {
+            LaunchError::IoError(error)
+        }
+    }
+
+    impl From<RegistryError> for LaunchError {
+        fn from(error: RegistryError) -> Self {
+            LaunchError::Registry(error)
+        }
+    }
+
+    impl From<UnsupportedFeature> for LaunchError {
+        fn from(error: UnsupportedFeature) -> Self {
+            LaunchError::UnsupportedFeature(error)
+        }
+    }
+
+    impl From<LibraryInstallError> for LaunchError {
+        fn from(error: LibraryInstallError) -> Self {
+            LaunchError::LibraryInstall(error)
+        }
+    }
+
+    /// Everything computed for a launch, before the process is spawned
+    #[derive(Debug)]
+    pub struct PreparedLaunch {
+        /// The resolved version
+        pub version: ResolvedVersion,
+
+        /// The selected Java runtime
+        pub java: JavaSelection,
+
+        /// Directory the natives were extracted into
+        pub natives_dir: PathBuf,
+
+        /// Classpath entries in order
+        pub classpath: Vec<PathBuf>,
+
+        /// Module path entries in order (modern Forge and NeoForge)
+        pub module_path: Vec<PathBuf>,
+
+        /// Working directory of the game
+        pub working_dir: PathBuf,
+
+        /// Environment overrides of the game; None values are removed
+        pub env: BTreeMap<String, Option<String>>,
+
+        /// The command line, owning its argument file
+        pub command: PreparedCommand,
+
+        /// Values substituted into hook commands
+        pub hook_placeholders: ArgumentPlaceholders,
+
+        /// Commands run before the game is spawned
+        pub pre_launch_hooks: Vec<Vec<String>>,
+
+        /// Commands run after the game has exited
+        pub post_exit_hooks: Vec<Vec<String>>,
+    }
+
+    /// A running game
+    ///
+    /// Dropping the handle does not stop the game; call `kill` or `dispose` for that.
+    /// The argument file is removed once `wait` or `try_wait` sees the game exit, or on
+    /// `dispose`. A file left behind by a dropped handle is removed by a later launch
+    /// after this launcher process has exited.
+    ///
+    /// Post-exit hooks run on the calling thread from `wait`, `kill` and `dispose`,
+    /// never from `try_wait`.
+    #[derive(Debug)]
+    pub struct GameProcess {
+        child: Child,
+        command: PreparedCommand,
+        natives_dir: PathBuf,
+        lock: Option<InstanceLock>,
+        working_dir: PathBuf,
+        hook_placeholders: ArgumentPlaceholders,
+        post_exit_hooks: Vec<Vec<String>>,
+        hook_error: Option<HookError>,
+        disposed: bool,
+    }
+
+    impl GameProcess {
+        /// Gets the process ID
+        pub fn pid(&self) -> u32 {
+            self.child.id()
+        }
+
+        /// Gets the command line the game was started with
+        pub fn command(&self) -> &[String] {
+            &self.command.command
+        }
+
+        /// Gets the natives directory of the game
+        pub fn natives_dir(&self) -> &Path {
+            &self.natives_dir
+        }
+
+        /// Takes the standard output stream; None if it was already taken
+        pub fn take_stdout(&mut self) -> Option<ChildStdout> {
+            self.child.stdout.take()
+        }
+
+        /// Takes the standard error stream; None if it was already taken
+        pub fn take_stderr(&mut self) -> Option<ChildStderr> {
+            self.child.stderr.take()
+        }
+
+        /// Streams the records of the game's stdout and stderr
+        ///
+        /// Takes both streams; the receiver is closed once the game exits.
+        pub fn output(&mut self) -> Receiver<GameLogRecord> {
+            stream_output(self.take_stdout(), self.take_stderr())
+        }
+
+        /// Calls back for every record of the game's output until the game closes it
+        ///
+        /// Takes both streams and blocks until they are closed.
+        pub fn capture_output(&mut self, mut callback: impl FnMut(GameLogRecord)) {
+            for record in self.output() {
+                callback(record);
+            }
+        }
+
+        /// Waits for the game to exit and runs the post-exit hooks
+        pub fn wait(&mut self) -> io::Result<ExitStatus> {
+            let status = self.child.wait()?;
+            self.exited();
+            self.run_post_exit_hooks(status);
+            Ok(status)
+        }
+
+        /// Checks whether the game has exited without blocking
+        ///
+        /// Once the game has exited, the instance is released and the argument file is
+        /// removed. The post-exit hooks may take a while, so they are not run here;
+        /// call `wait` (which returns at once) to run them.
+        pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
+            let status = self.child.try_wait()?;
+            if status.is_some() {
+                self.exited();
+            }
+            Ok(status)
+        }
+
+        /// Gets the error of the first post-exit hook that failed
+        pub fn post_exit_hook_error(&self) -> Option<&HookError> {
+            self.hook_error.as_ref()
+        }
+
+        /// Releases the instance and removes the argument file after the game has exited
+        fn exited(&mut self) {
+            self.command.dispose();
+            self.release_lock();
+        }
+
+        /// Runs the post-exit hooks, once
+        fn run_post_exit_hooks(&mut self, status: ExitStatus) {
+            let hooks = std::mem::take(&mut self.post_exit_hooks);
+            if hooks.is_empty() {
+                return;
+            }
+            let exit_code = status.code().map(|code| code.to_string());
+            let placeholders = self
+                .hook_placeholders
+                .clone()
+                .with("exit_code", exit_code.as_deref().unwrap_or("-1"));
+            if let Err(e) = run_hooks(
+                HookStage::PostExit,
+                &hooks,
+                &placeholders,
+                &self.working_dir,
+                &self.command.env,
+            ) {
+                self.hook_error = Some(e);
+            }
+        }
+
+        /// Kills the game, waits for it to exit and runs the post-exit hooks
+        pub fn kill(&mut self) -> io::Result<ExitStatus> {
+            if self.child.try_wait()?.is_none() {
+                self.child.kill()?;
+            }
+            self.wait()
+        }
+
+        /// Removes the instance from the process registry
+        fn release_lock(&mut self) {
+            if let Some(lock) = self.lock.as_mut() {
+                lock.release();
+            }
+        }
+    }
+
+    impl Disposable for GameProcess {
+        /// Kills the game if it is still running, runs the post-exit hooks and removes
+        /// the argument file
+        fn dispose(&mut self) {
+            if self.disposed {
+                return;
+            }
+            self.disposed = true;
+            let _ = self.kill();
+            self.command.dispose();
+            self.release_lock();
+        }
+    }
+
+    /// Launches a game instance
+    ///
+    /// Resolves the version, installs missing files, extracts natives, picks Java,
+    /// substitutes arguments and spawns the game in the instance's game directory
+    /// with stdout and stderr piped. Unless disabled, the instance is locked in the
+    /// process registry first, so it cannot be launched twice.
+    ///
+    /// # Arguments
+    ///
+    /// * `instance` - The instance to launch
+    /// * `account` - The player to launch as
+    /// * `options` - Options of this launch
+    ///
+    /// # Returns
+    ///
+    /// * `Result<GameProcess, LaunchError>` - The running game or an error
+    pub fn launch(
+        instance: &Instance,
+        account: &Account,
+        options: &LaunchOptions,
+    ) -> Result<GameProcess, LaunchError> {
+        let lock = if options.use_process_registry {
+            // The lock is keyed by the canonical game directory, so it must exist first
+            fs::create_dir_all(&instance.game_dir)?;
+            let registry = ProcessRegistry::new(&instance.root_dir);
+            Some(registry.acquire(&instance.game_dir, &instance.version_id, account)?)
+        } else {
+            None
+        };
+
+        let prepared = prepare_launch(instance, account, options)?;
+        let mut game = spawn(prepared)?;
+        if let Some(mut lock) = lock {
+            if let Err(e) = lock.set_running(game.pid()) {
+                game.dispose();
+                return Err(e.into());
+            }
+            game.lock = Some(lock);
+        }
+        Ok(game)
+    }
+
+    /// Prepares a launch without spawning the game
+    ///
+    /// Performs every step of `launch` up to and including writing the argument file.
+    pub fn prepare_launch(
+        instance: &Instance,
+        account: &Account,
+        options: &LaunchOptions,
+    ) -> Result<PreparedLaunch, LaunchError> {
+        let root_dir = instance.root_dir.as_path();
+        let version = resolve_version(root_dir, &instance.version_id, options.conflict_policy)
+            .map_err(|e| LaunchError::VersionResolution(e.to_string()))?;
+
+        if options.install_files {
+            install_files(&version, root_dir, &instance.game_dir, options)?;
+        }
+
+        let natives_dir = extract_natives(&version, root_dir, &options.context)?;
+        let java = pick_java(&version, instance, options)?;
+
+        let classpath = build_classpath(
+            &version,
+            root_dir,
+            &ClasspathOptions {
+                context: options.context.clone(),
+                fail_on_missing: options.install_files,
+                ..ClasspathOptions::default()
+            },
+        )
+        .map_err(LaunchError::Classpath)?;
+
+        fs::create_dir_all(&instance.game_dir)?;
+        let (builder, module_path) = command_builder(
+            &version,
+            instance,
+            account,
+            options,
+            &java.runtime.executable,
+            Some(&natives_dir),
+            &classpath,
+        )?;
+
+        let mut builder = builder.keep_argument_file(options.debug);
+        if options.use_argument_file {
+            let argument_file_dir = root_dir.join("bin").join("argfiles");
+            remove_stale_argument_files(&argument_file_dir)?;
+            builder = builder.with_argument_file(argument_file_dir);
+        }
+        let command = builder.prepare()?;
+        let hook_placeholders = hook_placeholders(&version, instance);
+
+        Ok(PreparedLaunch {
+            version,
+            java,
+            natives_dir,
+            classpath,
+            module_path,
+            working_dir: instance.game_dir.clone(),
+            env: instance.env.clone(),
+            command,
+            hook_placeholders,
+            pre_launch_hooks: instance.pre_launch_hooks.clone(),
+            post_exit_hooks: instance.post_exit_hooks.clone(),
+        })
+    }
+
+    /// Spawns a prepared launch
+    ///
+    /// Runs the pre-launch hooks first; if one fails, the game is not spawned.
+    pub fn spawn(prepared: PreparedLaunch) -> Result<GameProcess, LaunchError> {
+        run_hooks(
+            HookStage::PreLaunch,
+            &prepared.pre_launch_hooks,
+            &prepared.hook_placeholders,
+            &prepared.working_dir,
+            &prepared.command.env,
+        )
+        .map_err(LaunchError::Hook)?;
+
+        let child = prepared
+            .command
+            .to_command()
+            .current_dir(&prepared.working_dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        Ok(GameProcess {
+            child,
+            command: prepared.command,
+            natives_dir: prepared.natives_dir,
+            lock: None,
+            working_dir: prepared.working_dir,
+            hook_placeholders: prepared.hook_placeholders,
+            post_exit_hooks: prepared.post_exit_hooks,
+            hook_error: None,
+            disposed: false,
+        })
+    }
+
+    /// Builds the command line of a launch and returns it with the module path entries
+    ///
+    /// Placeholders without a value, such as `${natives_directory}` when `natives_dir`
+    /// is None, are left in the arguments. Requested launch features enable the rules
+    /// the version declares for them and fall back to legacy arguments otherwise.
+    pub(crate) fn command_builder(
+        version: &ResolvedVersion,
+        instance: &Instance,
+        account: &Account,
+        options: &LaunchOptions,
+        java_executable: &Path,
+        natives_dir: Option<&Path>,
+        classpath: &[PathBuf],
+    ) -> Result<(JavaArgumentsBuilder, Vec<PathBuf>), LaunchError> {
+        let placeholders = options.features.placeholders(placeholders(
+            version,
+            instance,
+            account,
+            options,
+            natives_dir,
+            classpath,
+        ));
+        let context = options.features.rule_context(version, &options.context);
+
+        let mut jvm_args = version_jvm_arguments(version, &context);
+        remove_classpath_option(&mut jvm_args);
+        let mut jvm_args = placeholders.substitute_all(&jvm_args);
+        let module_path = take_module_path(&mut jvm_args);
+
+        let mut merger = java_args_merger();
+        merger.add_jvm_args(jvm_args);
+        if let Some(argument) = log_config_argument(version, &instance.root_dir, options) {
+            merger.add_jvm_args([argument]);
+        }
+        if let Some(min) = instance.min_memory_mb {
+            merger.add_jvm_args([format!("-Xms{}M", min)]);
+        }
+        if let Some(max) = instance.max_memory_mb {
+            merger.add_jvm_args([format!("-Xmx{}M", max)]);
+        }
+        merger.add_jvm_args(account.jvm_args.iter().cloned());
+        merger.add_jvm_args(placeholders.substitute_all(&instance.jvm_args));
+
+        let mut game_args = placeholders.substitute_all(&version_game_arguments(version, &context));
+        game_args.extend(options.features.legacy_arguments(version)?);
+        game_args.extend(placeholders.substitute_all(&instance.game_args));
+
+        let hook_placeholders = hook_placeholders(version, instance);
+        let mut builder = java_args();
+        for wrapper in &instance.wrappers {
+            builder = builder.add_wrapper(hook_placeholders.substitute_all(wrapper));
+        }
+        for (name, value) in &instance.env {
+            builder = match value {
+                Some(value) => builder.with_env(name, value),
+                None => builder.remove_env(name),
+            };
+        }
+
+        let builder = builder
+            .with_executable(java_executable.to_string_lossy())
+            .check_exists(false)
+            .add_jvm_args(merger.build_jvm_args())
+            .add_module_path_entries(&module_path)
+            .add_classpath_entries(classpath)
+            .with_main_class(version.main_class.clone())
+            .add_program_args(game_args);
+        Ok((builder, module_path))
+    }
+
+    /// Gets the path the Log4j config of a version is stored at
+    ///
+    /// # Arguments
+    ///
+    /// * `root_dir` - Path to the root directory where game data is stored
+    /// * `logging` - The client logging configuration of the version
+    pub fn log_config_path(root_dir: &Path, logging: &LoggingClient) -> PathBuf {
+        root_dir
+            .join("assets")
+            .join("log_configs")
+            .join(&logging.file.id)
+    }
+
+    /// Gets the JVM argument that points Log4j at the version's XML config
+    fn log_config_argument(
+        version: &ResolvedVersion,
+        root_dir: &Path,
+        options: &LaunchOptions,
+    ) -> Option<String> {
+        let logging = version.logging.as_ref()?;
+        if !options.use_log_config || logging.type_ != "log4j2-xml" {
+            return None;
+        }
+        let path = log_config_path(root_dir, logging);
+        Some(logging.argument.replace("${path}", &path.to_string_lossy()))
+    }
+
+    /// Gets the JVM arguments of a version, with the legacy defaults for versions without any
+    fn version_jvm_arguments(version: &ResolvedVersion, context: &RuleContext) -> Vec<String> {
+        if version.jvm_arguments.is_empty() {
+            return vec![
+                "-Djava.library.path=${natives_directory}".to_string(),
+                "-cp".to_string(),
+                "${classpath}".to_string(),
+            ];
+        }
+        flatten_arguments(&version.jvm_arguments, context)
+    }
+
+    /// Gets the game arguments of a version, splitting legacy `minecraftArguments`
+    fn version_game_arguments(version: &ResolvedVersion, context: &RuleContext) -> Vec<String> {
+        if !version.game_arguments.is_empty() {
+            return flatten_arguments(&version.game_arguments, context);
+        }
+        version
+            .minecraft_arguments
+            .as_deref()
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Removes `-cp ${classpath}` so the builder can own the classpath
+    fn remove_classpath_option(arguments: &mut Vec<String>) {
+        let mut index = 0;
+        while index < arguments.len() {
+            let is_option = matches!(
+                arguments[index].as_str(),
+                "-cp" | "-classpath" | "--class-path"
+            );
+            if is_option && arguments.get(index + 1).map(String::as_str) == Some("${classpath}") {
+                arguments.drain(index..index + 2);
+            } else {
+                index += 1;
+            }
+        }
+    }
+
+    /// Builds the placeholder values of a launch
+    fn placeholders(
+        version: &ResolvedVersion,
+        instance: &Instance,
+        account: &Account,
+        options: &LaunchOptions,
+        natives_dir: Option<&Path>,
+        classpath: &[PathBuf],
+    ) -> ArgumentPlaceholders {
+        let root_dir = &instance.root_dir;
+        let assets_root = root_dir.join("assets");
+        let assets_index_name = version
+            .asset_index
+            .as_ref()
+            .map(|index| index.id.clone())
+            .or_else(|| version.assets.clone())
+            .unwrap_or_else(|| "legacy".to_string());
+        let game_assets = legacy_assets_dir(root_dir, &instance.game_dir, &assets_index_name);
+
+        let mut placeholders =
+            ArgumentPlaceholders::for_version(version, root_dir).with_classpath(classpath);
+        if let Some(natives_dir) = natives_dir {
+            placeholders = placeholders.with("natives_directory", &natives_dir.to_string_lossy());
+        }
+
+        placeholders
+            .with("launcher_name", &options.launcher_name)
+            .with("launcher_version", &options.launcher_version)
+            .with("game_directory", &instance.game_dir.to_string_lossy())
+            .with("assets_root", &assets_root.to_string_lossy())
+            .with("assets_index_name", &assets_index_name)
+            .with("game_assets", &game_assets.to_string_lossy())
+            .with("auth_player_name", &account.username)
+            .with("auth_uuid", &account.uuid)
+            .with("auth_access_token", &account.access_token)
+            .with(
+                "auth_session",
+                &format!("token:{}:{}", account.access_token, account.uuid),
+            )
+            .with("user_type", launch_user_type(version, account))
+            .with("user_properties", &account.user_properties)
+            .with("auth_xuid", account.xuid.as_deref().unwrap_or("0"))
+            .with("clientid", account.client_id.as_deref().unwrap_or("0"))
+    }
+
+    /// Gets `${user_type}` for a version
+    ///
+    /// The `legacy` type of offline accounts only means something to versions with
+    /// `minecraftArguments`; versions with `arguments` get `msa`, which every modern
+    /// client accepts.
+    fn launch_user_type<'a>(version: &ResolvedVersion, account: &'a Account) -> &'a str {
+        if account.user_type == "legacy" && !version.game_arguments.is_empty() {
+            "msa"
+        } else {
+            &account.user_type
+        }
+    }
+
+    /// Builds the placeholder values of wrapper and hook commands
+    ///
+    /// Only paths and the version are exposed; account secrets are not.
+    pub fn hook_placeholders(
+        version: &ResolvedVersion,
+        instance: &Instance,
+    ) -> ArgumentPlaceholders {
+        let game_dir = instance.game_dir.to_string_lossy();
+        ArgumentPlaceholders::for_version(version, &instance.root_dir)
+            .with("instance_dir", &game_dir)
+            .with("game_directory", &game_dir)
+            .with("root_dir", &instance.root_dir.to_string_lossy())
+            .with("version_id", &version.id)
+    }
+
+    /// Gets the directory of pre-1.7 assets: `resources` in the game directory or `assets/virtual/<index>`
+    fn legacy_assets_dir(root_dir: &Path, game_dir: &Path, index_name: &str) -> PathBuf {
+        let index_path = root_dir
+            .join("assets")
+            .join("indexes")
+            .join(format!("{}.json", index_name));
+        let index = fs::read_to_string(index_path)
+            .ok()
+            .and_then(|json| AssetsIndex::from_json(&json).ok());
+        match index {
+            Some(index) if index.is_map_to_resources() => game_dir.join("resources"),
+            _ => root_dir.join("assets").join("virtual").join(index_name),
+        }
+    }
+
+    /// Extracts natives, falling back to an empty directory for versions without any
+    pub(crate) fn extract_natives(
+        version: &ResolvedVersion,
+        root_dir: &Path,
+        context: &RuleContext,
+    ) -> Result<PathBuf, LaunchError> {
+        match extract_native_libraries_from(root_dir, &version.libraries, context) {
+            Ok(dir) => Ok(dir),
+            Err(LibraryExtractionError::NoNativeLibrariesFound) => {
+                let dir = root_dir.join("bin").join("empty");
+                fs::create_dir_all(&dir)?;
+                Ok(dir)
+            }
+            Err(e) => Err(LaunchError::Natives(e)),
+        }
+    }
+
+    /// Gets the Java runtimes a launch can choose from
+    pub(crate) fn available_runtimes(
+        instance: &Instance,
+        options: &LaunchOptions,
+    ) -> Vec<JavaRuntime> {
+        match &options.java_runtimes {
+            Some(runtimes) => runtimes.clone(),
+            None => JavaDiscovery::new()
+                .add_managed_dir(&instance.root_dir)
+                .runtimes()
+                .to_vec(),
+        }
+    }
+
+    /// Selects Java, installing the version's Mojang runtime when nothing fits
+    fn pick_java(
+        version: &ResolvedVersion,
+        instance: &Instance,
+        options: &LaunchOptions,
+    ) -> Result<JavaSelection, LaunchError> {
+        let runtimes = available_runtimes(instance, options);
+
+        let strict = JavaSelectionOptions {
+            strict: true,
+            ..instance.java.clone()
+        };
+        let error = match select_java(version, &runtimes, &strict) {
+            Ok(selection) => return Ok(selection),
+            Err(e) => e,
+        };
+
+        let requirement = JavaRequirement::for_version(version);
+        let can_install = options.install_java
+            && instance.java.java_path.is_none()
+            && instance.java.major_version.is_none()
+            && requirement.component.is_some();
+        if !can_install {
+            // Without strict mode the best incompatible runtime is still acceptable
+            if instance.java.strict {
+                return Err(LaunchError::Java(error));
+            }
+            return select_java(version, &runtimes, &instance.java).map_err(LaunchError::Java);
+        }
+
+        let mut installer = JavaRuntimeInstaller::new(&instance.root_dir);
+        if let Some(url) = &options.java_runtime_index_url {
+            installer = installer.with_index_url(url);
+        }
+        let component = requirement.component.as_deref().unwrap_or_default();
+        let installed = installer
+            .install(component)
+            .map_err(LaunchError::JavaInstall)?;
+        let home = installed
+            .executable
+            .parent()
+            .and_then(Path::parent)
+            .unwrap_or(&installed.directory);
+        let runtime = inspect_java_home(home, JavaSource::Managed).ok_or_else(|| {
+            LaunchError::JavaInstall(RuntimeInstallError::InvalidPath(home.display().to_string()))
+        })?;
+        select_java(version, &[runtime], &instance.java).map_err(LaunchError::Java)
+    }
+
+    /// Installs the client jar, the libraries and the assets of a version
+    fn install_files(
+        version: &ResolvedVersion,
+        root_dir: &Path,
+        game_dir: &Path,
+        options: &LaunchOptions,
+    ) -> Result<(), LaunchError> {
+        if let Some(client) = &version.client_download {
+            let jar = root_dir
+                .join("versions")
+                .join(&version.jar)
+                .join(format!("{}.jar", version.jar));
+            ensure_file(&jar, client)?;
+        }
+
+        let libraries: Vec<_> = version
+            .libraries
+            .iter()
+            .filter(|library| rules_allow(library.rules(), &options.context))
+            .cloned()
+            .collect();
+        LibraryInstaller::new(root_dir).install_all(&libraries)?;
+
+        if let Some(logging) = &version.logging
+            && options.use_log_config
+        {
+            ensure_file(
+                &log_config_path(root_dir, logging),
+                &DownloadEntry {
+                    sha1: logging.file.sha1.clone(),
+                    size: logging.file.size,
+                    url: logging.file.url.clone(),
+                },
+            )?;
+        }
+
+        if let Some(asset_index) = &version.asset_index {
+            let index_path = root_dir
+                .join("assets")
+                .join("indexes")
+                .join(format!("{}.json", asset_index.id));
+            ensure_file(
+                &index_path,
+                &DownloadEntry {
+                    sha1: asset_index.sha1.clone(),
+                    size: asset_index.size,
+                    url: asset_index.url.clone(),
+                },
+            )?;
+            install_assets(root_dir, game_dir, &index_path, &asset_index.id, options)?;
+        }
+
+        Ok(())
+    }
+
+    /// Downloads the objects of an asset index and lays out legacy assets
+    ///
+    /// Objects are copied to the `${game_assets}` directory of `legacy_assets_dir`.
+    fn install_assets(
+        root_dir: &Path,
+        game_dir: &Path,
+        index_path: &Path,
+        index_id: &str,
+        options: &LaunchOptions,
+    ) -> Result<(), LaunchError> {
+        let json = fs::read_to_string(index_path)?;
+        let index =
+            AssetsIndex::from_json(&json).map_err(|e| LaunchError::Install(e.to_string()))?;
+        let objects_dir = root_dir.join("assets").join("objects");
+        let resources_url = options.resources_url.trim_end_matches('/');
+        let legacy_dir = if index.is_map_to_resources() {
+            Some(game_dir.join("resources"))
+        } else if index.is_virtual() {
+            Some(root_dir.join("assets").join("virtual").join(index_id))
+        } else {
+            None
+        };
+
+        for (name, object) in &index.objects {
+            let prefix = object.hash.get(..2).unwrap_or_default();
+            let path = objects_dir.join(prefix).join(&object.hash);
+            ensure_file(
+                &path,
+                &DownloadEntry {
+                    sha1: object.hash.clone(),
+                    size: object.size as i64,
+                    url: format!("{}/{}/{}", resources_url, prefix, object.hash),
+                },
+            )?;
+
+            if let Some(legacy_dir) = &legacy_dir {
+                let legacy_path = legacy_dir.join(asset_relative_path(name)?);
+                if !legacy_path.is_file() {
+                    if let Some(parent) = legacy_path.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+                    fs::copy(&path, &legacy_path)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that an asset name is a relative path that stays inside its directory
+    fn asset_relative_path(name: &str) -> Result<&Path, LaunchError> {
+        let path = Path::new(name);
+        let is_safe = !name.is_empty()
+            && path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)));
+        if is_safe {
+            Ok(path)
+        } else {
+            Err(LaunchError::Install(format!(
+                "Invalid asset name: {}",
+                name
+            )))
+        }
+    }
+
+    /// Downloads a file unless it already exists with the expected hash
+    fn ensure_file(path: &Path, download: &DownloadEntry) -> Result<(), LaunchError> {
+        networking::ensure_file(path, &download.url, &download.sha1)
+            .map_err(|e| LaunchError::Install(e.to_string()))?;
+        Ok(())
+    }
 }
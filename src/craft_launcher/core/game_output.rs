@@ -0,0 +1,450 @@
+pub mod game_output {
+    use quick_xml::escape::unescape;
+    use quick_xml::events::{BytesStart, Event};
+    use quick_xml::{Reader, XmlVersion};
+    use regex::Regex;
+    use std::io::{BufRead, BufReader, Read};
+    use std::sync::LazyLock;
+    use std::sync::mpsc::{self, Receiver, Sender};
+    use std::thread;
+
+    /// Opening tag of a Log4j XML event
+    const EVENT_START: &str = "<log4j:Event";
+
+    /// Closing tag of a Log4j XML event
+    const EVENT_END: &str = "</log4j:Event>";
+
+    /// Matches the `[12:34:56] [Render thread/INFO]: message` lines of plain-text logging
+    static PLAIN_LINE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"^\[(\d{2}:\d{2}:\d{2})\] \[([^\]]+)/([A-Z]+)\](?: \[([^\]]+)\])?: (.*)$")
+            .unwrap()
+    });
+
+    /// Severity of a log record
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub enum LogLevel {
+        Trace,
+        Debug,
+        Info,
+        Warn,
+        Error,
+        Fatal,
+    }
+
+    impl LogLevel {
+        /// Parses a Log4j level name, ignoring case
+        pub fn parse(name: &str) -> Option<LogLevel> {
+            match name.to_ascii_uppercase().as_str() {
+                "TRACE" => Some(LogLevel::Trace),
+                "DEBUG" => Some(LogLevel::Debug),
+                "INFO" => Some(LogLevel::Info),
+                "WARN" | "WARNING" => Some(LogLevel::Warn),
+                "ERROR" => Some(LogLevel::Error),
+                "FATAL" => Some(LogLevel::Fatal),
+                _ => None,
+            }
+        }
+    }
+
+    /// The stream a record was read from
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum OutputStream {
+        Stdout,
+        Stderr,
+    }
+
+    /// A line or event the game wrote
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct GameLogRecord {
+        /// The stream the record was read from
+        pub stream: OutputStream,
+
+        /// Milliseconds since the Unix epoch; only known for XML events
+        pub timestamp: Option<i64>,
+
+        /// Time of day as printed by plain-text logging (e.g. "12:34:56")
+        pub time: Option<String>,
+
+        /// Severity, if the record carried one
+        pub level: Option<LogLevel>,
+
+        /// Name of the thread that logged the record
+        pub thread: Option<String>,
+
+        /// Name of the logger
+        pub logger: Option<String>,
+
+        /// The message
+        pub message: String,
+
+        /// Stack trace attached to the record
+        pub throwable: Option<String>,
+
+        /// Whether the record was parsed from a Log4j XML event
+        pub xml: bool,
+    }
+
+    impl GameLogRecord {
+        /// Creates a record from a line that has no structure
+        pub fn plain(stream: OutputStream, message: &str) -> Self {
+            GameLogRecord {
+                stream,
+                timestamp: None,
+                time: None,
+                level: None,
+                thread: None,
+                logger: None,
+                message: message.to_string(),
+                throwable: None,
+                xml: false,
+            }
+        }
+    }
+
+    /// Turns lines of game output into log records
+    ///
+    /// Versions with a `log4j2-xml` logging config print every event as a multi-line
+    /// `<log4j:Event>` element. Output that is not part of an event, such as
+    /// `System.out` prints or the plain-text logging of old versions, becomes one
+    /// record per line.
+    #[derive(Debug)]
+    pub struct GameOutputParser {
+        stream: OutputStream,
+        buffer: Option<String>,
+    }
+
+    impl GameOutputParser {
+        /// Creates a parser for a stream
+        pub fn new(stream: OutputStream) -> Self {
+            GameOutputParser {
+                stream,
+                buffer: None,
+            }
+        }
+
+        /// Feeds a line without its line terminator
+        ///
+        /// # Returns
+        ///
+        /// * The records completed by this line
+        pub fn push_line(&mut self, line: &str) -> Vec<GameLogRecord> {
+            let mut records = Vec::new();
+            let mut rest = line;
+
+            loop {
+                match self.buffer.as_mut() {
+                    Some(buffer) => match rest.find(EVENT_END) {
+                        Some(end) => {
+                            let end = end + EVENT_END.len();
+                            buffer.push_str(&rest[..end]);
+                            let xml = self.buffer.take().unwrap_or_default();
+                            records.extend(self.finish_event(&xml));
+                            rest = &rest[end..];
+                        }
+                        None => {
+                            buffer.push_str(rest);
+                            buffer.push('\n');
+                            return records;
+                        }
+                    },
+                    None => match rest.find(EVENT_START) {
+                        Some(start) => {
+                            let before = rest[..start].trim();
+                            if !before.is_empty() {
+                                records.push(parse_plain_line(self.stream, before));
+                            }
+                            self.buffer = Some(String::new());
+                            rest = &rest[start..];
+                        }
+                        None => {
+                            if !rest.trim().is_empty() {
+                                records.push(parse_plain_line(self.stream, rest));
+                            }
+                            return records;
+                        }
+                    },
+                }
+            }
+        }
+
+        /// Flushes an unfinished event as plain lines, e.g. when the game was killed
+        pub fn finish(&mut self) -> Vec<GameLogRecord> {
+            match self.buffer.take() {
+                Some(buffer) => buffer
+                    .lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(|line| GameLogRecord::plain(self.stream, line))
+                    .collect(),
+                None => Vec::new(),
+            }
+        }
+
+        /// Parses a complete event, falling back to its raw lines if it is malformed
+        fn finish_event(&self, xml: &str) -> Vec<GameLogRecord> {
+            match parse_log4j_event(self.stream, xml) {
+                Some(record) => vec![record],
+                None => xml
+                    .lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(|line| GameLogRecord::plain(self.stream, line))
+                    .collect(),
+            }
+        }
+    }
+
+    /// Parses a single `<log4j:Event>` element
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - The stream the event was read from
+    /// * `xml` - The event element, from `<log4j:Event` to `</log4j:Event>`
+    ///
+    /// # Returns
+    ///
+    /// * The record, or None if the element is not a valid event
+    pub fn parse_log4j_event(stream: OutputStream, xml: &str) -> Option<GameLogRecord> {
+        let mut reader = Reader::from_str(xml);
+        let mut record = GameLogRecord {
+            xml: true,
+            ..GameLogRecord::plain(stream, "")
+        };
+        let mut field: Option<String> = None;
+        let mut text = String::new();
+        let mut seen_event = false;
+
+        loop {
+            match reader.read_event().ok()? {
+                Event::Start(start) => {
+                    let name = local_name(&start);
+                    if name == "Event" {
+                        read_event_attributes(&start, &mut record)?;
+                        seen_event = true;
+                    } else {
+                        field = Some(name);
+                        text.clear();
+                    }
+                }
+                Event::Empty(start) if local_name(&start) == "Event" => {
+                    read_event_attributes(&start, &mut record)?;
+                    return Some(record);
+                }
+                Event::Text(content) => text.push_str(&content.into_inner()),
+                Event::CData(content) => text.push_str(&content.into_inner()),
+                Event::GeneralRef(reference) => {
+                    let entity = format!("&{};", reference.into_inner());
+                    text.push_str(&unescape(&entity).ok()?);
+                }
+                Event::End(end) => {
+                    let name = end.local_name().as_ref().to_string();
+                    if name == "Event" {
+                        return seen_event.then_some(record);
+                    }
+                    match field.take().as_deref() {
+                        Some("Message") => record.message = text.clone(),
+                        Some("Throwable") => record.throwable = Some(text.clone()),
+                        _ => {}
+                    }
+                }
+                Event::Eof => return None,
+                _ => {}
+            }
+        }
+    }
+
+    /// Parses a line of plain-text output
+    ///
+    /// Lines in the `[12:34:56] [Render thread/INFO]: message` layout get their time,
+    /// thread and level; other lines keep only the message.
+    pub fn parse_plain_line(stream: OutputStream, line: &str) -> GameLogRecord {
+        let Some(captures) = PLAIN_LINE.captures(line) else {
+            return GameLogRecord::plain(stream, line);
+        };
+        GameLogRecord {
+            time: Some(captures[1].to_string()),
+            thread: Some(captures[2].to_string()),
+            level: LogLevel::parse(&captures[3]),
+            logger: captures.get(4).map(|m| m.as_str().to_string()),
+            message: captures[5].to_string(),
+            ..GameLogRecord::plain(stream, line)
+        }
+    }
+
+    /// Reads a stream to its end, calling back for every record
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The stream to read, usually the game's stdout or stderr
+    /// * `stream` - Which stream `reader` is
+    /// * `callback` - Called for every record in order
+    pub fn read_output<R: Read>(
+        reader: R,
+        stream: OutputStream,
+        mut callback: impl FnMut(GameLogRecord),
+    ) {
+        let mut parser = GameOutputParser::new(stream);
+        for line in BufReader::new(reader).split(b'\n') {
+            let Ok(line) = line else {
+                break;
+            };
+            let line = String::from_utf8_lossy(&line);
+            for record in parser.push_line(line.trim_end_matches('\r')) {
+                callback(record);
+            }
+        }
+        for record in parser.finish() {
+            callback(record);
+        }
+    }
+
+    /// Reads stdout and stderr on background threads and streams their records
+    ///
+    /// The receiver yields records until both streams are closed, which happens when
+    /// the game exits. Records of one stream stay in order; the two streams are
+    /// interleaved as they arrive.
+    ///
+    /// # Arguments
+    ///
+    /// * `stdout` - The game's standard output, if it was captured
+    /// * `stderr` - The game's standard error, if it was captured
+    pub fn stream_output<O, E>(stdout: Option<O>, stderr: Option<E>) -> Receiver<GameLogRecord>
+    where
+        O: Read + Send + 'static,
+        E: Read + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        if let Some(stdout) = stdout {
+            spawn_reader(stdout, OutputStream::Stdout, sender.clone());
+        }
+        if let Some(stderr) = stderr {
+            spawn_reader(stderr, OutputStream::Stderr, sender);
+        }
+        receiver
+    }
+
+    /// Reads a stream on a new thread, sending its records until the receiver hangs up
+    fn spawn_reader<R: Read + Send + 'static>(
+        reader: R,
+        stream: OutputStream,
+        sender: Sender<GameLogRecord>,
+    ) {
+        thread::spawn(move || {
+            read_output(reader, stream, |record| {
+                let _ = sender.send(record);
+            });
+        });
+    }
+
+    /// Reads the logger, timestamp, level and thread attributes of an event
+    fn read_event_attributes(start: &BytesStart, record: &mut GameLogRecord) -> Option<()> {
+        for attribute in start.attributes() {
+            let attribute = attribute.ok()?;
+            let value = attribute
+                .normalized_value(XmlVersion::Implicit1_0)
+                .ok()?
+                .into_owned();
+            match attribute.key.local_name().as_ref() {
+                "logger" => record.logger = Some(value),
+                "timestamp" => record.timestamp = value.parse().ok(),
+                "level" => record.level = LogLevel::parse(&value),
+                "thread" => record.thread = Some(value),
+                _ => {}
+            }
+        }
+        Some(())
+    }
+
+    /// Gets the local name of an element, without its namespace prefix
+    fn local_name(start: &BytesStart) -> String {
+        start.local_name().as_ref().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::game_output::{
+        GameOutputParser, LogLevel, OutputStream, parse_plain_line, read_output,
+    };
+
+    const XML_OUTPUT: &str = r#"<log4j:Event logger="net.minecraft.client.Minecraft" timestamp="1700000000123" level="INFO" thread="Render thread">
+  <log4j:Message><![CDATA[Setting user: Steve]]></log4j:Message>
+</log4j:Event>
+Plain print from a mod
+<log4j:Event logger="net.minecraft.server.MinecraftServer" timestamp="1700000000456" level="ERROR" thread="Server thread">
+  <log4j:Message><![CDATA[Encountered an unexpected exception]]></log4j:Message>
+  <log4j:Throwable><![CDATA[java.lang.IllegalStateException: broken
+	at net.minecraft.server.MinecraftServer.run(MinecraftServer.java:100)
+]]></log4j:Throwable>
+</log4j:Event>
+"#;
+
+    /// Tests parsing XML events mixed with plain lines
+    #[test]
+    fn test_parse_xml_events() {
+        let mut records = Vec::new();
+        read_output(XML_OUTPUT.as_bytes(), OutputStream::Stdout, |record| {
+            records.push(record)
+        });
+
+        assert_eq!(records.len(), 3);
+        assert!(records[0].xml);
+        assert_eq!(records[0].timestamp, Some(1700000000123));
+        assert_eq!(records[0].level, Some(LogLevel::Info));
+        assert_eq!(records[0].thread.as_deref(), Some("Render thread"));
+        assert_eq!(
+            records[0].logger.as_deref(),
+            Some("net.minecraft.client.Minecraft")
+        );
+        assert_eq!(records[0].message, "Setting user: Steve");
+        assert!(records[0].throwable.is_none());
+
+        assert!(!records[1].xml);
+        assert_eq!(records[1].message, "Plain print from a mod");
+
+        assert_eq!(records[2].level, Some(LogLevel::Error));
+        assert!(
+            records[2]
+                .throwable
+                .as_deref()
+                .unwrap()
+                .starts_with("java.lang.IllegalStateException: broken\n\tat ")
+        );
+    }
+
+    /// Tests the plain-text fallback of versions without XML logging
+    #[test]
+    fn test_parse_plain_line() {
+        let record = parse_plain_line(
+            OutputStream::Stdout,
+            "[12:34:56] [Client thread/WARN]: Skipping bad option: lastServer:",
+        );
+        assert_eq!(record.time.as_deref(), Some("12:34:56"));
+        assert_eq!(record.thread.as_deref(), Some("Client thread"));
+        assert_eq!(record.level, Some(LogLevel::Warn));
+        assert_eq!(record.message, "Skipping bad option: lastServer:");
+
+        let record = parse_plain_line(OutputStream::Stderr, "Exception in thread \"main\"");
+        assert_eq!(record.level, None);
+        assert_eq!(record.message, "Exception in thread \"main\"");
+    }
+
+    /// Tests escaped attributes and an event cut off by the game exiting
+    #[test]
+    fn test_escaped_and_unfinished_events() {
+        let mut parser = GameOutputParser::new(OutputStream::Stdout);
+        let records = parser.push_line(
+            r#"<log4j:Event logger="a&amp;b" timestamp="1" level="DEBUG" thread="main"><log4j:Message>x &lt; y</log4j:Message></log4j:Event>"#,
+        );
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].logger.as_deref(), Some("a&b"));
+        assert_eq!(records[0].message, "x < y");
+
+        assert!(
+            parser
+                .push_line(r#"<log4j:Event logger="a" timestamp="2" level="INFO" thread="main">"#)
+                .is_empty()
+        );
+        let records = parser.finish();
+        assert_eq!(records.len(), 1);
+        assert!(!records[0].xml);
+    }
+}
@@ -0,0 +1,363 @@
+pub mod launch_features {
+    use crate::craft_launcher::core::version::base_version::ArgumentValue;
+    use crate::craft_launcher::core::version::launch_arguments::launch_arguments::ArgumentPlaceholders;
+    use crate::craft_launcher::core::version::rules::rules::RuleContext;
+    use crate::craft_launcher::core::version::version_resolver::version_resolver::ResolvedVersion;
+    use std::error::Error;
+    use std::fmt;
+
+    /// Feature of version arguments that adds `--demo`
+    pub const DEMO_FEATURE: &str = "is_demo_user";
+
+    /// Feature of version arguments that adds `--width` and `--height`
+    pub const CUSTOM_RESOLUTION_FEATURE: &str = "has_custom_resolution";
+
+    /// Port of a Minecraft server when the address has none
+    pub const DEFAULT_SERVER_PORT: u16 = 25565;
+
+    /// A world, server or realm the game opens right after starting
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum QuickPlay {
+        /// Opens the singleplayer world with this folder name
+        Singleplayer(String),
+
+        /// Joins the server at this address (e.g. "mc.example.com:25565")
+        Multiplayer(String),
+
+        /// Joins the realm with this ID
+        Realms(String),
+    }
+
+    impl QuickPlay {
+        /// Gets the rule feature that enables this kind of Quick Play
+        pub fn feature(&self) -> &'static str {
+            match self {
+                QuickPlay::Singleplayer(_) => "is_quick_play_singleplayer",
+                QuickPlay::Multiplayer(_) => "is_quick_play_multiplayer",
+                QuickPlay::Realms(_) => "is_quick_play_realms",
+            }
+        }
+
+        /// Gets the placeholder the version arguments take the value from
+        pub fn placeholder(&self) -> &'static str {
+            match self {
+                QuickPlay::Singleplayer(_) => "quickPlaySingleplayer",
+                QuickPlay::Multiplayer(_) => "quickPlayMultiplayer",
+                QuickPlay::Realms(_) => "quickPlayRealms",
+            }
+        }
+
+        /// Gets the world name, server address or realm ID
+        pub fn value(&self) -> &str {
+            match self {
+                QuickPlay::Singleplayer(value)
+                | QuickPlay::Multiplayer(value)
+                | QuickPlay::Realms(value) => value,
+            }
+        }
+    }
+
+    /// Size of the game window
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Resolution {
+        /// Width in pixels
+        pub width: u32,
+
+        /// Height in pixels
+        pub height: u32,
+    }
+
+    impl Resolution {
+        /// Creates a resolution
+        ///
+        /// # Arguments
+        ///
+        /// * `width` - Width in pixels
+        /// * `height` - Height in pixels
+        pub fn new(width: u32, height: u32) -> Self {
+            Resolution { width, height }
+        }
+    }
+
+    /// A requested feature that the version has no arguments for
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct UnsupportedFeature {
+        /// The version that was launched
+        pub version_id: String,
+
+        /// The rule feature it does not declare
+        pub feature: String,
+    }
+
+    impl fmt::Display for UnsupportedFeature {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "Version {} does not support {}",
+                self.version_id, self.feature
+            )
+        }
+    }
+
+    impl Error for UnsupportedFeature {}
+
+    /// Quick Play, window size and demo mode requested for a launch
+    ///
+    /// Modern versions gate their arguments behind `features` rules, so requesting a
+    /// feature enables the rule and supplies its placeholder values. Versions that do
+    /// not declare a feature get the legacy arguments instead: `--server`/`--port` for
+    /// multiplayer, `--width`/`--height` and `--demo`.
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct LaunchFeatures {
+        /// World, server or realm to open right after starting
+        pub quick_play: Option<QuickPlay>,
+
+        /// Size of the game window
+        pub resolution: Option<Resolution>,
+
+        /// Launch in demo mode
+        pub demo: bool,
+    }
+
+    impl LaunchFeatures {
+        /// Creates a set with no features requested
+        pub fn new() -> Self {
+            LaunchFeatures::default()
+        }
+
+        /// Sets the Quick Play target.
+        ///
+        /// # Arguments
+        ///
+        /// * `quick_play` - The world, server or realm to open
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_quick_play(mut self, quick_play: QuickPlay) -> Self {
+            self.quick_play = Some(quick_play);
+            self
+        }
+
+        /// Sets the size of the game window.
+        ///
+        /// # Arguments
+        ///
+        /// * `width` - Width in pixels
+        /// * `height` - Height in pixels
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_resolution(mut self, width: u32, height: u32) -> Self {
+            self.resolution = Some(Resolution::new(width, height));
+            self
+        }
+
+        /// Sets whether the game runs in demo mode.
+        ///
+        /// # Arguments
+        ///
+        /// * `demo` - Whether to launch in demo mode
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_demo(mut self, demo: bool) -> Self {
+            self.demo = demo;
+            self
+        }
+
+        /// Enables the rule features of the requested features the version declares
+        ///
+        /// # Arguments
+        ///
+        /// * `version` - The resolved version
+        /// * `context` - The context to add the features to
+        pub fn rule_context(
+            &self,
+            version: &ResolvedVersion,
+            context: &RuleContext,
+        ) -> RuleContext {
+            let mut context = context.clone();
+            for feature in self.requested_features() {
+                if declares_feature(&version.game_arguments, feature) {
+                    context = context.with_feature(feature, true);
+                }
+            }
+            context
+        }
+
+        /// Adds the placeholder values of the requested features
+        ///
+        /// # Arguments
+        ///
+        /// * `placeholders` - The placeholders of the launch
+        pub fn placeholders(&self, placeholders: ArgumentPlaceholders) -> ArgumentPlaceholders {
+            let mut placeholders = placeholders;
+            if let Some(resolution) = self.resolution {
+                placeholders = placeholders
+                    .with("resolution_width", &resolution.width.to_string())
+                    .with("resolution_height", &resolution.height.to_string());
+            }
+            if let Some(quick_play) = &self.quick_play {
+                placeholders = placeholders.with(quick_play.placeholder(), quick_play.value());
+            }
+            placeholders
+        }
+
+        /// Gets the game arguments of requested features the version does not declare
+        ///
+        /// # Arguments
+        ///
+        /// * `version` - The resolved version
+        ///
+        /// # Returns
+        ///
+        /// * `Result<Vec<String>, UnsupportedFeature>` - The arguments to append, or an
+        ///   error for a singleplayer or realms target, which legacy versions cannot open
+        pub fn legacy_arguments(
+            &self,
+            version: &ResolvedVersion,
+        ) -> Result<Vec<String>, UnsupportedFeature> {
+            let declares = |feature| declares_feature(&version.game_arguments, feature);
+            let mut arguments = Vec::new();
+
+            if let Some(quick_play) = &self.quick_play
+                && !declares(quick_play.feature())
+            {
+                match quick_play {
+                    QuickPlay::Multiplayer(address) => {
+                        let (host, port) = split_server_address(address);
+                        arguments.extend([
+                            "--server".to_string(),
+                            host,
+                            "--port".to_string(),
+                            port.to_string(),
+                        ]);
+                    }
+                    QuickPlay::Singleplayer(_) | QuickPlay::Realms(_) => {
+                        return Err(UnsupportedFeature {
+                            version_id: version.id.clone(),
+                            feature: quick_play.feature().to_string(),
+                        });
+                    }
+                }
+            }
+            if let Some(resolution) = self.resolution
+                && !declares(CUSTOM_RESOLUTION_FEATURE)
+            {
+                arguments.extend([
+                    "--width".to_string(),
+                    resolution.width.to_string(),
+                    "--height".to_string(),
+                    resolution.height.to_string(),
+                ]);
+            }
+            if self.demo && !declares(DEMO_FEATURE) {
+                arguments.push("--demo".to_string());
+            }
+            Ok(arguments)
+        }
+
+        /// Gets the rule features of everything requested
+        fn requested_features(&self) -> Vec<&'static str> {
+            let mut features = Vec::new();
+            if let Some(quick_play) = &self.quick_play {
+                features.push(quick_play.feature());
+            }
+            if self.resolution.is_some() {
+                features.push(CUSTOM_RESOLUTION_FEATURE);
+            }
+            if self.demo {
+                features.push(DEMO_FEATURE);
+            }
+            features
+        }
+    }
+
+    /// Checks whether any argument is gated behind a rule feature
+    ///
+    /// # Arguments
+    ///
+    /// * `arguments` - The `game` or `jvm` arguments of a version
+    /// * `feature` - The feature name (e.g. "is_quick_play_multiplayer")
+    pub fn declares_feature(arguments: &[ArgumentValue], feature: &str) -> bool {
+        arguments.iter().any(|argument| match argument {
+            ArgumentValue::String(_) => false,
+            ArgumentValue::RuleArgument { rules, .. } => rules.iter().any(|rule| {
+                rule.features
+                    .as_ref()
+                    .is_some_and(|features| features.contains_key(feature))
+            }),
+        })
+    }
+
+    /// Splits a server address into host and port
+    ///
+    /// Accepts `host`, `host:port` and `[ipv6]:port`; the port defaults to 25565.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The server address
+    pub fn split_server_address(address: &str) -> (String, u16) {
+        let address = address.trim();
+        if let Some(rest) = address.strip_prefix('[')
+            && let Some((host, after)) = rest.split_once(']')
+        {
+            let port = after
+                .strip_prefix(':')
+                .and_then(|port| port.parse().ok())
+                .unwrap_or(DEFAULT_SERVER_PORT);
+            return (host.to_string(), port);
+        }
+        match address.rsplit_once(':') {
+            Some((host, port)) if !host.contains(':') => match port.parse() {
+                Ok(port) => (host.to_string(), port),
+                Err(_) => (address.to_string(), DEFAULT_SERVER_PORT),
+            },
+            _ => (address.to_string(), DEFAULT_SERVER_PORT),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::launch_features::{LaunchFeatures, QuickPlay, split_server_address};
+
+    /// Tests splitting server addresses
+    #[test]
+    fn test_split_server_address() {
+        assert_eq!(
+            split_server_address("mc.example.com"),
+            ("mc.example.com".to_string(), 25565)
+        );
+        assert_eq!(
+            split_server_address("mc.example.com:25570"),
+            ("mc.example.com".to_string(), 25570)
+        );
+        assert_eq!(
+            split_server_address("[::1]:25570"),
+            ("::1".to_string(), 25570)
+        );
+        assert_eq!(split_server_address("::1"), ("::1".to_string(), 25565));
+    }
+
+    /// Tests the placeholder values of requested features
+    #[test]
+    fn test_placeholders() {
+        let features = LaunchFeatures::new()
+            .with_quick_play(QuickPlay::Singleplayer("New World".to_string()))
+            .with_resolution(1280, 720);
+        let placeholders = features.placeholders(Default::default());
+
+        assert_eq!(
+            placeholders.substitute_all(&[
+                "${quickPlaySingleplayer}".to_string(),
+                "${resolution_width}x${resolution_height}".to_string(),
+                "${quickPlayMultiplayer}".to_string(),
+            ]),
+            vec!["New World", "1280x720", "${quickPlayMultiplayer}"]
+        );
+    }
+}
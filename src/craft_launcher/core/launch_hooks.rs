@@ -0,0 +1,135 @@
+pub mod launch_hooks {
+    use crate::craft_launcher::core::version::launch_arguments::launch_arguments::ArgumentPlaceholders;
+    use std::collections::BTreeMap;
+    use std::error::Error;
+    use std::fmt;
+    use std::io;
+    use std::path::Path;
+    use std::process::{Command, ExitStatus};
+
+    /// When a hook runs
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum HookStage {
+        /// Before the game is spawned; a failure aborts the launch
+        PreLaunch,
+
+        /// After the game has exited
+        PostExit,
+    }
+
+    impl fmt::Display for HookStage {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                HookStage::PreLaunch => write!(f, "pre-launch"),
+                HookStage::PostExit => write!(f, "post-exit"),
+            }
+        }
+    }
+
+    /// Error type for hook commands
+    #[derive(Debug)]
+    pub enum HookError {
+        /// A hook has no program to run
+        EmptyCommand(HookStage),
+
+        /// A hook could not be started
+        SpawnFailed {
+            stage: HookStage,
+            command: Vec<String>,
+            error: io::Error,
+        },
+
+        /// A hook exited unsuccessfully
+        Failed {
+            stage: HookStage,
+            command: Vec<String>,
+            status: ExitStatus,
+        },
+    }
+
+    impl fmt::Display for HookError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                HookError::EmptyCommand(stage) => write!(f, "A {} hook has no command", stage),
+                HookError::SpawnFailed {
+                    stage,
+                    command,
+                    error,
+                } => write!(
+                    f,
+                    "Failed to start {} hook `{}`: {}",
+                    stage,
+                    command.join(" "),
+                    error
+                ),
+                HookError::Failed {
+                    stage,
+                    command,
+                    status,
+                } => write!(
+                    f,
+                    "The {} hook `{}` failed with {}",
+                    stage,
+                    command.join(" "),
+                    status
+                ),
+            }
+        }
+    }
+
+    impl Error for HookError {}
+
+    /// Runs hook commands one after another, stopping at the first failure
+    ///
+    /// Placeholders such as `${instance_dir}` and `${version_id}` are substituted in
+    /// every argument before the hook runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `stage` - When the hooks run, used in errors
+    /// * `hooks` - The commands, each a program followed by its arguments
+    /// * `placeholders` - Values substituted into the commands
+    /// * `working_dir` - Directory the hooks run in
+    /// * `env` - Environment variables to set (Some) or remove (None)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), HookError>` - Ok if every hook succeeded
+    pub fn run_hooks(
+        stage: HookStage,
+        hooks: &[Vec<String>],
+        placeholders: &ArgumentPlaceholders,
+        working_dir: &Path,
+        env: &BTreeMap<String, Option<String>>,
+    ) -> Result<(), HookError> {
+        for hook in hooks {
+            let command = placeholders.substitute_all(hook);
+            let Some(program) = command.first() else {
+                return Err(HookError::EmptyCommand(stage));
+            };
+
+            let mut process = Command::new(program);
+            process.args(&command[1..]).current_dir(working_dir);
+            for (key, value) in env {
+                match value {
+                    Some(value) => process.env(key, value),
+                    None => process.env_remove(key),
+                };
+            }
+
+            let status = process.status().map_err(|error| HookError::SpawnFailed {
+                stage,
+                command: command.clone(),
+                error,
+            })?;
+            if !status.success() {
+                return Err(HookError::Failed {
+                    stage,
+                    command,
+                    status,
+                });
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,587 @@
+pub mod launch_plan {
+    use crate::craft_launcher::core::assets::assets_parser::assets_parser::AssetsIndex;
+    use crate::craft_launcher::core::engine::engine::{
+        Account, Instance, LaunchError, LaunchOptions, available_runtimes, command_builder,
+        hook_placeholders, log_config_path,
+    };
+    use crate::craft_launcher::core::version::classpath::classpath::{
+        ClasspathOptions, build_classpath,
+    };
+    use crate::craft_launcher::core::version::library_installer::library_installer::LibraryInstaller;
+    use crate::craft_launcher::core::version::rules::rules::rules_allow;
+    use crate::craft_launcher::core::version::version_resolver::version_resolver::{
+        ResolvedVersion, resolve_version,
+    };
+    use crate::craft_launcher::java::java_selector::java_selector::{JavaRequirement, select_java};
+    use crate::craft_launcher::java::library_extractor::library_extractor::{
+        native_directory_for_libraries, select_native_libraries,
+    };
+    use crate::craft_launcher::java::runtime_installer::runtime_installer::runtime_executable;
+    use crate::craft_launcher::utils::file_operations::file_utils;
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::io;
+    use std::path::{Path, PathBuf};
+
+    /// A file a launch would download
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct PlannedDownload {
+        /// The URL the file would be downloaded from
+        pub url: String,
+
+        /// Where the file would be stored
+        pub path: PathBuf,
+
+        /// The expected SHA-1 hash, if known
+        pub sha1: Option<String>,
+
+        /// The expected size in bytes, if known
+        pub size: Option<i64>,
+    }
+
+    /// Everything a launch would do, computed without changing anything on disk
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct LaunchPlan {
+        /// The version being launched
+        pub version_id: String,
+
+        /// Path to the java executable
+        pub java: PathBuf,
+
+        /// Major version of the java executable
+        pub java_major_version: u32,
+
+        /// Mojang runtime component that would be installed first, if no suitable Java exists
+        pub java_install: Option<String>,
+
+        /// Working directory of the game
+        pub working_dir: PathBuf,
+
+        /// Environment variables set for the game
+        pub env: BTreeMap<String, String>,
+
+        /// Environment variables removed from the game
+        pub env_unset: Vec<String>,
+
+        /// Commands run before the game, with placeholders substituted
+        pub pre_launch_hooks: Vec<Vec<String>>,
+
+        /// Commands run after the game, with placeholders other than `${exit_code}` substituted
+        pub post_exit_hooks: Vec<Vec<String>>,
+
+        /// The full command line, starting with any wrappers and then the java executable
+        pub argv: Vec<String>,
+
+        /// Classpath entries in order
+        pub classpath: Vec<PathBuf>,
+
+        /// Module path entries in order
+        pub module_path: Vec<PathBuf>,
+
+        /// The natives directory; None if native jars still have to be downloaded,
+        /// in which case `${natives_directory}` is left in `argv`
+        pub natives_dir: Option<PathBuf>,
+
+        /// Files that are missing or corrupt and would be downloaded
+        pub downloads: Vec<PlannedDownload>,
+    }
+
+    impl LaunchPlan {
+        /// Exports the plan as a POSIX shell script
+        ///
+        /// The script changes into the working directory, exports the environment, runs
+        /// the pre-launch hooks and execs the game. If there are post-exit hooks, the
+        /// game is run as a child instead and the script exits with its status. It
+        /// contains the access token of the account, so it should be treated like a
+        /// credential. Files listed in `downloads` are not fetched by it.
+        ///
+        /// # Returns
+        ///
+        /// * `io::Result<String>` - The script, or an `InvalidInput` error if an
+        ///   environment variable name is not a valid shell identifier
+        pub fn to_shell_script(&self) -> io::Result<String> {
+            let mut script = String::new();
+            script.push_str("#!/bin/sh\n");
+            script.push_str(&format!("# Launch script for {}\n", self.version_id));
+            if !self.downloads.is_empty() {
+                script.push_str(&format!(
+                    "# Warning: {} files were missing when this script was generated\n",
+                    self.downloads.len()
+                ));
+            }
+            script.push_str("set -e\n");
+            script.push_str(&format!(
+                "cd {}\n",
+                shell_quote(&self.working_dir.to_string_lossy())
+            ));
+            for (name, value) in &self.env {
+                check_env_name(name)?;
+                script.push_str(&format!("export {}={}\n", name, shell_quote(value)));
+            }
+            for name in &self.env_unset {
+                check_env_name(name)?;
+                script.push_str(&format!("unset {}\n", name));
+            }
+            for hook in &self.pre_launch_hooks {
+                let quoted: Vec<String> = hook.iter().map(|arg| shell_quote(arg)).collect();
+                script.push_str(&format!("{}\n", quoted.join(" ")));
+            }
+
+            let command: Vec<String> = self.argv.iter().map(|arg| shell_quote(arg)).collect();
+            if self.post_exit_hooks.is_empty() {
+                script.push_str(&format!("exec \\\n  {}\n", command.join(" \\\n  ")));
+            } else {
+                script.push_str("set +e\n");
+                script.push_str(&format!("{}\n", command.join(" \\\n  ")));
+                script.push_str("exit_code=$?\n");
+                for hook in &self.post_exit_hooks {
+                    let quoted: Vec<String> =
+                        hook.iter().map(|arg| quote_hook_argument(arg)).collect();
+                    script.push_str(&format!("{}\n", quoted.join(" ")));
+                }
+                script.push_str("exit $exit_code\n");
+            }
+            Ok(script)
+        }
+
+        /// Writes the shell script to a file and makes it executable
+        ///
+        /// # Arguments
+        ///
+        /// * `path` - Where to write the script
+        pub fn write_shell_script(&self, path: &Path) -> io::Result<()> {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, self.to_shell_script()?)?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Computes what `launch` would do, without downloading, extracting or spawning anything
+    ///
+    /// # Arguments
+    ///
+    /// * `instance` - The instance to launch
+    /// * `account` - The player to launch as
+    /// * `options` - Options of this launch
+    ///
+    /// # Returns
+    ///
+    /// * `Result<LaunchPlan, LaunchError>` - The plan or an error
+    pub fn plan_launch(
+        instance: &Instance,
+        account: &Account,
+        options: &LaunchOptions,
+    ) -> Result<LaunchPlan, LaunchError> {
+        let root_dir = instance.root_dir.as_path();
+        let version = resolve_version(root_dir, &instance.version_id, options.conflict_policy)
+            .map_err(|e| LaunchError::VersionResolution(e.to_string()))?;
+
+        let downloads = if options.install_files {
+            planned_downloads(&version, root_dir, options)?
+        } else {
+            Vec::new()
+        };
+
+        let natives_dir =
+            match native_directory_for_libraries(root_dir, &version.libraries, &options.context) {
+                Ok(Some(dir)) => Some(dir),
+                Ok(None) => Some(root_dir.join("bin").join("empty")),
+                Err(_) => None,
+            };
+
+        let runtimes = available_runtimes(instance, options);
+        let strict = instance.java.clone().with_strict(true);
+        let requirement = JavaRequirement::for_version(&version);
+        let (java, java_major_version, java_install) =
+            match select_java(&version, &runtimes, &strict) {
+                Ok(selection) => (
+                    selection.runtime.executable,
+                    selection.runtime.major_version,
+                    None,
+                ),
+                Err(error) => match &requirement.component {
+                    Some(component)
+                        if options.install_java
+                            && instance.java.java_path.is_none()
+                            && instance.java.major_version.is_none() =>
+                    {
+                        let directory = root_dir.join("runtime").join(component);
+                        (
+                            runtime_executable(&directory),
+                            requirement.major_version,
+                            Some(component.clone()),
+                        )
+                    }
+                    _ if instance.java.strict => return Err(LaunchError::Java(error)),
+                    _ => {
+                        let selection = select_java(&version, &runtimes, &instance.java)
+                            .map_err(LaunchError::Java)?;
+                        (
+                            selection.runtime.executable,
+                            selection.runtime.major_version,
+                            None,
+                        )
+                    }
+                },
+            };
+
+        let classpath = build_classpath(
+            &version,
+            root_dir,
+            &ClasspathOptions {
+                context: options.context.clone(),
+                ..ClasspathOptions::default()
+            },
+        )
+        .map_err(LaunchError::Classpath)?;
+
+        let (builder, module_path) = command_builder(
+            &version,
+            instance,
+            account,
+            options,
+            &java,
+            natives_dir.as_deref(),
+            &classpath,
+        )?;
+
+        let placeholders = hook_placeholders(&version, instance);
+        Ok(LaunchPlan {
+            version_id: version.id.clone(),
+            java,
+            java_major_version,
+            java_install,
+            working_dir: instance.game_dir.clone(),
+            env: instance
+                .env
+                .iter()
+                .filter_map(|(name, value)| Some((name.clone(), value.clone()?)))
+                .collect(),
+            env_unset: instance
+                .env
+                .iter()
+                .filter(|(_, value)| value.is_none())
+                .map(|(name, _)| name.clone())
+                .collect(),
+            pre_launch_hooks: instance
+                .pre_launch_hooks
+                .iter()
+                .map(|hook| placeholders.substitute_all(hook))
+                .collect(),
+            post_exit_hooks: instance
+                .post_exit_hooks
+                .iter()
+                .map(|hook| placeholders.substitute_all(hook))
+                .collect(),
+            argv: builder.build(),
+            classpath,
+            module_path,
+            natives_dir,
+            downloads,
+        })
+    }
+
+    /// Lists the client jar, libraries, native jars and assets that are missing or corrupt
+    ///
+    /// Libraries are checked the way `LibraryInstaller` would install them, and are listed
+    /// with the first URL it would try. Asset objects are only listed when the asset index
+    /// is already present.
+    fn planned_downloads(
+        version: &ResolvedVersion,
+        root_dir: &Path,
+        options: &LaunchOptions,
+    ) -> Result<Vec<PlannedDownload>, LaunchError> {
+        let mut downloads = Vec::new();
+        let libraries_dir = root_dir.join("libraries");
+
+        if let Some(client) = &version.client_download {
+            let path = root_dir
+                .join("versions")
+                .join(&version.jar)
+                .join(format!("{}.jar", version.jar));
+            push_if_needed(
+                &mut downloads,
+                &client.url,
+                path,
+                Some(&client.sha1),
+                Some(client.size),
+            );
+        }
+
+        let libraries: Vec<_> = version
+            .libraries
+            .iter()
+            .filter(|library| rules_allow(library.rules(), &options.context))
+            .cloned()
+            .collect();
+        let installer = LibraryInstaller::new(root_dir);
+        for target in installer.targets(&libraries)? {
+            if !installer.is_installed(&target) {
+                downloads.push(PlannedDownload {
+                    url: target.urls.first().cloned().unwrap_or_default(),
+                    path: installer.libraries_dir().join(&target.path),
+                    sha1: target.sha1,
+                    size: (target.size > 0).then_some(target.size),
+                });
+            }
+        }
+
+        if let Some(logging) = &version.logging
+            && options.use_log_config
+        {
+            push_if_needed(
+                &mut downloads,
+                &logging.file.url,
+                log_config_path(root_dir, logging),
+                Some(&logging.file.sha1),
+                Some(logging.file.size),
+            );
+        }
+
+        for native in select_native_libraries(&version.libraries, &options.context) {
+            if let Some(url) = &native.url {
+                push_if_needed(
+                    &mut downloads,
+                    url,
+                    libraries_dir.join(&native.path),
+                    native.sha1.as_ref(),
+                    None,
+                );
+            }
+        }
+
+        if let Some(asset_index) = &version.asset_index {
+            let index_path = root_dir
+                .join("assets")
+                .join("indexes")
+                .join(format!("{}.json", asset_index.id));
+            push_if_needed(
+                &mut downloads,
+                &asset_index.url,
+                index_path.clone(),
+                Some(&asset_index.sha1),
+                Some(asset_index.size),
+            );
+
+            let index = fs::read_to_string(&index_path)
+                .ok()
+                .and_then(|json| AssetsIndex::from_json(&json).ok());
+            if let Some(index) = index {
+                let resources_url = options.resources_url.trim_end_matches('/');
+                let objects_dir = root_dir.join("assets").join("objects");
+                for object in index.objects.values() {
+                    let prefix = object.hash.get(..2).unwrap_or_default();
+                    let path = objects_dir.join(prefix).join(&object.hash);
+                    // Checking the size keeps dry runs fast for thousands of objects
+                    let present = fs::metadata(&path).is_ok_and(|m| m.len() == object.size);
+                    if !present {
+                        downloads.push(PlannedDownload {
+                            url: format!("{}/{}/{}", resources_url, prefix, object.hash),
+                            path,
+                            sha1: Some(object.hash.clone()),
+                            size: Some(object.size as i64),
+                        });
+                    }
+                }
+            }
+        }
+
+        downloads.sort_by(|a, b| a.path.cmp(&b.path));
+        downloads.dedup_by(|a, b| a.path == b.path);
+        Ok(downloads)
+    }
+
+    /// Adds a download unless the file exists with the expected hash
+    fn push_if_needed(
+        downloads: &mut Vec<PlannedDownload>,
+        url: &str,
+        path: PathBuf,
+        sha1: Option<&String>,
+        size: Option<i64>,
+    ) {
+        let present = match (fs::read(&path), sha1) {
+            (Ok(bytes), Some(sha1)) => file_utils::sha1_hex(&bytes) == *sha1,
+            (Ok(_), None) => true,
+            (Err(_), _) => false,
+        };
+        if !present {
+            downloads.push(PlannedDownload {
+                url: url.to_string(),
+                path,
+                sha1: sha1.cloned(),
+                size,
+            });
+        }
+    }
+
+    /// Quotes a string for a POSIX shell
+    ///
+    /// Strings of safe characters are left as they are; everything else is wrapped in
+    /// single quotes, with embedded single quotes written as `'\''`.
+    pub fn shell_quote(value: &str) -> String {
+        let is_safe = !value.is_empty()
+            && value.chars().all(|c| {
+                c.is_ascii_alphanumeric()
+                    || matches!(c, '-' | '_' | '.' | '/' | ':' | '=' | ',' | '+' | '@')
+            });
+        if is_safe {
+            value.to_string()
+        } else {
+            format!("'{}'", value.replace('\'', "'\\''"))
+        }
+    }
+
+    /// Checks that an environment variable name matches `[A-Za-z_][A-Za-z0-9_]*`
+    fn check_env_name(name: &str) -> io::Result<()> {
+        let mut chars = name.chars();
+        let is_valid = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if is_valid {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid environment variable name: {}", name),
+            ))
+        }
+    }
+
+    /// Quotes a post-exit hook argument, expanding `${exit_code}` to the game's status
+    fn quote_hook_argument(value: &str) -> String {
+        if !value.contains("${exit_code}") {
+            return shell_quote(value);
+        }
+        value
+            .split("${exit_code}")
+            .map(|part| {
+                if part.is_empty() {
+                    String::new()
+                } else {
+                    shell_quote(part)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\"$exit_code\"")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::launch_plan::{LaunchPlan, shell_quote};
+    use std::collections::BTreeMap;
+    use std::io;
+    use std::path::PathBuf;
+
+    /// Tests quoting for POSIX shells
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("-Xmx2G"), "-Xmx2G");
+        assert_eq!(shell_quote("/opt/java/bin/java"), "/opt/java/bin/java");
+        assert_eq!(shell_quote(""), "''");
+        assert_eq!(shell_quote("my world"), "'my world'");
+        assert_eq!(shell_quote("it's $HOME"), "'it'\\''s $HOME'");
+    }
+
+    /// Tests the layout of an exported script
+    #[test]
+    fn test_to_shell_script() {
+        let mut env = BTreeMap::new();
+        env.insert("MESA_GL_VERSION_OVERRIDE".to_string(), "4.5".to_string());
+        let plan = LaunchPlan {
+            version_id: "1.20.1".to_string(),
+            java: PathBuf::from("/opt/java/bin/java"),
+            java_major_version: 17,
+            java_install: None,
+            working_dir: PathBuf::from("/games/my instance"),
+            env,
+            env_unset: Vec::new(),
+            pre_launch_hooks: Vec::new(),
+            post_exit_hooks: Vec::new(),
+            argv: vec![
+                "/opt/java/bin/java".to_string(),
+                "net.minecraft.client.main.Main".to_string(),
+                "--username".to_string(),
+                "Steve".to_string(),
+            ],
+            classpath: Vec::new(),
+            module_path: Vec::new(),
+            natives_dir: None,
+            downloads: Vec::new(),
+        };
+
+        assert_eq!(
+            plan.to_shell_script().unwrap(),
+            "#!/bin/sh\n\
+             # Launch script for 1.20.1\n\
+             set -e\n\
+             cd '/games/my instance'\n\
+             export MESA_GL_VERSION_OVERRIDE=4.5\n\
+             exec \\\n  /opt/java/bin/java \\\n  net.minecraft.client.main.Main \\\n  --username \\\n  Steve\n"
+        );
+    }
+
+    /// Tests that hooks run around the game, the script keeps its exit status and
+    /// invalid environment variable names are rejected
+    #[test]
+    fn test_shell_script_with_hooks() {
+        let plan = LaunchPlan {
+            version_id: "1.20.1".to_string(),
+            java: PathBuf::from("/opt/java/bin/java"),
+            java_major_version: 17,
+            java_install: None,
+            working_dir: PathBuf::from("/games/survival"),
+            env: BTreeMap::new(),
+            env_unset: vec!["JAVA_TOOL_OPTIONS".to_string()],
+            pre_launch_hooks: vec![vec!["sync-mods".to_string(), "1.20.1".to_string()]],
+            post_exit_hooks: vec![vec![
+                "notify".to_string(),
+                "exited with ${exit_code}".to_string(),
+            ]],
+            argv: vec![
+                "gamemoderun".to_string(),
+                "/opt/java/bin/java".to_string(),
+                "net.minecraft.client.main.Main".to_string(),
+            ],
+            classpath: Vec::new(),
+            module_path: Vec::new(),
+            natives_dir: None,
+            downloads: Vec::new(),
+        };
+
+        assert_eq!(
+            plan.to_shell_script().unwrap(),
+            "#!/bin/sh\n\
+             # Launch script for 1.20.1\n\
+             set -e\n\
+             cd /games/survival\n\
+             unset JAVA_TOOL_OPTIONS\n\
+             sync-mods 1.20.1\n\
+             set +e\n\
+             gamemoderun \\\n  /opt/java/bin/java \\\n  net.minecraft.client.main.Main\n\
+             exit_code=$?\n\
+             notify 'exited with '\"$exit_code\"\n\
+             exit $exit_code\n"
+        );
+
+        let mut plan = plan;
+        plan.env_unset = vec!["X; rm -rf ~".to_string()];
+        let error = plan.to_shell_script().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        plan.env_unset.clear();
+        plan.env.insert("1X".to_string(), "value".to_string());
+        assert!(plan.to_shell_script().is_err());
+        plan.env.clear();
+        plan.env.insert("_X1".to_string(), "value".to_string());
+        assert!(plan.to_shell_script().is_ok());
+    }
+}
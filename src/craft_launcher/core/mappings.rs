@@ -0,0 +1,584 @@
+pub mod mappings {
+    use crate::craft_launcher::core::version::version_resolver::version_resolver::ResolvedVersion;
+    use crate::craft_launcher::utils::networking::networking::{self, DownloadError};
+    use regex::{Captures, Regex};
+    use std::collections::HashMap;
+    use std::error::Error;
+    use std::fmt;
+    use std::fs;
+    use std::io;
+    use std::path::{Path, PathBuf};
+    use std::sync::{Arc, LazyLock, Mutex};
+
+    /// Matches a stack frame, including the module prefix Forge and Java 9+ print
+    static STACK_FRAME: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"^(\s*at\s+(?:[^\s/(]+/)*)([\w$]+(?:\.[\w$]+)*)\.([\w$<>]+)\(([^)]*)\)")
+            .unwrap()
+    });
+
+    /// Matches the class of an exception line such as `Caused by: enn$a: message`
+    static EXCEPTION_CLASS: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r#"^(\s*(?:Caused by: |Suppressed: |Exception in thread "[^"]*" )?)([\w$]+(?:\.[\w$]+)*)(:|$)"#)
+            .unwrap()
+    });
+
+    /// Matches members quoted by helpful NullPointerException messages, e.g. `"eyz.a()"`
+    static QUOTED_MEMBER: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r#""([\w$]+(?:\.[\w$]+)*)\.([\w$]+)(\(\))?""#).unwrap());
+
+    /// Error type for reading mappings
+    #[derive(Debug)]
+    pub enum MappingsError {
+        /// A mapping file could not be read or written
+        IoError(io::Error),
+
+        /// Mappings could not be downloaded
+        NetworkingError(String),
+
+        /// A line of a mapping file is malformed
+        ParseError { line: usize, message: String },
+
+        /// The version does not publish client mappings
+        NoMappings(String),
+
+        /// Downloaded mappings do not match the expected hash
+        HashMismatch { expected: String, actual: String },
+    }
+
+    impl fmt::Display for MappingsError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                MappingsError::IoError(e) => write!(f, "IO error: {}", e),
+                MappingsError::NetworkingError(e) => write!(f, "Networking error: {}", e),
+                MappingsError::ParseError { line, message } => {
+                    write!(f, "Parse error on line {}: {}", line, message)
+                }
+                MappingsError::NoMappings(version) => {
+                    write!(f, "Version {} has no client mappings", version)
+                }
+                MappingsError::HashMismatch { expected, actual } => write!(
+                    f,
+                    "SHA-1 mismatch for mappings: expected {}, got {}",
+                    expected, actual
+                ),
+            }
+        }
+    }
+
+    impl Error for MappingsError {}
+
+    impl From<DownloadError> for MappingsError {
+        fn from(error: DownloadError) -> Self {
+            match error {
+                DownloadError::HashMismatch {
+                    expected, actual, ..
+                } => MappingsError::HashMismatch { expected, actual },
+                DownloadError::IoError(e) => MappingsError::IoError(e),
+                e => MappingsError::NetworkingError(e.to_string()),
+            }
+        }
+    }
+
+    impl From<io::Error> for MappingsError {
+        fn from(error: io::Error) -> Self {
+            MappingsError::IoError(error)
+        }
+    }
+
+    /// A field of a mapped class
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct FieldMapping {
+        /// Type of the field
+        pub field_type: String,
+
+        /// Mojang name
+        pub original: String,
+
+        /// Obfuscated name
+        pub obfuscated: String,
+    }
+
+    /// A method of a mapped class
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct MethodMapping {
+        /// Return type of the method
+        pub return_type: String,
+
+        /// Mojang name
+        pub original: String,
+
+        /// Argument types separated by commas
+        pub arguments: String,
+
+        /// Obfuscated name
+        pub obfuscated: String,
+
+        /// Line range in the obfuscated jar, if recorded
+        pub obfuscated_lines: Option<(u32, u32)>,
+
+        /// Line range in the original source, if it differs from the obfuscated one
+        pub original_lines: Option<(u32, Option<u32>)>,
+    }
+
+    impl MethodMapping {
+        /// Maps a line of the obfuscated jar to a line of the original source
+        ///
+        /// # Returns
+        ///
+        /// * The original line, or None if the line is outside this method
+        pub fn original_line(&self, line: u32) -> Option<u32> {
+            let Some((start, end)) = self.obfuscated_lines else {
+                return Some(line);
+            };
+            if line < start || line > end {
+                return None;
+            }
+            match self.original_lines {
+                Some((original_start, Some(_))) => Some(original_start + (line - start)),
+                Some((original_start, None)) => Some(original_start),
+                None => Some(line),
+            }
+        }
+    }
+
+    /// A mapped class with its fields and methods
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct ClassMapping {
+        /// Mojang name, e.g. "net.minecraft.client.Minecraft"
+        pub original: String,
+
+        /// Obfuscated name, e.g. "enn"
+        pub obfuscated: String,
+
+        /// Mapped fields
+        pub fields: Vec<FieldMapping>,
+
+        /// Mapped methods
+        pub methods: Vec<MethodMapping>,
+    }
+
+    impl ClassMapping {
+        /// Finds a field by its obfuscated name
+        pub fn field(&self, obfuscated: &str) -> Option<&FieldMapping> {
+            self.fields
+                .iter()
+                .find(|field| field.obfuscated == obfuscated)
+        }
+
+        /// Finds a method by its obfuscated name and, if known, a line inside it
+        ///
+        /// # Returns
+        ///
+        /// * The method and the original line
+        pub fn method(
+            &self,
+            obfuscated: &str,
+            line: Option<u32>,
+        ) -> Option<(&MethodMapping, Option<u32>)> {
+            let mut candidates = self
+                .methods
+                .iter()
+                .filter(|method| method.obfuscated == obfuscated);
+            match line {
+                Some(line) => {
+                    let candidates: Vec<_> = candidates.collect();
+                    candidates
+                        .iter()
+                        .find_map(|method| {
+                            method
+                                .original_line(line)
+                                .filter(|_| method.obfuscated_lines.is_some())
+                                .map(|original| (*method, Some(original)))
+                        })
+                        .or_else(|| candidates.first().map(|method| (*method, Some(line))))
+                }
+                None => candidates.next().map(|method| (method, None)),
+            }
+        }
+
+        /// Gets the name of the source file the class was compiled from
+        pub fn source_file(&self) -> String {
+            let simple = self.original.rsplit('.').next().unwrap_or(&self.original);
+            let outer = simple.split('$').next().unwrap_or(simple);
+            format!("{}.java", outer)
+        }
+    }
+
+    /// Mappings in the ProGuard format Mojang publishes as `client_mappings`
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct ProguardMappings {
+        classes: Vec<ClassMapping>,
+        by_obfuscated: HashMap<String, usize>,
+    }
+
+    impl ProguardMappings {
+        /// Parses a ProGuard mapping file
+        ///
+        /// # Arguments
+        ///
+        /// * `text` - The contents of the mapping file
+        ///
+        /// # Returns
+        ///
+        /// * `Result<ProguardMappings, MappingsError>` - The mappings or an error
+        pub fn parse(text: &str) -> Result<Self, MappingsError> {
+            let mut mappings = ProguardMappings::default();
+
+            for (index, line) in text.lines().enumerate() {
+                let line_number = index + 1;
+                let trimmed = line.trim();
+                if trimmed.is_empty() || trimmed.starts_with('#') {
+                    continue;
+                }
+                let error = |message: &str| MappingsError::ParseError {
+                    line: line_number,
+                    message: message.to_string(),
+                };
+
+                let (left, obfuscated) = trimmed
+                    .split_once(" -> ")
+                    .ok_or_else(|| error("missing \" -> \""))?;
+
+                if !line.starts_with(char::is_whitespace) {
+                    let obfuscated = obfuscated
+                        .strip_suffix(':')
+                        .ok_or_else(|| error("class line does not end with ':'"))?;
+                    mappings
+                        .by_obfuscated
+                        .insert(obfuscated.to_string(), mappings.classes.len());
+                    mappings.classes.push(ClassMapping {
+                        original: left.to_string(),
+                        obfuscated: obfuscated.to_string(),
+                        fields: Vec::new(),
+                        methods: Vec::new(),
+                    });
+                    continue;
+                }
+
+                let class = mappings
+                    .classes
+                    .last_mut()
+                    .ok_or_else(|| error("member before the first class"))?;
+                if left.contains('(') {
+                    class
+                        .methods
+                        .push(parse_method(left, obfuscated).ok_or_else(|| error("bad method"))?);
+                } else {
+                    let (field_type, original) = left
+                        .split_once(' ')
+                        .ok_or_else(|| error("field without a type"))?;
+                    class.fields.push(FieldMapping {
+                        field_type: field_type.to_string(),
+                        original: original.to_string(),
+                        obfuscated: obfuscated.to_string(),
+                    });
+                }
+            }
+
+            Ok(mappings)
+        }
+
+        /// Gets every mapped class
+        pub fn classes(&self) -> &[ClassMapping] {
+            &self.classes
+        }
+
+        /// Finds a class by its obfuscated name
+        pub fn class(&self, obfuscated: &str) -> Option<&ClassMapping> {
+            self.by_obfuscated
+                .get(obfuscated)
+                .map(|index| &self.classes[*index])
+        }
+    }
+
+    /// Parses `[a:b:]type name(args)[:c[:d]]`
+    fn parse_method(left: &str, obfuscated: &str) -> Option<MethodMapping> {
+        let open = left.find('(')?;
+        let close = left.rfind(')')?;
+        let head = &left[..open];
+        let arguments = &left[open + 1..close];
+        let tail = &left[close + 1..];
+
+        let mut head_parts: Vec<&str> = head.splitn(3, ':').collect();
+        let obfuscated_lines = if head_parts.len() == 3 {
+            let start = head_parts[0].parse().ok()?;
+            let end = head_parts[1].parse().ok()?;
+            head_parts.drain(..2);
+            Some((start, end))
+        } else {
+            None
+        };
+        let (return_type, original) = head_parts[0].split_once(' ')?;
+
+        let original_lines = match tail.strip_prefix(':') {
+            Some(lines) => {
+                let mut lines = lines.split(':');
+                let start = lines.next()?.parse().ok()?;
+                let end = match lines.next() {
+                    Some(end) => Some(end.parse().ok()?),
+                    None => None,
+                };
+                Some((start, end))
+            }
+            None => None,
+        };
+
+        Some(MethodMapping {
+            return_type: return_type.to_string(),
+            original: original.to_string(),
+            arguments: arguments.to_string(),
+            obfuscated: obfuscated.to_string(),
+            obfuscated_lines,
+            original_lines,
+        })
+    }
+
+    /// Rewrites obfuscated names in crash reports and stack traces to Mojang names
+    #[derive(Debug, Clone)]
+    pub struct Deobfuscator {
+        mappings: Arc<ProguardMappings>,
+    }
+
+    impl Deobfuscator {
+        /// Creates a deobfuscator from mappings
+        pub fn new(mappings: Arc<ProguardMappings>) -> Self {
+            Deobfuscator { mappings }
+        }
+
+        /// Deobfuscates every line of a text, such as a crash report
+        pub fn deobfuscate(&self, text: &str) -> String {
+            let mut result: Vec<String> = text
+                .split('\n')
+                .map(|line| self.deobfuscate_line(line))
+                .collect();
+            if text.ends_with('\n') {
+                result.pop();
+                result.push(String::new());
+            }
+            result.join("\n")
+        }
+
+        /// Deobfuscates a stack frame, exception line or helpful NullPointerException message
+        ///
+        /// Other lines are returned unchanged, so short obfuscated names inside
+        /// ordinary log messages are never rewritten by accident.
+        pub fn deobfuscate_line(&self, line: &str) -> String {
+            if let Some(captures) = STACK_FRAME.captures(line) {
+                return self.deobfuscate_frame(line, &captures);
+            }
+
+            let line = match EXCEPTION_CLASS.captures(line) {
+                Some(captures) => match self.mappings.class(&captures[2]) {
+                    Some(class) => format!(
+                        "{}{}{}{}",
+                        &captures[1],
+                        class.original,
+                        &captures[3],
+                        &line[captures[0].len()..]
+                    ),
+                    None => line.to_string(),
+                },
+                None => line.to_string(),
+            };
+
+            QUOTED_MEMBER
+                .replace_all(&line, |captures: &Captures| {
+                    let Some(class) = self.mappings.class(&captures[1]) else {
+                        return captures[0].to_string();
+                    };
+                    let is_method = captures.get(3).is_some();
+                    let member = if is_method {
+                        class
+                            .method(&captures[2], None)
+                            .map(|(method, _)| method.original.as_str())
+                    } else {
+                        class
+                            .field(&captures[2])
+                            .map(|field| field.original.as_str())
+                    };
+                    format!(
+                        "\"{}.{}{}\"",
+                        class.original,
+                        member.unwrap_or(&captures[2]),
+                        if is_method { "()" } else { "" }
+                    )
+                })
+                .into_owned()
+        }
+
+        /// Rewrites the class, method and source position of a stack frame
+        fn deobfuscate_frame(&self, line: &str, captures: &Captures) -> String {
+            let rest = &line[captures[0].len()..];
+            let Some(class) = self.mappings.class(&captures[2]) else {
+                return line.to_string();
+            };
+
+            let location = &captures[4];
+            let obfuscated_line = location
+                .rsplit_once(':')
+                .and_then(|(_, number)| number.parse::<u32>().ok());
+            let (method, original_line) = match class.method(&captures[3], obfuscated_line) {
+                Some((method, original_line)) => (method.original.as_str(), original_line),
+                None => (&captures[3], obfuscated_line),
+            };
+
+            let location = match (location, original_line) {
+                ("Native Method", _) => location.to_string(),
+                (_, Some(number)) => format!("{}:{}", class.source_file(), number),
+                _ => class.source_file(),
+            };
+            format!(
+                "{}{}.{}({}){}",
+                &captures[1], class.original, method, location, rest
+            )
+        }
+    }
+
+    /// Downloads client mappings and keeps them per version
+    ///
+    /// Mappings are stored next to the client jar as
+    /// `versions/<jar>/<jar>-client-mappings.txt`, so loader versions share the
+    /// mappings of the vanilla version they inherit from.
+    #[derive(Debug, Clone)]
+    pub struct MappingsCache {
+        root_dir: PathBuf,
+        loaded: Arc<Mutex<HashMap<String, Arc<ProguardMappings>>>>,
+    }
+
+    impl MappingsCache {
+        /// Creates a cache for a game directory
+        ///
+        /// # Arguments
+        ///
+        /// * `root_dir` - Path to the root directory where game data is stored
+        pub fn new(root_dir: impl AsRef<Path>) -> Self {
+            MappingsCache {
+                root_dir: root_dir.as_ref().to_path_buf(),
+                loaded: Arc::new(Mutex::new(HashMap::new())),
+            }
+        }
+
+        /// Gets the path the client mappings of a version are stored at
+        pub fn mappings_path(&self, version: &ResolvedVersion) -> PathBuf {
+            self.root_dir
+                .join("versions")
+                .join(&version.jar)
+                .join(format!("{}-client-mappings.txt", version.jar))
+        }
+
+        /// Loads the client mappings of a version, downloading them if needed
+        ///
+        /// # Returns
+        ///
+        /// * `Result<Arc<ProguardMappings>, MappingsError>` - The mappings or an error
+        pub fn load(
+            &self,
+            version: &ResolvedVersion,
+        ) -> Result<Arc<ProguardMappings>, MappingsError> {
+            if let Some(mappings) = self.loaded.lock().unwrap().get(&version.jar) {
+                return Ok(mappings.clone());
+            }
+
+            let download = version
+                .client_mappings
+                .as_ref()
+                .ok_or_else(|| MappingsError::NoMappings(version.jar.clone()))?;
+            let path = self.mappings_path(version);
+
+            networking::ensure_file(&path, &download.url, &download.sha1)?;
+            let bytes = fs::read(&path)?;
+
+            let mappings = Arc::new(ProguardMappings::parse(&String::from_utf8_lossy(&bytes))?);
+            self.loaded
+                .lock()
+                .unwrap()
+                .insert(version.jar.clone(), mappings.clone());
+            Ok(mappings)
+        }
+
+        /// Creates a deobfuscator for a version
+        pub fn deobfuscator(
+            &self,
+            version: &ResolvedVersion,
+        ) -> Result<Deobfuscator, MappingsError> {
+            Ok(Deobfuscator::new(self.load(version)?))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mappings::{Deobfuscator, ProguardMappings};
+    use std::sync::Arc;
+
+    const MAPPINGS: &str = "# {\"id\":\"sourceFile\",\"fileName\":\"Minecraft.java\"}
+net.minecraft.client.Minecraft -> enn:
+    net.minecraft.client.renderer.GameRenderer gameRenderer -> j
+    12:15:void <init>() -> <init>
+    1180:1195:void runTick(boolean):1200:1215 -> e
+    1196:1196:void handleKeybinds():1900 -> e
+    boolean isRunning() -> f
+net.minecraft.client.Minecraft$ChatStatus -> enn$a:
+    int ordinal() -> ordinal
+";
+
+    fn deobfuscator() -> Deobfuscator {
+        Deobfuscator::new(Arc::new(ProguardMappings::parse(MAPPINGS).unwrap()))
+    }
+
+    /// Tests parsing classes, fields and methods with line ranges
+    #[test]
+    fn test_parse() {
+        let mappings = ProguardMappings::parse(MAPPINGS).unwrap();
+        assert_eq!(mappings.classes().len(), 2);
+
+        let class = mappings.class("enn").unwrap();
+        assert_eq!(class.original, "net.minecraft.client.Minecraft");
+        assert_eq!(class.field("j").unwrap().original, "gameRenderer");
+        assert_eq!(class.methods.len(), 4);
+        assert_eq!(class.methods[1].obfuscated_lines, Some((1180, 1195)));
+        assert_eq!(class.methods[1].original_lines, Some((1200, Some(1215))));
+        assert_eq!(class.methods[1].arguments, "boolean");
+
+        let (method, line) = class.method("e", Some(1185)).unwrap();
+        assert_eq!((method.original.as_str(), line), ("runTick", Some(1205)));
+        let (method, line) = class.method("e", Some(1196)).unwrap();
+        assert_eq!(
+            (method.original.as_str(), line),
+            ("handleKeybinds", Some(1900))
+        );
+
+        assert!(ProguardMappings::parse("enn:\n").is_err());
+        assert!(ProguardMappings::parse("    int a -> b\n").is_err());
+    }
+
+    /// Tests rewriting stack traces
+    #[test]
+    fn test_deobfuscate_trace() {
+        let trace = "java.lang.IllegalStateException: enn.e failed\n\
+                     \tat enn.e(SourceFile:1185) ~[client-intermediary.jar:?]\n\
+                     \tat TRANSFORMER/minecraft@1.20.1/enn$a.ordinal(SourceFile) ~[?:?]\n\
+                     \tat java.lang.Thread.run(Thread.java:833)\n\
+                     Caused by: enn$a: broken\n";
+
+        assert_eq!(
+            deobfuscator().deobfuscate(trace),
+            "java.lang.IllegalStateException: enn.e failed\n\
+             \tat net.minecraft.client.Minecraft.runTick(Minecraft.java:1205) ~[client-intermediary.jar:?]\n\
+             \tat TRANSFORMER/minecraft@1.20.1/net.minecraft.client.Minecraft$ChatStatus.ordinal(Minecraft.java) ~[?:?]\n\
+             \tat java.lang.Thread.run(Thread.java:833)\n\
+             Caused by: net.minecraft.client.Minecraft$ChatStatus: broken\n"
+        );
+    }
+
+    /// Tests rewriting helpful NullPointerException messages
+    #[test]
+    fn test_deobfuscate_npe_message() {
+        assert_eq!(
+            deobfuscator().deobfuscate_line(
+                "java.lang.NullPointerException: Cannot invoke \"enn.f()\" because \"enn.j\" is null"
+            ),
+            "java.lang.NullPointerException: Cannot invoke \"net.minecraft.client.Minecraft.isRunning()\" because \"net.minecraft.client.Minecraft.gameRenderer\" is null"
+        );
+    }
+}
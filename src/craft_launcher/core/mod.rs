@@ -2,17 +2,44 @@
 /// Asset downloader, parser and utility functions.
 pub mod assets;
 
+/// Player accounts that fill the `auth_*` launch placeholders
+pub mod account;
+
 /// Version manifest parser
 pub mod manifest;
 
+/// Crash detection and classification
+pub mod crash_analyzer;
+
 /// Disposable interface
 pub mod disposable;
 
 /// Core Minecraft launcher engine.
 pub mod engine;
 
+/// Quick Play, resolution and demo launch features
+pub mod launch_features;
+
+/// Pre-launch and post-exit hook commands
+pub mod launch_hooks;
+
+/// Dry-run launch plans and shell script export
+pub mod launch_plan;
+
+/// Game output capture and Log4j event parsing
+pub mod game_output;
+
 /// JSON serializable structs
 pub mod json_structs;
 
+/// ProGuard mappings and stack trace deobfuscation
+pub mod mappings;
+
+/// Lock-file registry of running game processes
+pub mod process_registry;
+
+/// Dedicated server installation and headless launching
+pub mod server;
+
 /// Version information of Minecraft
 pub mod version;
@@ -0,0 +1,503 @@
+pub mod process_registry {
+    use crate::craft_launcher::core::disposable::Disposable;
+    use crate::craft_launcher::core::engine::engine::Account;
+    use serde::{Deserialize, Serialize};
+    use sha1::{Digest, Sha1};
+    use std::error::Error;
+    use std::fmt;
+    use std::fs;
+    use std::io;
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::thread;
+    use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+    use sysinfo::{Pid, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, Signal, System};
+
+    /// How often a waiting caller checks whether a process has exited
+    const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// Counter that keeps temporary lock file names unique within a process
+    static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    /// Lifecycle state of a registered instance
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    pub enum RunningState {
+        /// The launcher holds the lock and is installing files or spawning the game;
+        /// `pid` is the launcher's own process
+        Starting,
+
+        /// The game is running; `pid` is the game process
+        Running,
+    }
+
+    /// A running game recorded in the registry
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct RunningInstance {
+        /// The game directory of the instance
+        pub instance: PathBuf,
+
+        /// The version being played
+        pub version_id: String,
+
+        /// Whether the game is still starting or running
+        pub state: RunningState,
+
+        /// The process holding the instance
+        pub pid: u32,
+
+        /// Start time of that process in seconds since the Unix epoch, used to tell it
+        /// apart from a later process that reuses the PID
+        pub process_start_time: u64,
+
+        /// When the launch started, in seconds since the Unix epoch
+        pub started_at: u64,
+
+        /// The player name of the account
+        pub username: String,
+
+        /// The player UUID of the account
+        pub uuid: String,
+
+        /// The launcher process that started the game
+        pub launcher_pid: u32,
+    }
+
+    impl RunningInstance {
+        /// Checks whether the recorded process is still alive
+        ///
+        /// A process with the same PID but a different start time is a new process,
+        /// so the record is stale.
+        pub fn is_alive(&self) -> bool {
+            process_start_time(self.pid).is_some_and(|start| start == self.process_start_time)
+        }
+
+        /// Gets the path of the log the game is writing
+        pub fn latest_log(&self) -> PathBuf {
+            self.instance.join("logs").join("latest.log")
+        }
+    }
+
+    /// Error type for the process registry
+    #[derive(Debug)]
+    pub enum RegistryError {
+        /// A lock file could not be read or written
+        IoError(io::Error),
+
+        /// A lock file is not valid JSON
+        ParseError(serde_json::Error),
+
+        /// The instance is already running
+        AlreadyRunning(Box<RunningInstance>),
+
+        /// The process could not be killed
+        KillFailed(u32),
+    }
+
+    impl fmt::Display for RegistryError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                RegistryError::IoError(e) => write!(f, "IO error: {}", e),
+                RegistryError::ParseError(e) => write!(f, "Parse error: {}", e),
+                RegistryError::AlreadyRunning(running) => write!(
+                    f,
+                    "{} is already running as process {} for {}",
+                    running.instance.display(),
+                    running.pid,
+                    running.username
+                ),
+                RegistryError::KillFailed(pid) => write!(f, "Failed to kill process {}", pid),
+            }
+        }
+    }
+
+    impl Error for RegistryError {}
+
+    impl From<io::Error> for RegistryError {
+        fn from(error: io::Error) -> Self {
+            RegistryError::IoError(error)
+        }
+    }
+
+    impl From<serde_json::Error> for RegistryError {
+        fn from(error: serde_json::Error) -> Self {
+            RegistryError::ParseError(error)
+        }
+    }
+
+    /// Lock files of running games, shared by every launcher process using the same root
+    ///
+    /// Each instance has one lock file, named after a hash of its game directory. The
+    /// file is created with a hard link, which fails atomically if another launcher
+    /// already holds the instance. Locks whose process has exited are stale and are
+    /// removed when they are found.
+    #[derive(Debug, Clone)]
+    pub struct ProcessRegistry {
+        directory: PathBuf,
+    }
+
+    impl ProcessRegistry {
+        /// Creates a registry stored in `<root_dir>/running`
+        ///
+        /// # Arguments
+        ///
+        /// * `root_dir` - Path to the root directory where game data is stored
+        pub fn new(root_dir: impl AsRef<Path>) -> Self {
+            ProcessRegistry {
+                directory: root_dir.as_ref().join("running"),
+            }
+        }
+
+        /// Creates a registry stored in a specific directory
+        pub fn with_directory(directory: impl AsRef<Path>) -> Self {
+            ProcessRegistry {
+                directory: directory.as_ref().to_path_buf(),
+            }
+        }
+
+        /// Gets the lock file of an instance
+        pub fn lock_path(&self, game_dir: &Path) -> PathBuf {
+            let mut hasher = Sha1::new();
+            hasher.update(instance_key(game_dir).to_string_lossy().as_bytes());
+            let hash = format!("{:x}", hasher.finalize());
+            self.directory.join(format!("{}.lock", &hash[..16]))
+        }
+
+        /// Locks an instance for a launch by the current process
+        ///
+        /// # Arguments
+        ///
+        /// * `game_dir` - The game directory of the instance
+        /// * `version_id` - The version being launched
+        /// * `account` - The player launching the game
+        ///
+        /// # Returns
+        ///
+        /// * `Result<InstanceLock, RegistryError>` - The lock, or `AlreadyRunning` if a
+        ///   live process holds the instance
+        pub fn acquire(
+            &self,
+            game_dir: &Path,
+            version_id: &str,
+            account: &Account,
+        ) -> Result<InstanceLock, RegistryError> {
+            fs::create_dir_all(&self.directory)?;
+            let path = self.lock_path(game_dir);
+            let launcher_pid = std::process::id();
+            let entry = RunningInstance {
+                instance: instance_key(game_dir),
+                version_id: version_id.to_string(),
+                state: RunningState::Starting,
+                pid: launcher_pid,
+                process_start_time: process_start_time(launcher_pid).unwrap_or_default(),
+                started_at: unix_time(),
+                username: account.username.clone(),
+                uuid: account.uuid.clone(),
+                launcher_pid,
+            };
+
+            // One retry covers a stale lock removed by the first attempt
+            for _ in 0..2 {
+                let temp = write_temp(&self.directory, &entry)?;
+                let linked = fs::hard_link(&temp, &path);
+                let _ = fs::remove_file(&temp);
+                match linked {
+                    Ok(()) => {
+                        return Ok(InstanceLock {
+                            path,
+                            entry,
+                            released: false,
+                        });
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                        if let Some(running) = self.read_live(&path)? {
+                            return Err(RegistryError::AlreadyRunning(Box::new(running)));
+                        }
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            }
+            match self.read_live(&path)? {
+                Some(running) => Err(RegistryError::AlreadyRunning(Box::new(running))),
+                None => Err(io::Error::new(
+                    io::ErrorKind::WouldBlock,
+                    "the instance lock keeps changing",
+                )
+                .into()),
+            }
+        }
+
+        /// Gets the running game of an instance, if any
+        pub fn get(&self, game_dir: &Path) -> Result<Option<RunningInstance>, RegistryError> {
+            self.read_live(&self.lock_path(game_dir))
+        }
+
+        /// Lists every running game, removing stale locks
+        pub fn list(&self) -> Result<Vec<RunningInstance>, RegistryError> {
+            let entries = match fs::read_dir(&self.directory) {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+                Err(e) => return Err(e.into()),
+            };
+
+            let mut running = Vec::new();
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path
+                    .extension()
+                    .is_some_and(|extension| extension == "lock")
+                    && let Some(instance) = self.read_live(&path)?
+                {
+                    running.push(instance);
+                }
+            }
+            running.sort_by_key(|instance| instance.started_at);
+            Ok(running)
+        }
+
+        /// Attaches to the running game of an instance
+        ///
+        /// The game may have been started by another launcher process.
+        pub fn attach(&self, game_dir: &Path) -> Result<Option<AttachedInstance>, RegistryError> {
+            Ok(self.get(game_dir)?.map(|entry| AttachedInstance {
+                entry,
+                lock_path: self.lock_path(game_dir),
+            }))
+        }
+
+        /// Kills the running game of an instance
+        ///
+        /// # Arguments
+        ///
+        /// * `game_dir` - The game directory of the instance
+        /// * `timeout` - How long to wait for a graceful exit before forcing it
+        ///
+        /// # Returns
+        ///
+        /// * `Result<bool, RegistryError>` - true if a game was killed, false if none was running
+        pub fn kill(&self, game_dir: &Path, timeout: Duration) -> Result<bool, RegistryError> {
+            match self.attach(game_dir)? {
+                Some(attached) => attached.kill(timeout).map(|_| true),
+                None => Ok(false),
+            }
+        }
+
+        /// Reads a lock file, removing it if its process has exited
+        fn read_live(&self, path: &Path) -> Result<Option<RunningInstance>, RegistryError> {
+            let json = match fs::read_to_string(path) {
+                Ok(json) => json,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+                Err(e) => return Err(e.into()),
+            };
+            let entry: RunningInstance = serde_json::from_str(&json)?;
+            if entry.is_alive() {
+                return Ok(Some(entry));
+            }
+
+            // Another launcher may have replaced the stale lock in the meantime
+            if fs::read_to_string(path).is_ok_and(|current| current == json) {
+                let _ = fs::remove_file(path);
+            }
+            Ok(None)
+        }
+    }
+
+    /// The lock of an instance held by this launcher process
+    ///
+    /// Dropping a lock that is still `Starting` releases it, so a failed launch does
+    /// not block the instance. Once the game runs, the lock stays until `release` is
+    /// called or the game exits, even if the launcher exits first.
+    #[derive(Debug)]
+    pub struct InstanceLock {
+        path: PathBuf,
+        entry: RunningInstance,
+        released: bool,
+    }
+
+    impl InstanceLock {
+        /// Gets the recorded entry
+        pub fn entry(&self) -> &RunningInstance {
+            &self.entry
+        }
+
+        /// Records the spawned game process
+        ///
+        /// # Arguments
+        ///
+        /// * `pid` - The process ID of the game
+        pub fn set_running(&mut self, pid: u32) -> io::Result<()> {
+            self.entry.state = RunningState::Running;
+            self.entry.pid = pid;
+            self.entry.process_start_time = process_start_time(pid).unwrap_or_default();
+
+            let directory = self.path.parent().unwrap_or(Path::new("."));
+            let temp = write_temp(directory, &self.entry)?;
+            fs::rename(&temp, &self.path)
+        }
+
+        /// Removes the lock file
+        pub fn release(&mut self) {
+            if self.released {
+                return;
+            }
+            self.released = true;
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+
+    impl Disposable for InstanceLock {
+        /// Removes the lock file
+        fn dispose(&mut self) {
+            self.release();
+        }
+    }
+
+    impl Drop for InstanceLock {
+        fn drop(&mut self) {
+            if self.entry.state == RunningState::Starting {
+                self.release();
+            }
+        }
+    }
+
+    /// A handle to a game found in the registry
+    #[derive(Debug, Clone)]
+    pub struct AttachedInstance {
+        entry: RunningInstance,
+        lock_path: PathBuf,
+    }
+
+    impl AttachedInstance {
+        /// Gets the recorded entry
+        pub fn entry(&self) -> &RunningInstance {
+            &self.entry
+        }
+
+        /// Checks whether the game is still running
+        pub fn is_running(&self) -> bool {
+            self.entry.is_alive()
+        }
+
+        /// Waits for the game to exit
+        ///
+        /// # Arguments
+        ///
+        /// * `timeout` - How long to wait; None waits forever
+        ///
+        /// # Returns
+        ///
+        /// * true if the game exited, false if the timeout passed first
+        pub fn wait(&self, timeout: Option<Duration>) -> bool {
+            let start = Instant::now();
+            while self.is_running() {
+                if timeout.is_some_and(|timeout| start.elapsed() >= timeout) {
+                    return false;
+                }
+                thread::sleep(POLL_INTERVAL);
+            }
+            self.remove_lock();
+            true
+        }
+
+        /// Asks the game to exit and kills it if it does not exit in time
+        ///
+        /// # Arguments
+        ///
+        /// * `timeout` - How long to wait for a graceful exit
+        pub fn kill(&self, timeout: Duration) -> Result<(), RegistryError> {
+            let pid = Pid::from_u32(self.entry.pid);
+            let system = refreshed_system(pid);
+            let Some(process) = system.process(pid).filter(|_| self.is_running()) else {
+                self.remove_lock();
+                return Ok(());
+            };
+
+            if process.kill_with(Signal::Term) == Some(true) && self.wait(Some(timeout)) {
+                return Ok(());
+            }
+            if !process.kill() && self.is_running() {
+                return Err(RegistryError::KillFailed(self.entry.pid));
+            }
+            if self.wait(Some(timeout)) {
+                Ok(())
+            } else {
+                Err(RegistryError::KillFailed(self.entry.pid))
+            }
+        }
+
+        /// Removes the lock file if it still describes this game
+        fn remove_lock(&self) {
+            let current = fs::read_to_string(&self.lock_path)
+                .ok()
+                .and_then(|json| serde_json::from_str::<RunningInstance>(&json).ok());
+            if current.is_some_and(|current| current == self.entry) {
+                let _ = fs::remove_file(&self.lock_path);
+            }
+        }
+    }
+
+    /// Gets the key an instance is registered under
+    fn instance_key(game_dir: &Path) -> PathBuf {
+        game_dir
+            .canonicalize()
+            .or_else(|_| std::path::absolute(game_dir))
+            .unwrap_or_else(|_| game_dir.to_path_buf())
+    }
+
+    /// Writes an entry to a uniquely named temporary file
+    fn write_temp(directory: &Path, entry: &RunningInstance) -> io::Result<PathBuf> {
+        let temp = directory.join(format!(
+            ".{}-{}.tmp",
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&temp, serde_json::to_string_pretty(entry)?)?;
+        Ok(temp)
+    }
+
+    /// Checks whether a process with the given PID is running
+    ///
+    /// # Arguments
+    ///
+    /// * `pid` - The process ID
+    ///
+    /// # Returns
+    ///
+    /// * `true` if the process exists and is not a zombie
+    pub fn is_process_running(pid: u32) -> bool {
+        process_start_time(pid).is_some()
+    }
+
+    /// Gets the start time of a live process; None if it has exited or is a zombie
+    fn process_start_time(pid: u32) -> Option<u64> {
+        let pid = Pid::from_u32(pid);
+        let system = refreshed_system(pid);
+        let process = system.process(pid)?;
+        if matches!(
+            process.status(),
+            ProcessStatus::Zombie | ProcessStatus::Dead
+        ) {
+            return None;
+        }
+        Some(process.start_time())
+    }
+
+    /// Loads the basic information of a single process
+    fn refreshed_system(pid: Pid) -> System {
+        let mut system = System::new();
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[pid]),
+            true,
+            ProcessRefreshKind::nothing(),
+        );
+        system
+    }
+
+    /// Gets the current time in seconds since the Unix epoch
+    fn unix_time() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,814 @@
+pub mod server {
+    use crate::craft_launcher::core::disposable::Disposable;
+    use crate::craft_launcher::core::game_output::game_output::{GameLogRecord, stream_output};
+    use crate::craft_launcher::core::version::library_conflict::library_conflict::ConflictPolicy;
+    use crate::craft_launcher::core::version::maven_coordinate::maven_coordinate::compare_versions;
+    use crate::craft_launcher::core::version::version_resolver::version_resolver::resolve_version;
+    use crate::craft_launcher::utils::file_operations::file_utils;
+    use crate::craft_launcher::utils::networking::networking::{self, DownloadError};
+    use std::error::Error;
+    use std::fmt;
+    use std::fs;
+    use std::io::{self, Write};
+    use std::path::{Path, PathBuf};
+    use std::process::{Child, ChildStderr, ChildStdin, ChildStdout, Command, ExitStatus, Stdio};
+    use std::sync::mpsc::Receiver;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    /// Base URL of the Fabric meta API
+    pub const FABRIC_META_URL: &str = "https://meta.fabricmc.net";
+
+    /// File name of the vanilla server jar in the server directory
+    pub const SERVER_JAR: &str = "server.jar";
+
+    /// File name the Fabric server launcher is installed as
+    pub const FABRIC_LAUNCHER_JAR: &str = "fabric-server-launch.jar";
+
+    /// How long `dispose` waits for the server to stop before killing it
+    pub const DEFAULT_STOP_TIMEOUT: Duration = Duration::from_secs(30);
+
+    const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+    /// Error type for installing and running dedicated servers
+    #[derive(Debug)]
+    pub enum ServerError {
+        /// The version or one of its parents could not be read
+        VersionResolution(String),
+
+        /// The version has no server download
+        NoServerDownload(String),
+
+        /// A file could not be downloaded
+        NetworkingError(String),
+
+        /// A downloaded file does not have the expected SHA-1 hash
+        HashMismatch {
+            url: String,
+            expected: String,
+            actual: String,
+        },
+
+        /// A downloaded file is not what was expected, e.g. not a jar
+        InvalidDownload { url: String, reason: String },
+
+        /// The EULA has not been accepted in this server directory
+        EulaNotAccepted(PathBuf),
+
+        /// No server jar or Forge/NeoForge argument file was found
+        NoLauncher(PathBuf),
+
+        /// A Forge or NeoForge installer exited unsuccessfully
+        InstallerFailed(ExitStatus),
+
+        /// A file system operation or the process spawn failed
+        IoError(io::Error),
+    }
+
+    impl fmt::Display for ServerError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                ServerError::VersionResolution(e) => write!(f, "Failed to resolve version: {}", e),
+                ServerError::NoServerDownload(id) => {
+                    write!(f, "Version {} has no server download", id)
+                }
+                ServerError::NetworkingError(e) => write!(f, "Server download failed: {}", e),
+                ServerError::HashMismatch {
+                    url,
+                    expected,
+                    actual,
+                } => write!(
+                    f,
+                    "SHA-1 mismatch for {}: expected {}, got {}",
+                    url, expected, actual
+                ),
+                ServerError::InvalidDownload { url, reason } => {
+                    write!(f, "Invalid download from {}: {}", url, reason)
+                }
+                ServerError::EulaNotAccepted(dir) => write!(
+                    f,
+                    "The Minecraft EULA has not been accepted in {}",
+                    dir.display()
+                ),
+                ServerError::NoLauncher(dir) => {
+                    write!(f, "No server is installed in {}", dir.display())
+                }
+                ServerError::InstallerFailed(status) => {
+                    write!(f, "The server installer failed with {}", status)
+                }
+                ServerError::IoError(e) => write!(f, "Server IO error: {}", e),
+            }
+        }
+    }
+
+    impl Error for ServerError {}
+
+    impl From<DownloadError> for ServerError {
+        fn from(error: DownloadError) -> Self {
+            match error {
+                DownloadError::NetworkingError { url, message } => {
+                    ServerError::NetworkingError(format!("{}: {}", url, message))
+                }
+                DownloadError::HashMismatch {
+                    url,
+                    expected,
+                    actual,
+                } => ServerError::HashMismatch {
+                    url,
+                    expected,
+                    actual,
+                },
+                DownloadError::IoError(e) => ServerError::IoError(e),
+            }
+        }
+    }
+
+    impl From<io::Error> for ServerError {
+        fn from(error: io::Error) -> Self {
+            ServerError::IoError(error)
+        }
+    }
+
+    /// The `server.properties` values to seed a server with
+    ///
+    /// Seeding only adds keys the file does not have yet, so settings changed by the
+    /// server owner are kept.
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct ServerProperties {
+        entries: Vec<(String, String)>,
+    }
+
+    impl ServerProperties {
+        /// Creates an empty set of properties
+        pub fn new() -> Self {
+            ServerProperties::default()
+        }
+
+        /// Parses the contents of a `server.properties` file
+        ///
+        /// # Arguments
+        ///
+        /// * `text` - The file contents
+        pub fn parse(text: &str) -> Self {
+            let mut properties = ServerProperties::new();
+            for line in text.lines() {
+                let line = line.trim_start();
+                if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+                    continue;
+                }
+                let (key, value) = line
+                    .split_once(['=', ':'])
+                    .map(|(key, value)| (key.trim(), value))
+                    .unwrap_or((line.trim(), ""));
+                properties = properties.with(key, value);
+            }
+            properties
+        }
+
+        /// Sets a property.
+        ///
+        /// # Arguments
+        ///
+        /// * `key` - The property name (e.g. "server-port")
+        /// * `value` - The property value
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with(mut self, key: &str, value: &str) -> Self {
+            match self.entries.iter_mut().find(|(k, _)| k == key) {
+                Some(entry) => entry.1 = value.to_string(),
+                None => self.entries.push((key.to_string(), value.to_string())),
+            }
+            self
+        }
+
+        /// Gets a property value
+        pub fn get(&self, key: &str) -> Option<&str> {
+            self.entries
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, value)| value.as_str())
+        }
+
+        /// Gets the properties in the order they were set
+        pub fn entries(&self) -> &[(String, String)] {
+            &self.entries
+        }
+    }
+
+    /// What kind of server a server directory runs
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ServerKind {
+        Vanilla,
+        Fabric,
+        Forge,
+        NeoForge,
+    }
+
+    /// How a server is started
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum ServerEntryPoint {
+        /// `java -jar <path>`
+        Jar(PathBuf),
+
+        /// `java @user_jvm_args.txt @<path>`, as written by the Forge and NeoForge
+        /// installers since 1.17
+        ArgumentFile(PathBuf),
+    }
+
+    /// An installed server that can be started
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct ServerLauncher {
+        /// What kind of server it is
+        pub kind: ServerKind,
+
+        /// How it is started, relative to the server directory
+        pub entry_point: ServerEntryPoint,
+    }
+
+    impl ServerLauncher {
+        /// Finds the server installed in a directory
+        ///
+        /// Prefers a NeoForge or Forge argument file, then the Fabric server launcher,
+        /// then a legacy Forge jar and finally the vanilla `server.jar`. If several
+        /// loader versions are installed, the newest one is used.
+        ///
+        /// # Arguments
+        ///
+        /// * `server_dir` - The server directory
+        ///
+        /// # Returns
+        ///
+        /// * `Option<ServerLauncher>` - The server, or None if nothing is installed
+        pub fn detect(server_dir: &Path) -> Option<Self> {
+            let args_file = if cfg!(windows) {
+                "win_args.txt"
+            } else {
+                "unix_args.txt"
+            };
+            let loaders = [
+                (ServerKind::NeoForge, "libraries/net/neoforged/neoforge"),
+                (ServerKind::NeoForge, "libraries/net/neoforged/forge"),
+                (ServerKind::Forge, "libraries/net/minecraftforge/forge"),
+            ];
+            for (kind, directory) in loaders {
+                let mut versions = sorted_entries(&server_dir.join(directory));
+                versions.sort_by(|a, b| {
+                    compare_versions(
+                        &a.file_name().unwrap_or_default().to_string_lossy(),
+                        &b.file_name().unwrap_or_default().to_string_lossy(),
+                    )
+                });
+                let path = versions
+                    .into_iter()
+                    .rev()
+                    .map(|version| version.join(args_file))
+                    .find(|path| path.is_file());
+                if let Some(path) = path {
+                    let relative = path.strip_prefix(server_dir).unwrap_or(&path);
+                    return Some(ServerLauncher {
+                        kind,
+                        entry_point: ServerEntryPoint::ArgumentFile(relative.to_path_buf()),
+                    });
+                }
+            }
+
+            let jars: Vec<String> = sorted_entries(server_dir)
+                .iter()
+                .filter_map(|path| path.file_name()?.to_str().map(str::to_string))
+                .filter(|name| name.ends_with(".jar"))
+                .collect();
+            let find = |kind, matches: &dyn Fn(&str) -> bool| {
+                jars.iter()
+                    .rev()
+                    .find(|name| matches(name))
+                    .map(|name| ServerLauncher {
+                        kind,
+                        entry_point: ServerEntryPoint::Jar(PathBuf::from(name)),
+                    })
+            };
+            find(ServerKind::Fabric, &|name| {
+                name == FABRIC_LAUNCHER_JAR
+                    || (name.starts_with("fabric-server-") && name.contains("launcher"))
+            })
+            .or_else(|| {
+                find(ServerKind::Forge, &|name| {
+                    name.starts_with("forge-") && !name.contains("installer")
+                })
+            })
+            .or_else(|| {
+                find(ServerKind::Vanilla, &|name| {
+                    name == SERVER_JAR || name.starts_with("minecraft_server")
+                })
+            })
+        }
+
+        /// Builds the command line that starts the server
+        ///
+        /// # Arguments
+        ///
+        /// * `server_dir` - The server directory
+        /// * `options` - Java and server options
+        ///
+        /// # Returns
+        ///
+        /// * The command line, starting with the java executable
+        pub fn command(&self, server_dir: &Path, options: &ServerOptions) -> Vec<String> {
+            let mut command = vec![options.java.to_string_lossy().to_string()];
+            if let Some(min) = options.min_memory_mb {
+                command.push(format!("-Xms{}M", min));
+            }
+            if let Some(max) = options.max_memory_mb {
+                command.push(format!("-Xmx{}M", max));
+            }
+            command.extend(options.jvm_args.iter().cloned());
+
+            match &self.entry_point {
+                ServerEntryPoint::Jar(jar) => {
+                    command.push("-jar".to_string());
+                    command.push(jar.to_string_lossy().to_string());
+                }
+                ServerEntryPoint::ArgumentFile(args_file) => {
+                    if server_dir.join("user_jvm_args.txt").is_file() {
+                        command.push("@user_jvm_args.txt".to_string());
+                    }
+                    command.push(format!("@{}", args_file.to_string_lossy()));
+                }
+            }
+
+            if options.nogui {
+                command.push("nogui".to_string());
+            }
+            command.extend(options.server_args.iter().cloned());
+            command
+        }
+    }
+
+    /// Options for starting a dedicated server
+    #[derive(Debug, Clone)]
+    pub struct ServerOptions {
+        /// Path to the java executable
+        pub java: PathBuf,
+
+        /// Initial heap size in megabytes (`-Xms`)
+        pub min_memory_mb: Option<u32>,
+
+        /// Maximum heap size in megabytes (`-Xmx`)
+        pub max_memory_mb: Option<u32>,
+
+        /// Extra JVM arguments
+        pub jvm_args: Vec<String>,
+
+        /// Pass `nogui`, so the server does not open its Swing console
+        pub nogui: bool,
+
+        /// Extra arguments passed to the server
+        pub server_args: Vec<String>,
+    }
+
+    impl Default for ServerOptions {
+        fn default() -> Self {
+            ServerOptions {
+                java: PathBuf::from("java"),
+                min_memory_mb: None,
+                max_memory_mb: None,
+                jvm_args: Vec::new(),
+                nogui: true,
+                server_args: Vec::new(),
+            }
+        }
+    }
+
+    /// A dedicated server directory
+    #[derive(Debug, Clone)]
+    pub struct ServerInstance {
+        /// Root directory holding `versions`
+        pub root_dir: PathBuf,
+
+        /// Working directory of the server (world, eula.txt, server.properties)
+        pub server_dir: PathBuf,
+
+        /// Base URL of the Fabric meta API
+        pub fabric_meta_url: String,
+    }
+
+    impl ServerInstance {
+        /// Creates a server instance.
+        ///
+        /// # Arguments
+        ///
+        /// * `root_dir` - Path to the root directory holding the version JSONs
+        /// * `server_dir` - Working directory of the server
+        pub fn new(root_dir: &Path, server_dir: &Path) -> Self {
+            ServerInstance {
+                root_dir: root_dir.to_path_buf(),
+                server_dir: server_dir.to_path_buf(),
+                fabric_meta_url: FABRIC_META_URL.to_string(),
+            }
+        }
+
+        /// Sets the base URL of the Fabric meta API.
+        ///
+        /// # Arguments
+        ///
+        /// * `url` - The base URL, without a trailing slash
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_fabric_meta_url(mut self, url: &str) -> Self {
+            self.fabric_meta_url = url.trim_end_matches('/').to_string();
+            self
+        }
+
+        /// Downloads the vanilla `server.jar` of a version, verifying its hash
+        ///
+        /// The version JSON must already be in `root_dir/versions`. An existing jar
+        /// with the expected hash is kept.
+        ///
+        /// # Arguments
+        ///
+        /// * `version_id` - The version ID (e.g. "1.20.1")
+        ///
+        /// # Returns
+        ///
+        /// * `Result<PathBuf, ServerError>` - Path to the server jar or an error
+        pub fn install_vanilla(&self, version_id: &str) -> Result<PathBuf, ServerError> {
+            let version = resolve_version(&self.root_dir, version_id, ConflictPolicy::ChildWins)
+                .map_err(|e| ServerError::VersionResolution(e.to_string()))?;
+            let download = version
+                .server_download
+                .ok_or_else(|| ServerError::NoServerDownload(version_id.to_string()))?;
+
+            let jar = self.server_dir.join(SERVER_JAR);
+            networking::ensure_file(&jar, &download.url, &download.sha1)?;
+            Ok(jar)
+        }
+
+        /// Installs the vanilla `server.jar` and downloads the Fabric server launcher
+        ///
+        /// The launcher downloads Fabric's libraries on its first start and then runs
+        /// `server.jar`. Fabric publishes no hash for the launcher, so it is only
+        /// checked to be a jar with a manifest before it replaces the old one.
+        ///
+        /// # Arguments
+        ///
+        /// * `game_version` - The Minecraft version (e.g. "1.20.1")
+        /// * `loader_version` - The Fabric loader version
+        /// * `installer_version` - The Fabric installer version
+        ///
+        /// # Returns
+        ///
+        /// * `Result<PathBuf, ServerError>` - Path to the launcher jar or an error
+        pub fn install_fabric(
+            &self,
+            game_version: &str,
+            loader_version: &str,
+            installer_version: &str,
+        ) -> Result<PathBuf, ServerError> {
+            let url = format!(
+                "{}/v2/versions/loader/{}/{}/{}/server/jar",
+                self.fabric_meta_url, game_version, loader_version, installer_version
+            );
+            self.install_vanilla(game_version)?;
+            let bytes = networking::fetch_bytes(&url)
+                .map_err(|e| ServerError::NetworkingError(format!("{}: {}", url, e)))?;
+            let is_jar = zip::ZipArchive::new(io::Cursor::new(&bytes))
+                .is_ok_and(|mut archive| archive.by_name("META-INF/MANIFEST.MF").is_ok());
+            if !is_jar {
+                return Err(ServerError::InvalidDownload {
+                    url,
+                    reason: "not a jar with a manifest".to_string(),
+                });
+            }
+
+            let jar = self.server_dir.join(FABRIC_LAUNCHER_JAR);
+            file_utils::write_atomically(&jar, &bytes)?;
+            Ok(jar)
+        }
+
+        /// Runs a Forge or NeoForge installer with `--installServer`
+        ///
+        /// The installer writes its libraries, `run.sh` and argument files into the
+        /// server directory, where `ServerLauncher::detect` finds them.
+        ///
+        /// # Arguments
+        ///
+        /// * `java` - Path to the java executable
+        /// * `installer_jar` - Path to the downloaded installer jar
+        pub fn run_installer(&self, java: &Path, installer_jar: &Path) -> Result<(), ServerError> {
+            fs::create_dir_all(&self.server_dir)?;
+            let status = Command::new(java)
+                .arg("-jar")
+                .arg(installer_jar)
+                .arg("--installServer")
+                .arg(&self.server_dir)
+                .current_dir(&self.server_dir)
+                .stdin(Stdio::null())
+                .status()?;
+            if !status.success() {
+                return Err(ServerError::InstallerFailed(status));
+            }
+            Ok(())
+        }
+
+        /// Gets the path of `eula.txt`
+        pub fn eula_path(&self) -> PathBuf {
+            self.server_dir.join("eula.txt")
+        }
+
+        /// Checks whether `eula.txt` accepts the EULA
+        pub fn eula_accepted(&self) -> bool {
+            fs::read_to_string(self.eula_path())
+                .map(|text| {
+                    ServerProperties::parse(&text)
+                        .get("eula")
+                        .is_some_and(|value| value.trim().eq_ignore_ascii_case("true"))
+                })
+                .unwrap_or(false)
+        }
+
+        /// Writes `eula.txt` accepting the Minecraft EULA
+        ///
+        /// Only call this after the user has explicitly agreed to
+        /// <https://aka.ms/MinecraftEULA>.
+        pub fn accept_eula(&self) -> Result<(), ServerError> {
+            fs::create_dir_all(&self.server_dir)?;
+            fs::write(
+                self.eula_path(),
+                "#By changing the setting below to TRUE you are indicating your agreement to our EULA (https://aka.ms/MinecraftEULA).\neula=true\n",
+            )?;
+            Ok(())
+        }
+
+        /// Gets the path of `server.properties`
+        pub fn properties_path(&self) -> PathBuf {
+            self.server_dir.join("server.properties")
+        }
+
+        /// Adds the properties `server.properties` does not have yet
+        ///
+        /// # Arguments
+        ///
+        /// * `properties` - The values to seed
+        ///
+        /// # Returns
+        ///
+        /// * `Result<ServerProperties, ServerError>` - The properties of the file afterwards
+        pub fn seed_properties(
+            &self,
+            properties: &ServerProperties,
+        ) -> Result<ServerProperties, ServerError> {
+            let path = self.properties_path();
+            let mut text = match fs::read_to_string(&path) {
+                Ok(text) => text,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+                Err(e) => return Err(e.into()),
+            };
+            let existing = ServerProperties::parse(&text);
+
+            let missing: Vec<_> = properties
+                .entries()
+                .iter()
+                .filter(|(key, _)| existing.get(key).is_none())
+                .collect();
+            if !missing.is_empty() {
+                if !text.is_empty() && !text.ends_with('\n') {
+                    text.push('\n');
+                }
+                for (key, value) in missing {
+                    text.push_str(&format!("{}={}\n", key, value));
+                }
+                fs::create_dir_all(&self.server_dir)?;
+                fs::write(&path, &text)?;
+            }
+            Ok(ServerProperties::parse(&text))
+        }
+
+        /// Starts the installed server without a GUI
+        ///
+        /// Refuses to start until the EULA has been accepted. The console is attached
+        /// to the returned process: commands go to its stdin and the log comes from
+        /// its stdout and stderr.
+        ///
+        /// # Arguments
+        ///
+        /// * `options` - Java and server options
+        ///
+        /// # Returns
+        ///
+        /// * `Result<ServerProcess, ServerError>` - The running server or an error
+        pub fn launch(&self, options: &ServerOptions) -> Result<ServerProcess, ServerError> {
+            if !self.eula_accepted() {
+                return Err(ServerError::EulaNotAccepted(self.server_dir.clone()));
+            }
+            let launcher = ServerLauncher::detect(&self.server_dir)
+                .ok_or_else(|| ServerError::NoLauncher(self.server_dir.clone()))?;
+
+            let command = launcher.command(&self.server_dir, options);
+            let mut child = Command::new(&command[0])
+                .args(&command[1..])
+                .current_dir(&self.server_dir)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()?;
+
+            Ok(ServerProcess {
+                stdin: child.stdin.take(),
+                child,
+                launcher,
+                command,
+                disposed: false,
+            })
+        }
+    }
+
+    /// A running dedicated server
+    ///
+    /// Dropping the handle does not stop the server; call `stop` or `dispose` for that.
+    #[derive(Debug)]
+    pub struct ServerProcess {
+        child: Child,
+        stdin: Option<ChildStdin>,
+        launcher: ServerLauncher,
+        command: Vec<String>,
+        disposed: bool,
+    }
+
+    impl ServerProcess {
+        /// Gets the process ID
+        pub fn pid(&self) -> u32 {
+            self.child.id()
+        }
+
+        /// Gets the server that was started
+        pub fn launcher(&self) -> &ServerLauncher {
+            &self.launcher
+        }
+
+        /// Gets the command line the server was started with
+        pub fn command(&self) -> &[String] {
+            &self.command
+        }
+
+        /// Sends a console command (e.g. "say hello" or "op Steve")
+        ///
+        /// # Arguments
+        ///
+        /// * `command` - The command, without a leading slash or newline
+        pub fn send_command(&mut self, command: &str) -> io::Result<()> {
+            let stdin = self
+                .stdin
+                .as_mut()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::BrokenPipe, "console is closed"))?;
+            stdin.write_all(format!("{}\n", command.trim_end()).as_bytes())?;
+            stdin.flush()
+        }
+
+        /// Takes the standard output stream; None if it was already taken
+        pub fn take_stdout(&mut self) -> Option<ChildStdout> {
+            self.child.stdout.take()
+        }
+
+        /// Takes the standard error stream; None if it was already taken
+        pub fn take_stderr(&mut self) -> Option<ChildStderr> {
+            self.child.stderr.take()
+        }
+
+        /// Streams the records of the server's console output
+        ///
+        /// Takes both streams; the receiver is closed once the server exits.
+        pub fn output(&mut self) -> Receiver<GameLogRecord> {
+            stream_output(self.take_stdout(), self.take_stderr())
+        }
+
+        /// Waits for the server to exit
+        pub fn wait(&mut self) -> io::Result<ExitStatus> {
+            self.stdin = None;
+            self.child.wait()
+        }
+
+        /// Checks whether the server has exited without blocking
+        pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
+            self.child.try_wait()
+        }
+
+        /// Sends `stop` and waits for the server to save and exit
+        ///
+        /// Kills the server if it has not exited within the timeout.
+        ///
+        /// # Arguments
+        ///
+        /// * `timeout` - How long to wait for a graceful exit
+        pub fn stop(&mut self, timeout: Duration) -> io::Result<ExitStatus> {
+            if let Some(status) = self.child.try_wait()? {
+                return Ok(status);
+            }
+            // A server that closed its console can still be killed below
+            let _ = self.send_command("stop");
+            self.stdin = None;
+
+            let start = Instant::now();
+            while start.elapsed() < timeout {
+                if let Some(status) = self.child.try_wait()? {
+                    return Ok(status);
+                }
+                thread::sleep(POLL_INTERVAL);
+            }
+            self.kill()?;
+            self.child.wait()
+        }
+
+        /// Kills the server without saving
+        pub fn kill(&mut self) -> io::Result<()> {
+            match self.child.kill() {
+                Err(e) if e.kind() != io::ErrorKind::InvalidInput => Err(e),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    impl Disposable for ServerProcess {
+        /// Stops the server if it is still running, waiting `DEFAULT_STOP_TIMEOUT`
+        fn dispose(&mut self) {
+            if self.disposed {
+                return;
+            }
+            self.disposed = true;
+            let _ = self.stop(DEFAULT_STOP_TIMEOUT);
+        }
+    }
+
+    /// Lists the entries of a directory sorted by name; empty if it cannot be read
+    fn sorted_entries(directory: &Path) -> Vec<PathBuf> {
+        let mut entries: Vec<PathBuf> = fs::read_dir(directory)
+            .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+            .unwrap_or_default();
+        entries.sort();
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::server::{
+        ServerEntryPoint, ServerKind, ServerLauncher, ServerOptions, ServerProperties,
+    };
+    use std::path::PathBuf;
+
+    /// Tests parsing `server.properties`
+    #[test]
+    fn test_parse_properties() {
+        let properties = ServerProperties::parse(
+            "#Minecraft server properties\nserver-port=25570\nmotd=A Minecraft Server\n! comment\nlevel-seed=\n",
+        );
+
+        assert_eq!(properties.get("server-port"), Some("25570"));
+        assert_eq!(properties.get("motd"), Some("A Minecraft Server"));
+        assert_eq!(properties.get("level-seed"), Some(""));
+        assert_eq!(properties.get("comment"), None);
+        assert_eq!(properties.entries().len(), 3);
+    }
+
+    /// Tests the command lines of jar and argument file servers
+    #[test]
+    fn test_server_command() {
+        let options = ServerOptions {
+            java: PathBuf::from("/opt/java/bin/java"),
+            max_memory_mb: Some(4096),
+            ..ServerOptions::default()
+        };
+        let vanilla = ServerLauncher {
+            kind: ServerKind::Vanilla,
+            entry_point: ServerEntryPoint::Jar(PathBuf::from("server.jar")),
+        };
+        assert_eq!(
+            vanilla.command(&PathBuf::from("/srv/missing"), &options),
+            [
+                "/opt/java/bin/java",
+                "-Xmx4096M",
+                "-jar",
+                "server.jar",
+                "nogui"
+            ]
+        );
+
+        let forge = ServerLauncher {
+            kind: ServerKind::Forge,
+            entry_point: ServerEntryPoint::ArgumentFile(PathBuf::from(
+                "libraries/net/minecraftforge/forge/1.20.1-47.2.0/unix_args.txt",
+            )),
+        };
+        assert_eq!(
+            forge.command(&PathBuf::from("/srv/missing"), &options),
+            [
+                "/opt/java/bin/java",
+                "-Xmx4096M",
+                "@libraries/net/minecraftforge/forge/1.20.1-47.2.0/unix_args.txt",
+                "nogui"
+            ]
+        );
+    }
+}
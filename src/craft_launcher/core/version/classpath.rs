@@ -0,0 +1,169 @@
+pub mod classpath {
+    use crate::craft_launcher::core::version::base_version::ArgumentValue;
+    use crate::craft_launcher::core::version::rules::rules::{RuleContext, rules_allow};
+    use crate::craft_launcher::core::version::version_resolver::version_resolver::ResolvedVersion;
+    use crate::craft_launcher::java::library_extractor::library_extractor::select_native_libraries;
+    use std::collections::HashSet;
+    use std::error::Error;
+    use std::fmt;
+    use std::path::{Path, PathBuf};
+
+    /// Options for building a classpath
+    #[derive(Debug, Clone)]
+    pub struct ClasspathOptions {
+        /// Platform and features that library rules are evaluated against
+        pub context: RuleContext,
+
+        /// Keep entries listed in Forge's `-DignoreList` off the classpath
+        pub respect_ignore_list: bool,
+
+        /// Fail if any classpath entry does not exist on disk
+        pub fail_on_missing: bool,
+    }
+
+    impl Default for ClasspathOptions {
+        fn default() -> Self {
+            ClasspathOptions {
+                context: RuleContext::current(),
+                respect_ignore_list: true,
+                fail_on_missing: false,
+            }
+        }
+    }
+
+    /// Error returned when classpath entries are missing on disk
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct ClasspathError {
+        /// The entries that do not exist
+        pub missing: Vec<PathBuf>,
+    }
+
+    impl fmt::Display for ClasspathError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let missing: Vec<String> = self
+                .missing
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect();
+            write!(f, "Missing classpath entries: {}", missing.join(", "))
+        }
+    }
+
+    impl Error for ClasspathError {}
+
+    /// Builds the ordered classpath of a resolved version
+    ///
+    /// Libraries come first in the order of the resolved version, skipping those whose rules
+    /// do not apply and those that only provide native classifiers. Of the `natives-*`
+    /// libraries, only the variants the native extractor selects for the platform are
+    /// kept, so other architectures' natives stay off the classpath. The client jar
+    /// (`versions/<jar>/<jar>.jar`) is appended last. Duplicate paths are removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - The resolved version
+    /// * `root_dir` - Path to the root directory containing the libraries and versions folders
+    /// * `options` - Options for building the classpath
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<PathBuf>, ClasspathError>` - The classpath entries or the missing entries
+    pub fn build_classpath(
+        version: &ResolvedVersion,
+        root_dir: &Path,
+        options: &ClasspathOptions,
+    ) -> Result<Vec<PathBuf>, ClasspathError> {
+        let libraries_dir = root_dir.join("libraries");
+        let ignore_list = if options.respect_ignore_list {
+            ignore_list(version)
+        } else {
+            Vec::new()
+        };
+
+        let natives: HashSet<String> =
+            select_native_libraries(&version.libraries, &options.context)
+                .into_iter()
+                .map(|native| native.path)
+                .collect();
+
+        let mut candidates: Vec<PathBuf> = version
+            .libraries
+            .iter()
+            .filter(|library| rules_allow(library.rules(), &options.context))
+            .filter(|library| {
+                let is_native = library.coordinate().is_ok_and(|coordinate| {
+                    coordinate
+                        .classifier
+                        .is_some_and(|classifier| classifier.starts_with("natives-"))
+                });
+                !is_native
+                    || library
+                        .artifact_path()
+                        .is_some_and(|path| natives.contains(&path))
+            })
+            .filter_map(|library| library.artifact_path())
+            .map(|path| libraries_dir.join(path))
+            .collect();
+
+        // The client jar stands in for the jar of the requested version,
+        // so a `${version_name}.jar` ignore entry excludes it as well
+        let version_jar = format!("{}.jar", version.id);
+        if !ignore_list.contains(&version_jar) {
+            candidates.push(
+                root_dir
+                    .join("versions")
+                    .join(&version.jar)
+                    .join(format!("{}.jar", version.jar)),
+            );
+        }
+
+        let mut seen = HashSet::new();
+        let classpath: Vec<PathBuf> = candidates
+            .into_iter()
+            .filter(|path| !is_ignored(path, &ignore_list))
+            .filter(|path| seen.insert(path.clone()))
+            .collect();
+
+        if options.fail_on_missing {
+            let missing: Vec<PathBuf> = classpath
+                .iter()
+                .filter(|path| !path.exists())
+                .cloned()
+                .collect();
+            if !missing.is_empty() {
+                return Err(ClasspathError { missing });
+            }
+        }
+
+        Ok(classpath)
+    }
+
+    /// Gets the entries of the `-DignoreList=` JVM argument used by Forge and NeoForge 1.17+
+    ///
+    /// `${version_name}` is replaced with the ID of the resolved version.
+    pub fn ignore_list(version: &ResolvedVersion) -> Vec<String> {
+        version
+            .jvm_arguments
+            .iter()
+            .filter_map(|argument| match argument {
+                ArgumentValue::String(value) => value.strip_prefix("-DignoreList="),
+                ArgumentValue::RuleArgument { .. } => None,
+            })
+            .flat_map(|list| list.split(','))
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| entry.replace("${version_name}", &version.id))
+            .collect()
+    }
+
+    /// Checks whether a file is excluded by the ignore list
+    ///
+    /// Like Forge's bootstrap launcher, entries match file names by prefix.
+    fn is_ignored(path: &Path, ignore_list: &[String]) -> bool {
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            return false;
+        };
+        ignore_list
+            .iter()
+            .any(|entry| file_name.starts_with(entry.as_str()))
+    }
+}
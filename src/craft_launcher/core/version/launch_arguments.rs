@@ -0,0 +1,302 @@
+pub mod launch_arguments {
+    use crate::craft_launcher::core::version::base_version::{ArgumentValue, ArgumentValueInner};
+    use crate::craft_launcher::core::version::rules::rules::{RuleContext, rules_allow};
+    use crate::craft_launcher::core::version::version_resolver::version_resolver::ResolvedVersion;
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
+
+    /// Separator between classpath and module path entries on this platform
+    #[cfg(windows)]
+    pub const CLASSPATH_SEPARATOR: &str = ";";
+
+    /// Separator between classpath and module path entries on this platform
+    #[cfg(not(windows))]
+    pub const CLASSPATH_SEPARATOR: &str = ":";
+
+    /// Values substituted for `${name}` placeholders in version arguments
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct ArgumentPlaceholders {
+        values: HashMap<String, String>,
+    }
+
+    impl ArgumentPlaceholders {
+        /// Creates an empty set of placeholder values
+        pub fn new() -> Self {
+            ArgumentPlaceholders::default()
+        }
+
+        /// Creates the placeholders every version needs
+        ///
+        /// Sets `version_name`, `version_type`, `library_directory` and `classpath_separator`.
+        ///
+        /// # Arguments
+        ///
+        /// * `version` - The resolved version
+        /// * `root_dir` - Path to the root directory where game data is stored
+        pub fn for_version(version: &ResolvedVersion, root_dir: &Path) -> Self {
+            ArgumentPlaceholders::new()
+                .with("version_name", &version.id)
+                .with("version_type", &version.type_)
+                .with(
+                    "library_directory",
+                    &root_dir.join("libraries").to_string_lossy(),
+                )
+                .with("classpath_separator", CLASSPATH_SEPARATOR)
+        }
+
+        /// Sets a placeholder value.
+        ///
+        /// # Arguments
+        ///
+        /// * `name` - The placeholder name without `${` and `}` (e.g. "game_directory")
+        /// * `value` - The value to substitute
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with(mut self, name: &str, value: &str) -> Self {
+            self.values.insert(name.to_string(), value.to_string());
+            self
+        }
+
+        /// Sets `${classpath}` from classpath entries.
+        ///
+        /// # Arguments
+        ///
+        /// * `entries` - The classpath entries in order
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_classpath(self, entries: &[PathBuf]) -> Self {
+            self.with("classpath", &join_paths(entries))
+        }
+
+        /// Sets `${modulePath}` from module path entries.
+        ///
+        /// # Arguments
+        ///
+        /// * `entries` - The module path entries in order
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_module_path(self, entries: &[PathBuf]) -> Self {
+            self.with("modulePath", &join_paths(entries))
+        }
+
+        /// Gets a placeholder value
+        pub fn get(&self, name: &str) -> Option<&str> {
+            self.values.get(name).map(String::as_str)
+        }
+
+        /// Replaces every known `${name}` in an argument
+        ///
+        /// Unknown placeholders are left untouched so they can be reported or filled later.
+        pub fn substitute(&self, argument: &str) -> String {
+            let mut result = String::with_capacity(argument.len());
+            let mut rest = argument;
+
+            while let Some(start) = rest.find("${") {
+                result.push_str(&rest[..start]);
+                let after = &rest[start + 2..];
+                match after.find('}') {
+                    Some(end) => {
+                        let name = &after[..end];
+                        match self.values.get(name) {
+                            Some(value) => result.push_str(value),
+                            None => result.push_str(&rest[start..start + 3 + end]),
+                        }
+                        rest = &after[end + 1..];
+                    }
+                    None => {
+                        result.push_str(&rest[start..]);
+                        rest = "";
+                    }
+                }
+            }
+            result.push_str(rest);
+            result
+        }
+
+        /// Substitutes placeholders in every argument
+        pub fn substitute_all(&self, arguments: &[String]) -> Vec<String> {
+            arguments
+                .iter()
+                .map(|argument| self.substitute(argument))
+                .collect()
+        }
+    }
+
+    /// Flattens version arguments, keeping the ones whose rules allow them
+    ///
+    /// # Arguments
+    ///
+    /// * `arguments` - The `game` or `jvm` arguments of a version
+    /// * `context` - The platform and features to evaluate rules against
+    ///
+    /// # Returns
+    ///
+    /// * The arguments as plain strings, placeholders not yet substituted
+    pub fn flatten_arguments(arguments: &[ArgumentValue], context: &RuleContext) -> Vec<String> {
+        let mut result = Vec::new();
+        for argument in arguments {
+            match argument {
+                ArgumentValue::String(value) => result.push(value.clone()),
+                ArgumentValue::RuleArgument { rules, value } => {
+                    if !rules_allow(rules, context) {
+                        continue;
+                    }
+                    match value {
+                        ArgumentValueInner::Single(value) => result.push(value.clone()),
+                        ArgumentValueInner::Multiple(values) => result.extend(values.clone()),
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// Removes the module path option from JVM arguments and returns its entries
+    ///
+    /// Modern Forge and NeoForge pass BootstrapLauncher's module path as
+    /// `-p <entries>` or `--module-path <entries>`. Taking it out lets the builder own
+    /// the module path like it owns the classpath. Call this after substitution, so the
+    /// entries are real paths joined with the platform separator.
+    ///
+    /// # Arguments
+    ///
+    /// * `arguments` - Substituted JVM arguments; the module path option is removed
+    ///
+    /// # Returns
+    ///
+    /// * The module path entries, empty if there was no module path option
+    pub fn take_module_path(arguments: &mut Vec<String>) -> Vec<PathBuf> {
+        let mut entries = Vec::new();
+        let mut index = 0;
+
+        while index < arguments.len() {
+            let argument = &arguments[index];
+            if argument == "-p" || argument == "--module-path" {
+                if index + 1 < arguments.len() {
+                    entries.extend(split_paths(&arguments[index + 1]));
+                    arguments.drain(index..index + 2);
+                } else {
+                    arguments.remove(index);
+                }
+            } else if let Some(value) = argument.strip_prefix("--module-path=") {
+                entries.extend(split_paths(value));
+                arguments.remove(index);
+            } else {
+                index += 1;
+            }
+        }
+
+        entries
+    }
+
+    /// Joins classpath or module path entries with the platform separator
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - The paths to join
+    ///
+    /// # Returns
+    ///
+    /// * The entries separated by `CLASSPATH_SEPARATOR`
+    pub fn join_paths(entries: &[PathBuf]) -> String {
+        entries
+            .iter()
+            .map(|entry| entry.to_string_lossy().into_owned())
+            .collect::<Vec<_>>()
+            .join(CLASSPATH_SEPARATOR)
+    }
+
+    /// Splits a path list joined with the platform separator
+    fn split_paths(value: &str) -> Vec<PathBuf> {
+        value
+            .split(CLASSPATH_SEPARATOR)
+            .filter(|entry| !entry.is_empty())
+            .map(PathBuf::from)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::launch_arguments::{
+        ArgumentPlaceholders, CLASSPATH_SEPARATOR, flatten_arguments, take_module_path,
+    };
+    use crate::craft_launcher::core::version::base_version::ArgumentValue;
+    use crate::craft_launcher::core::version::rules::rules::{Platform, RuleContext};
+    use std::path::PathBuf;
+
+    /// Tests substitution of known and unknown placeholders
+    #[test]
+    fn test_substitute() {
+        let placeholders = ArgumentPlaceholders::new()
+            .with("library_directory", "/mc/libraries")
+            .with("classpath_separator", ":");
+
+        assert_eq!(
+            placeholders.substitute("${library_directory}/a.jar${classpath_separator}b.jar"),
+            "/mc/libraries/a.jar:b.jar"
+        );
+        assert_eq!(
+            placeholders.substitute("--token ${auth_access_token}"),
+            "--token ${auth_access_token}"
+        );
+        assert_eq!(placeholders.substitute("broken ${end"), "broken ${end");
+    }
+
+    /// Tests that rule arguments are filtered by the context
+    #[test]
+    fn test_flatten_arguments() {
+        let arguments: Vec<ArgumentValue> = serde_json::from_str(
+            r#"[
+                "-Djava.library.path=${natives_directory}",
+                {"rules": [{"action": "allow", "os": {"name": "osx"}}], "value": ["-XstartOnFirstThread"]},
+                {"rules": [{"action": "allow", "os": {"arch": "x86"}}], "value": "-Xss1M"}
+            ]"#,
+        )
+        .unwrap();
+
+        let linux = RuleContext::new(Platform::new("linux", "x86"));
+        assert_eq!(
+            flatten_arguments(&arguments, &linux),
+            vec!["-Djava.library.path=${natives_directory}", "-Xss1M"]
+        );
+    }
+
+    /// Tests extracting the module path from NeoForge style arguments
+    #[test]
+    fn test_take_module_path() {
+        let mut arguments = vec![
+            "-DlibraryDirectory=/mc/libraries".to_string(),
+            "-p".to_string(),
+            format!(
+                "/mc/libraries/a.jar{}/mc/libraries/b.jar",
+                CLASSPATH_SEPARATOR
+            ),
+            "--add-modules".to_string(),
+            "ALL-MODULE-PATH".to_string(),
+        ];
+
+        let entries = take_module_path(&mut arguments);
+        assert_eq!(
+            entries,
+            vec![
+                PathBuf::from("/mc/libraries/a.jar"),
+                PathBuf::from("/mc/libraries/b.jar")
+            ]
+        );
+        assert_eq!(
+            arguments,
+            vec![
+                "-DlibraryDirectory=/mc/libraries",
+                "--add-modules",
+                "ALL-MODULE-PATH"
+            ]
+        );
+    }
+}
@@ -0,0 +1,404 @@
+pub mod library_conflict {
+    use crate::craft_launcher::core::version::library_parser::library_parser::LibraryInfo;
+    use crate::craft_launcher::core::version::maven_coordinate::maven_coordinate::{
+        MavenCoordinate, compare_versions,
+    };
+    use std::cmp::Ordering;
+    use std::collections::HashMap;
+    use std::error::Error;
+    use std::fmt;
+
+    /// How to decide between two libraries with the same group, artifact and classifier
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub enum ConflictPolicy {
+        /// The library declared by the child version (e.g. Fabric or Forge) is kept
+        #[default]
+        ChildWins,
+
+        /// The library with the highest version is kept
+        HighestVersion,
+
+        /// Conflicting versions are reported as an error
+        Error,
+    }
+
+    /// Identifies libraries that must not appear on the classpath more than once
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct LibraryKey {
+        /// Group ID of the library
+        pub group_id: String,
+
+        /// Artifact ID of the library
+        pub artifact_id: String,
+
+        /// Classifier of the library, if any
+        pub classifier: Option<String>,
+    }
+
+    impl LibraryKey {
+        /// Builds the key of a Maven coordinate
+        pub fn from_coordinate(coordinate: &MavenCoordinate) -> Self {
+            LibraryKey {
+                group_id: coordinate.group_id.clone(),
+                artifact_id: coordinate.artifact_id.clone(),
+                classifier: coordinate.classifier.clone(),
+            }
+        }
+    }
+
+    impl fmt::Display for LibraryKey {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}:{}", self.group_id, self.artifact_id)?;
+            if let Some(classifier) = &self.classifier {
+                write!(f, ":{}", classifier)?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Why a library was removed from the list
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum DropReason {
+        /// The exact same library was already declared
+        Duplicate,
+
+        /// The child version declares the same library
+        OverriddenByChild,
+
+        /// Another declaration has a higher version
+        LowerVersion,
+    }
+
+    impl fmt::Display for DropReason {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                DropReason::Duplicate => write!(f, "duplicate declaration"),
+                DropReason::OverriddenByChild => write!(f, "overridden by child version"),
+                DropReason::LowerVersion => write!(f, "lower version"),
+            }
+        }
+    }
+
+    /// A library removed during conflict resolution
+    #[derive(Debug, Clone)]
+    pub struct DroppedLibrary {
+        /// The library that was removed
+        pub library: LibraryInfo,
+
+        /// The name of the library that was kept instead
+        pub kept: String,
+
+        /// Why the library was removed
+        pub reason: DropReason,
+    }
+
+    impl fmt::Display for DroppedLibrary {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "dropped {} in favour of {} ({})",
+                self.library.name(),
+                self.kept,
+                self.reason
+            )
+        }
+    }
+
+    /// The result of resolving library conflicts
+    #[derive(Debug, Clone, Default)]
+    pub struct ConflictResolution {
+        /// The libraries to use, child libraries first
+        pub libraries: Vec<LibraryInfo>,
+
+        /// The libraries that were removed
+        pub dropped: Vec<DroppedLibrary>,
+    }
+
+    /// Error returned by the `ConflictPolicy::Error` policy
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct LibraryConflictError {
+        /// The conflicting group, artifact and classifier
+        pub key: LibraryKey,
+
+        /// The name declared by the parent version
+        pub parent: String,
+
+        /// The name declared by the child version
+        pub child: String,
+    }
+
+    impl fmt::Display for LibraryConflictError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "Library conflict for {}: parent declares {}, child declares {}",
+                self.key, self.parent, self.child
+            )
+        }
+    }
+
+    impl Error for LibraryConflictError {}
+
+    /// Resolves conflicts between the libraries of a parent version and a child version
+    ///
+    /// Libraries are keyed by group, artifact and classifier. Within one list only exact
+    /// duplicates are removed, because vanilla declares the same artifact several times with
+    /// different OS rules. Between the two lists the policy decides which version is kept.
+    /// Libraries whose name is not a valid Maven coordinate are always kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent` - Libraries of the inherited version (e.g. vanilla)
+    /// * `child` - Libraries of the inheriting version (e.g. Fabric or Forge)
+    /// * `policy` - How to resolve conflicting versions
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ConflictResolution, LibraryConflictError>` - The kept and dropped libraries
+    pub fn resolve_library_conflicts(
+        parent: &[LibraryInfo],
+        child: &[LibraryInfo],
+        policy: ConflictPolicy,
+    ) -> Result<ConflictResolution, LibraryConflictError> {
+        let mut resolution = ConflictResolution::default();
+
+        let child = deduplicate_layer(child, &mut resolution.dropped);
+        let parent = deduplicate_layer(parent, &mut resolution.dropped);
+
+        // Remember the versions declared by the child for each key
+        let mut child_entries: HashMap<LibraryKey, Vec<(usize, MavenCoordinate)>> = HashMap::new();
+        for (index, library) in child.iter().enumerate() {
+            if let Ok(coordinate) = library.coordinate() {
+                child_entries
+                    .entry(LibraryKey::from_coordinate(&coordinate))
+                    .or_default()
+                    .push((index, coordinate));
+            }
+        }
+
+        let mut child_kept = vec![true; child.len()];
+        let mut parent_kept = vec![true; parent.len()];
+
+        for (parent_index, library) in parent.iter().enumerate() {
+            let Ok(coordinate) = library.coordinate() else {
+                continue;
+            };
+            let key = LibraryKey::from_coordinate(&coordinate);
+            let Some(entries) = child_entries.get(&key) else {
+                continue;
+            };
+
+            for (child_index, child_coordinate) in entries {
+                if !child_kept[*child_index] {
+                    continue;
+                }
+
+                let ordering = compare_versions(&coordinate.version, &child_coordinate.version);
+                let parent_wins = match policy {
+                    _ if ordering == Ordering::Equal => false,
+                    ConflictPolicy::ChildWins => false,
+                    ConflictPolicy::HighestVersion => ordering == Ordering::Greater,
+                    ConflictPolicy::Error => {
+                        return Err(LibraryConflictError {
+                            key,
+                            parent: library.name().to_string(),
+                            child: child[*child_index].name().to_string(),
+                        });
+                    }
+                };
+
+                if parent_wins {
+                    child_kept[*child_index] = false;
+                    resolution.dropped.push(DroppedLibrary {
+                        library: child[*child_index].clone(),
+                        kept: library.name().to_string(),
+                        reason: DropReason::LowerVersion,
+                    });
+                } else {
+                    parent_kept[parent_index] = false;
+                    resolution.dropped.push(DroppedLibrary {
+                        library: library.clone(),
+                        kept: child[*child_index].name().to_string(),
+                        reason: match (policy, ordering) {
+                            (_, Ordering::Equal) => DropReason::Duplicate,
+                            (ConflictPolicy::HighestVersion, _) => DropReason::LowerVersion,
+                            _ => DropReason::OverriddenByChild,
+                        },
+                    });
+                    break;
+                }
+            }
+        }
+
+        resolution.libraries = child
+            .into_iter()
+            .zip(child_kept)
+            .chain(parent.into_iter().zip(parent_kept))
+            .filter(|(_, kept)| *kept)
+            .map(|(library, _)| library)
+            .collect();
+
+        Ok(resolution)
+    }
+
+    /// Removes libraries declared more than once with the exact same name within one list
+    fn deduplicate_layer(
+        libraries: &[LibraryInfo],
+        dropped: &mut Vec<DroppedLibrary>,
+    ) -> Vec<LibraryInfo> {
+        let mut result: Vec<LibraryInfo> = Vec::new();
+
+        for library in libraries {
+            if result.iter().any(|kept| is_same_declaration(kept, library)) {
+                dropped.push(DroppedLibrary {
+                    library: library.clone(),
+                    kept: library.name().to_string(),
+                    reason: DropReason::Duplicate,
+                });
+                continue;
+            }
+            result.push(library.clone());
+        }
+
+        result
+    }
+
+    /// Checks whether two libraries are the same declaration
+    ///
+    /// Rule-gated declarations of the same name (e.g. per-OS LWJGL entries) are kept apart.
+    fn is_same_declaration(a: &LibraryInfo, b: &LibraryInfo) -> bool {
+        if a.name() != b.name() {
+            return false;
+        }
+
+        match (a, b) {
+            (LibraryInfo::Base(a), LibraryInfo::Base(b)) => {
+                serde_json::to_value(&a.rules).ok() == serde_json::to_value(&b.rules).ok()
+                    && serde_json::to_value(&a.natives).ok()
+                        == serde_json::to_value(&b.natives).ok()
+            }
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::library_conflict::{ConflictPolicy, DropReason, resolve_library_conflicts};
+    use crate::craft_launcher::core::version::library_parser::library_parser::LibraryInfo;
+
+    fn library(name: &str) -> LibraryInfo {
+        LibraryInfo::Generic {
+            name: name.to_string(),
+            path: None,
+            url: None,
+            sha1: None,
+            size: 0,
+        }
+    }
+
+    fn names(libraries: &[LibraryInfo]) -> Vec<&str> {
+        libraries.iter().map(|library| library.name()).collect()
+    }
+
+    /// Tests that the child version replaces the parent declaration by default
+    #[test]
+    fn test_child_wins() {
+        let parent = vec![
+            library("org.ow2.asm:asm:9.8"),
+            library("com.google.guava:guava:32.1.2-jre"),
+        ];
+        let child = vec![library("org.ow2.asm:asm:9.7.1")];
+
+        let resolution =
+            resolve_library_conflicts(&parent, &child, ConflictPolicy::ChildWins).unwrap();
+
+        assert_eq!(
+            names(&resolution.libraries),
+            vec!["org.ow2.asm:asm:9.7.1", "com.google.guava:guava:32.1.2-jre"]
+        );
+        assert_eq!(resolution.dropped.len(), 1);
+        assert_eq!(resolution.dropped[0].library.name(), "org.ow2.asm:asm:9.8");
+        assert_eq!(resolution.dropped[0].kept, "org.ow2.asm:asm:9.7.1");
+        assert_eq!(resolution.dropped[0].reason, DropReason::OverriddenByChild);
+    }
+
+    /// Tests that the highest version is kept regardless of which layer declares it
+    #[test]
+    fn test_highest_version() {
+        let parent = vec![
+            library("org.ow2.asm:asm:9.8"),
+            library("org.lwjgl:lwjgl:3.3.1"),
+        ];
+        let child = vec![
+            library("org.ow2.asm:asm:9.7.1"),
+            library("org.lwjgl:lwjgl:3.3.3"),
+        ];
+
+        let resolution =
+            resolve_library_conflicts(&parent, &child, ConflictPolicy::HighestVersion).unwrap();
+
+        assert_eq!(
+            names(&resolution.libraries),
+            vec!["org.lwjgl:lwjgl:3.3.3", "org.ow2.asm:asm:9.8"]
+        );
+        assert_eq!(resolution.dropped.len(), 2);
+        assert!(
+            resolution
+                .dropped
+                .iter()
+                .all(|dropped| dropped.reason == DropReason::LowerVersion)
+        );
+    }
+
+    /// Tests that classifiers are part of the key
+    #[test]
+    fn test_classifier_is_part_of_key() {
+        let parent = vec![
+            library("org.lwjgl:lwjgl:3.3.3"),
+            library("org.lwjgl:lwjgl:3.3.3:natives-linux"),
+        ];
+        let child = vec![library("org.lwjgl:lwjgl:3.3.3")];
+
+        let resolution = resolve_library_conflicts(&parent, &child, ConflictPolicy::Error).unwrap();
+
+        assert_eq!(
+            names(&resolution.libraries),
+            vec![
+                "org.lwjgl:lwjgl:3.3.3",
+                "org.lwjgl:lwjgl:3.3.3:natives-linux"
+            ]
+        );
+        assert_eq!(resolution.dropped[0].reason, DropReason::Duplicate);
+    }
+
+    /// Tests that the error policy reports conflicting versions
+    #[test]
+    fn test_error_policy() {
+        let parent = vec![library("com.google.guava:guava:32.1.2-jre")];
+        let child = vec![library("com.google.guava:guava:31.1-jre")];
+
+        let error = resolve_library_conflicts(&parent, &child, ConflictPolicy::Error).unwrap_err();
+
+        assert_eq!(error.key.to_string(), "com.google.guava:guava");
+        assert_eq!(error.parent, "com.google.guava:guava:32.1.2-jre");
+        assert_eq!(error.child, "com.google.guava:guava:31.1-jre");
+    }
+
+    /// Tests that duplicates inside one list are removed
+    #[test]
+    fn test_duplicates_within_layer() {
+        let child = vec![
+            library("net.fabricmc:sponge-mixin:0.15.4"),
+            library("net.fabricmc:sponge-mixin:0.15.4"),
+        ];
+
+        let resolution = resolve_library_conflicts(&[], &child, ConflictPolicy::ChildWins).unwrap();
+
+        assert_eq!(
+            names(&resolution.libraries),
+            vec!["net.fabricmc:sponge-mixin:0.15.4"]
+        );
+        assert_eq!(resolution.dropped[0].reason, DropReason::Duplicate);
+    }
+}
@@ -0,0 +1,556 @@
+pub mod library_installer {
+    use crate::craft_launcher::core::version::base_version::Library as BaseLibrary;
+    use crate::craft_launcher::core::version::library_parser::library_parser::{
+        LibraryInfo, extract_base_library_path,
+    };
+    use crate::craft_launcher::core::version::maven_coordinate::maven_coordinate::MavenCoordinate;
+    use crate::craft_launcher::utils::file_operations::file_utils;
+    use crate::craft_launcher::utils::networking::networking;
+    use std::error::Error;
+    use std::fmt;
+    use std::io;
+    use std::path::{Path, PathBuf};
+
+    /// Maven repositories tried, in order, when a library cannot be fetched from its declared URL
+    pub const DEFAULT_MAVEN_REPOSITORIES: [&str; 5] = [
+        "https://libraries.minecraft.net/",
+        "https://maven.minecraftforge.net/",
+        "https://maven.neoforged.net/releases/",
+        "https://maven.fabricmc.net/",
+        "https://repo1.maven.org/maven2/",
+    ];
+
+    /// Error type for library installation operations
+    #[derive(Debug)]
+    pub enum LibraryInstallError {
+        /// A file system operation failed
+        IoError(io::Error),
+
+        /// The library name is not a valid Maven coordinate
+        InvalidLibraryName(String),
+
+        /// Every candidate URL failed; contains the URL and reason of each attempt
+        DownloadFailed {
+            name: String,
+            attempts: Vec<(String, String)>,
+        },
+
+        /// The downloaded file does not match the expected SHA-1 hash
+        HashMismatch {
+            name: String,
+            expected: String,
+            actual: String,
+        },
+    }
+
+    impl fmt::Display for LibraryInstallError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                LibraryInstallError::IoError(e) => write!(f, "Library install IO error: {}", e),
+                LibraryInstallError::InvalidLibraryName(name) => {
+                    write!(f, "Invalid library name: {}", name)
+                }
+                LibraryInstallError::DownloadFailed { name, attempts } => {
+                    write!(f, "Failed to download library {}", name)?;
+                    for (url, reason) in attempts {
+                        write!(f, "\n  {}: {}", url, reason)?;
+                    }
+                    Ok(())
+                }
+                LibraryInstallError::HashMismatch {
+                    name,
+                    expected,
+                    actual,
+                } => write!(
+                    f,
+                    "SHA-1 mismatch for library {}: expected {}, got {}",
+                    name, expected, actual
+                ),
+            }
+        }
+    }
+
+    impl Error for LibraryInstallError {}
+
+    impl From<io::Error> for LibraryInstallError {
+        fn from(error: io::Error) -> Self {
+            LibraryInstallError::IoError(error)
+        }
+    }
+
+    /// Result of installing a single library
+    #[derive(Debug, Clone)]
+    pub struct InstalledLibrary {
+        /// Maven artifact name
+        pub name: String,
+
+        /// Absolute path of the library file under `libraries/`
+        pub path: PathBuf,
+
+        /// SHA-1 hash of the file on disk
+        pub sha1: String,
+
+        /// Whether the hash was checked against the version JSON or a `.sha1` sidecar
+        pub verified: bool,
+
+        /// URL the file was downloaded from, or None if it was already installed
+        pub source_url: Option<String>,
+    }
+
+    /// Download plan for a library, resolved from its LibraryInfo
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct LibraryTarget {
+        /// Maven artifact name
+        pub name: String,
+
+        /// Path of the artifact relative to the libraries directory
+        pub path: String,
+
+        /// Candidate URLs, in the order they are tried
+        pub urls: Vec<String>,
+
+        /// Expected SHA-1 hash from the version JSON, if known
+        pub sha1: Option<String>,
+
+        /// Expected size in bytes, or 0 if unknown
+        pub size: i64,
+    }
+
+    /// Installs libraries from the output of `convert_version_to_libraries` into a `libraries/` directory.
+    ///
+    /// Each library is first fetched from the URL declared in its version JSON, then from each
+    /// configured Maven repository in order. When the version JSON has no hash for a library,
+    /// the `.sha1` sidecar file published next to the artifact is used for verification and
+    /// stored alongside the downloaded file.
+    pub struct LibraryInstaller {
+        /// The directory libraries are stored in
+        libraries_dir: PathBuf,
+        /// Fallback Maven repositories
+        repositories: Vec<String>,
+    }
+
+    impl LibraryInstaller {
+        /// Creates a new LibraryInstaller storing libraries under `root_dir/libraries`.
+        ///
+        /// # Arguments
+        ///
+        /// * `root_dir` - Path to the root directory where game data is stored
+        ///
+        /// # Returns
+        ///
+        /// * A new `LibraryInstaller` using `DEFAULT_MAVEN_REPOSITORIES` as fallback
+        pub fn new(root_dir: &Path) -> Self {
+            LibraryInstaller {
+                libraries_dir: root_dir.join("libraries"),
+                repositories: DEFAULT_MAVEN_REPOSITORIES
+                    .iter()
+                    .map(|r| r.to_string())
+                    .collect(),
+            }
+        }
+
+        /// Replaces the fallback Maven repositories.
+        ///
+        /// # Arguments
+        ///
+        /// * `repositories` - Repository base URLs, tried in order
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_repositories<I, S>(mut self, repositories: I) -> Self
+        where
+            I: IntoIterator<Item = S>,
+            S: Into<String>,
+        {
+            self.repositories = repositories.into_iter().map(|r| r.into()).collect();
+            self
+        }
+
+        /// Adds a fallback Maven repository after the existing ones.
+        ///
+        /// # Arguments
+        ///
+        /// * `repository` - Repository base URL
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn add_repository<S: Into<String>>(mut self, repository: S) -> Self {
+            self.repositories.push(repository.into());
+            self
+        }
+
+        /// Gets the directory libraries are installed into.
+        pub fn libraries_dir(&self) -> &Path {
+            &self.libraries_dir
+        }
+
+        /// Installs a single library.
+        ///
+        /// # Arguments
+        ///
+        /// * `library` - The library to install
+        ///
+        /// # Returns
+        ///
+        /// * `Ok(Some(InstalledLibrary))` - The library is present and verified
+        /// * `Ok(None)` - The library has no main artifact (e.g. native-only libraries)
+        /// * `Err(LibraryInstallError)` - The library could not be installed
+        pub fn install(
+            &self,
+            library: &LibraryInfo,
+        ) -> Result<Option<InstalledLibrary>, LibraryInstallError> {
+            let target = match self.target(library)? {
+                Some(target) => target,
+                None => return Ok(None),
+            };
+
+            let dest = self.libraries_dir.join(&target.path);
+
+            // Reuse the existing file unless it fails verification
+            if dest.is_file()
+                && let Some(installed) = self.check_existing(&target, &dest)?
+            {
+                return Ok(Some(installed));
+            }
+
+            self.download(&target, &dest).map(Some)
+        }
+
+        /// Installs every library in the list.
+        ///
+        /// # Arguments
+        ///
+        /// * `libraries` - The libraries to install
+        ///
+        /// # Returns
+        ///
+        /// * `Result<Vec<InstalledLibrary>, LibraryInstallError>` - The installed libraries, stopping at the first failure
+        pub fn install_all(
+            &self,
+            libraries: &[LibraryInfo],
+        ) -> Result<Vec<InstalledLibrary>, LibraryInstallError> {
+            let mut installed = Vec::new();
+            for library in libraries {
+                if let Some(result) = self.install(library)? {
+                    installed.push(result);
+                }
+            }
+            Ok(installed)
+        }
+
+        /// Resolves the download plans of the libraries that have a main artifact.
+        ///
+        /// # Arguments
+        ///
+        /// * `libraries` - The libraries to resolve
+        ///
+        /// # Returns
+        ///
+        /// * `Result<Vec<LibraryTarget>, LibraryInstallError>` - The plans in library order
+        pub fn targets(
+            &self,
+            libraries: &[LibraryInfo],
+        ) -> Result<Vec<LibraryTarget>, LibraryInstallError> {
+            let mut targets = Vec::new();
+            for library in libraries {
+                targets.extend(self.target(library)?);
+            }
+            Ok(targets)
+        }
+
+        /// Checks whether a library is installed the way `install` would accept it.
+        ///
+        /// # Arguments
+        ///
+        /// * `target` - The download plan of the library
+        ///
+        /// # Returns
+        ///
+        /// * `true` if the file exists and matches the known or sidecar hash, or no hash is known
+        pub fn is_installed(&self, target: &LibraryTarget) -> bool {
+            let dest = self.libraries_dir.join(&target.path);
+            dest.is_file() && matches!(self.check_existing(target, &dest), Ok(Some(_)))
+        }
+
+        /// Resolves the relative path, candidate URLs and expected hash of a library
+        ///
+        /// # Arguments
+        ///
+        /// * `library` - The library to resolve
+        ///
+        /// # Returns
+        ///
+        /// * `Ok(Some(LibraryTarget))` - The download plan
+        /// * `Ok(None)` - The library has no main artifact (e.g. native-only libraries)
+        /// * `Err(LibraryInstallError)` - The library name is not a valid Maven coordinate
+        pub fn target(
+            &self,
+            library: &LibraryInfo,
+        ) -> Result<Option<LibraryTarget>, LibraryInstallError> {
+            let (name, path, declared_url, sha1, size) = match library {
+                LibraryInfo::Base(base) => match extract_base_library_path(base) {
+                    Some((path, url, sha1, size)) => {
+                        (base.name.clone(), Some(path), Some(url), Some(sha1), size)
+                    }
+                    None if has_only_natives(base) => return Ok(None),
+                    None => (base.name.clone(), None, None, None, 0),
+                },
+                LibraryInfo::Generic {
+                    name,
+                    path,
+                    url,
+                    sha1,
+                    size,
+                } => (name.clone(), path.clone(), url.clone(), sha1.clone(), *size),
+            };
+
+            let mut urls = Vec::new();
+            let path = match path {
+                // A known path means the declared URL points at the artifact itself
+                Some(path) => {
+                    if let Some(url) = declared_url.filter(|u| !u.is_empty()) {
+                        urls.push(url);
+                    }
+                    path
+                }
+                // Otherwise the declared URL is a repository base (Fabric style)
+                None => {
+                    let path = match MavenCoordinate::parse(&name) {
+                        Ok(coordinate) => coordinate.to_path(),
+                        Err(_) => return Err(LibraryInstallError::InvalidLibraryName(name)),
+                    };
+                    if let Some(url) = declared_url.filter(|u| !u.is_empty()) {
+                        urls.push(join_url(&url, &path));
+                    }
+                    path
+                }
+            };
+
+            for repository in &self.repositories {
+                let url = join_url(repository, &path);
+                if !urls.contains(&url) {
+                    urls.push(url);
+                }
+            }
+
+            Ok(Some(LibraryTarget {
+                name,
+                path,
+                urls,
+                sha1: sha1.filter(|s| is_known_hash(s)),
+                size,
+            }))
+        }
+
+        /// Verifies an already installed file against the known or sidecar hash
+        ///
+        /// A file with neither is accepted as unverified, since there is nothing to
+        /// compare a new download against either.
+        fn check_existing(
+            &self,
+            target: &LibraryTarget,
+            dest: &PathBuf,
+        ) -> Result<Option<InstalledLibrary>, LibraryInstallError> {
+            let expected = target
+                .sha1
+                .clone()
+                .or_else(|| read_sidecar(&sidecar_path(dest)));
+            let actual = file_utils::calculate_sha1(dest)?;
+
+            match expected {
+                Some(expected) if expected.eq_ignore_ascii_case(&actual) => {
+                    Ok(Some(InstalledLibrary {
+                        name: target.name.clone(),
+                        path: dest.clone(),
+                        sha1: actual,
+                        verified: true,
+                        source_url: None,
+                    }))
+                }
+                None => Ok(Some(InstalledLibrary {
+                    name: target.name.clone(),
+                    path: dest.clone(),
+                    sha1: actual,
+                    verified: false,
+                    source_url: None,
+                })),
+                // A stale or corrupted file is downloaded again
+                Some(_) => Ok(None),
+            }
+        }
+
+        /// Downloads a library from the first candidate URL that succeeds and verifies it
+        ///
+        /// A URL serving a file with the wrong size or hash is skipped like one that
+        /// fails. If every URL served a file with the wrong hash, `HashMismatch` is
+        /// returned, otherwise `DownloadFailed` lists each attempt.
+        fn download(
+            &self,
+            target: &LibraryTarget,
+            dest: &Path,
+        ) -> Result<InstalledLibrary, LibraryInstallError> {
+            let mut attempts = Vec::new();
+            let mut mismatch = None;
+            let mut other_failure = false;
+
+            for url in &target.urls {
+                let bytes = match networking::fetch_bytes(url) {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        attempts.push((url.clone(), e.to_string()));
+                        other_failure = true;
+                        continue;
+                    }
+                };
+
+                if target.size > 0 && bytes.len() as i64 != target.size {
+                    attempts.push((
+                        url.clone(),
+                        format!("expected {} bytes, got {}", target.size, bytes.len()),
+                    ));
+                    other_failure = true;
+                    continue;
+                }
+
+                // Fall back to the .sha1 sidecar published next to the artifact
+                let sidecar = match &target.sha1 {
+                    Some(_) => None,
+                    None => networking::fetch_bytes(&format!("{}.sha1", url))
+                        .ok()
+                        .and_then(|text| parse_sidecar(&String::from_utf8_lossy(&text))),
+                };
+                let expected = target.sha1.clone().or(sidecar.clone());
+
+                let actual = file_utils::sha1_hex(&bytes);
+                if let Some(expected) = &expected
+                    && !expected.eq_ignore_ascii_case(&actual)
+                {
+                    attempts.push((
+                        url.clone(),
+                        format!("expected SHA-1 {}, got {}", expected, actual),
+                    ));
+                    mismatch = Some((expected.clone(), actual));
+                    continue;
+                }
+
+                file_utils::write_atomically(dest, &bytes)?;
+                if let Some(sidecar) = sidecar {
+                    file_utils::write_text(&sidecar_path(dest), &sidecar)?;
+                }
+
+                return Ok(InstalledLibrary {
+                    name: target.name.clone(),
+                    path: dest.to_path_buf(),
+                    sha1: actual,
+                    verified: expected.is_some(),
+                    source_url: Some(url.clone()),
+                });
+            }
+
+            match mismatch {
+                Some((expected, actual)) if !other_failure => {
+                    Err(LibraryInstallError::HashMismatch {
+                        name: target.name.clone(),
+                        expected,
+                        actual,
+                    })
+                }
+                _ => Err(LibraryInstallError::DownloadFailed {
+                    name: target.name.clone(),
+                    attempts,
+                }),
+            }
+        }
+    }
+
+    /// Installs libraries into `root_dir/libraries` using the default Maven repositories.
+    ///
+    /// # Arguments
+    ///
+    /// * `root_dir` - Path to the root directory where game data is stored
+    /// * `libraries` - The libraries returned by `convert_version_to_libraries`
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<InstalledLibrary>, LibraryInstallError>` - The installed libraries
+    pub fn install_libraries(
+        root_dir: &Path,
+        libraries: &[LibraryInfo],
+    ) -> Result<Vec<InstalledLibrary>, LibraryInstallError> {
+        LibraryInstaller::new(root_dir).install_all(libraries)
+    }
+
+    /// Checks if a library only provides native classifiers and no main artifact
+    fn has_only_natives(library: &BaseLibrary) -> bool {
+        library.url.is_none()
+            && (library.natives.is_some()
+                || library
+                    .downloads
+                    .as_ref()
+                    .is_some_and(|d| d.classifiers.is_some()))
+    }
+
+    /// Checks if a hash value from a version JSON is usable
+    fn is_known_hash(hash: &str) -> bool {
+        !hash.is_empty() && hash != "unknown"
+    }
+
+    /// Joins a repository base URL and a relative artifact path
+    fn join_url(base: &str, path: &str) -> String {
+        format!(
+            "{}/{}",
+            base.trim_end_matches('/'),
+            path.trim_start_matches('/')
+        )
+    }
+
+    /// Gets the path of the `.sha1` sidecar file for a library file
+    fn sidecar_path(path: &Path) -> PathBuf {
+        let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+        file_name.push(".sha1");
+        path.with_file_name(file_name)
+    }
+
+    /// Reads a stored `.sha1` sidecar file
+    fn read_sidecar(path: &PathBuf) -> Option<String> {
+        file_utils::read_text(path)
+            .ok()
+            .and_then(|text| parse_sidecar(&text))
+    }
+
+    /// Parses the content of a `.sha1` sidecar file.
+    /// Some repositories append the file name after the hash, so only the first token is used.
+    fn parse_sidecar(text: &str) -> Option<String> {
+        let hash = text.split_whitespace().next()?.to_lowercase();
+        if hash.len() == 40 && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            Some(hash)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::library_installer::LibraryInstaller;
+    use crate::craft_launcher::core::version::library_parser::library_parser::LibraryInfo;
+    use std::path::PathBuf;
+
+    /// Tests that a library without a valid Maven name is rejected
+    #[test]
+    fn test_invalid_library_name() {
+        let installer =
+            LibraryInstaller::new(&PathBuf::from("unused")).with_repositories(Vec::<String>::new());
+        let library = LibraryInfo::Generic {
+            name: "not-a-maven-name".to_string(),
+            path: None,
+            url: None,
+            sha1: None,
+            size: 0,
+        };
+
+        assert!(installer.install(&library).is_err());
+    }
+}
@@ -1,5 +1,8 @@
 pub mod library_parser {
-    use crate::craft_launcher::core::version::base_version::Library as BaseLibrary;
+    use crate::craft_launcher::core::version::base_version::{Library as BaseLibrary, Rule};
+    use crate::craft_launcher::core::version::maven_coordinate::maven_coordinate::{
+        MavenCoordinate, MavenCoordinateError,
+    };
     use crate::craft_launcher::core::version::version_parser::version_parser::MinecraftVersion;
 
     /// Library information with unified interface for different library types
@@ -39,6 +42,43 @@ pub mod library_parser {
                 LibraryInfo::Generic { name, .. } => name,
             }
         }
+
+        /// Parse the name of the library as a Maven coordinate
+        pub fn coordinate(&self) -> Result<MavenCoordinate, MavenCoordinateError> {
+            MavenCoordinate::parse(self.name())
+        }
+
+        /// Get the path of the main artifact relative to the libraries directory
+        ///
+        /// Returns None for libraries that only provide native classifiers
+        /// or whose name is not a valid Maven coordinate.
+        pub fn artifact_path(&self) -> Option<String> {
+            match self {
+                LibraryInfo::Base(lib) => {
+                    let downloads = lib.downloads.as_ref();
+                    if let Some(artifact) = downloads.and_then(|d| d.artifact.as_ref()) {
+                        return Some(artifact.path.clone());
+                    }
+                    let has_classifiers = downloads.is_some_and(|d| d.classifiers.is_some());
+                    if lib.url.is_none() && (lib.natives.is_some() || has_classifiers) {
+                        return None;
+                    }
+                    self.coordinate().ok().map(|c| c.to_path())
+                }
+                LibraryInfo::Generic {
+                    path: Some(path), ..
+                } => Some(path.clone()),
+                LibraryInfo::Generic { .. } => self.coordinate().ok().map(|c| c.to_path()),
+            }
+        }
+
+        /// Get the rules of the library, empty if it applies everywhere
+        pub fn rules(&self) -> &[Rule] {
+            match self {
+                LibraryInfo::Base(lib) => lib.rules.as_deref().unwrap_or(&[]),
+                LibraryInfo::Generic { .. } => &[],
+            }
+        }
     }
 
     /// Extracts library artifact path, download URL, SHA1 hash, and size from a BaseLibrary object
@@ -68,7 +108,7 @@ pub mod library_parser {
             // We don't have SHA1 and size in this case, using placeholders
             return Some((
                 path.clone(),
-                format!("{}{}", url, path),
+                format!("{}/{}", url.trim_end_matches('/'), path),
                 String::from("unknown"),
                 0,
             ));
@@ -79,37 +119,37 @@ pub mod library_parser {
 
     /// Converts a Maven artifact name to a path
     ///
+    /// Classifiers and `@extension` suffixes are honoured, so
+    /// "net.minecraftforge:forge:1.21.1-52.1.1:universal" becomes
+    /// "net/minecraftforge/forge/1.21.1-52.1.1/forge-1.21.1-52.1.1-universal.jar".
+    /// Returns an empty string if the name is not a valid coordinate.
+    ///
     /// Example: "com.example:foo:1.0" becomes "com/example/foo/1.0/foo-1.0.jar"
     pub fn maven_name_to_path(name: &str) -> String {
-        let parts: Vec<&str> = name.split(':').collect();
-        let (group_id, artifact_id, version) = match parts.len() {
-            3 => (parts[0], parts[1], parts[2]),
-            4 => (parts[0], parts[1], parts[2]), // Ignore classifier in this simple implementation
-            _ => return String::new(),           // Invalid format
-        };
-
-        let group_path = group_id.replace('.', "/");
-        format!(
-            "{}/{}/{}/{}-{}.jar",
-            group_path, artifact_id, version, artifact_id, version
-        )
-    }    /// Convert MinecraftVersion to a vector of LibraryInfo objects
+        MavenCoordinate::parse(name)
+            .map(|coordinate| coordinate.to_path())
+            .unwrap_or_default()
+    }
+
+    /// Convert MinecraftVersion to a vector of LibraryInfo objects
     ///
     /// This function handles the conversion of libraries from different Minecraft version types
     /// to a unified LibraryInfo structure that can be uniformly processed.
     pub fn convert_version_to_libraries(version: MinecraftVersion) -> Vec<LibraryInfo> {
         match version {
+            // Vanilla libraries keep their full definition so that rules and natives
+            // are still available to the installer and the native extractor
             MinecraftVersion::ModernVanilla(v) => v
                 .base
                 .libraries
                 .into_iter()
-                .map(|lib| base_library_to_library_info(lib))
+                .map(LibraryInfo::Base)
                 .collect(),
             MinecraftVersion::LegacyVanilla(v) => v
                 .base
                 .libraries
                 .into_iter()
-                .map(|lib| base_library_to_library_info(lib))
+                .map(LibraryInfo::Base)
                 .collect(),
             MinecraftVersion::ModernForge(v) => v
                 .libraries
@@ -184,9 +224,9 @@ pub mod library_parser {
                     LibraryInfo::Generic {
                         name: lib.name.clone(),
                         path: None,
-                        url: None,
-                        sha1: None,
-                        size: 0,
+                        url: lib.url.clone(),
+                        sha1: lib.sha1.clone(),
+                        size: lib.size.unwrap_or(0) as i64,
                     }
                 })
                 .collect(),
@@ -206,31 +246,27 @@ pub mod library_parser {
                 .collect(),
         }
     }
-
-    /// Convert BaseLibrary to LibraryInfo
-    ///
-    /// Helper function to convert BaseLibrary to the unified LibraryInfo structure
-    fn base_library_to_library_info(lib: BaseLibrary) -> LibraryInfo {
-        if let Some(info) = extract_base_library_path(&lib) {
-            let (path, url, sha1, size) = info;
-            LibraryInfo::Generic {
-                name: lib.name,
-                path: Some(path),
-                url: Some(url),
-                sha1: Some(sha1),
-                size,
-            }
-        } else {
-            LibraryInfo::Generic {
-                name: lib.name,
-                path: None,
-                url: None,
-                sha1: None,
-                size: 0,
-            }
-        }
-    }
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::library_parser::maven_name_to_path;
+
+    /// Tests that classifiers and extensions end up in the library path
+    #[test]
+    fn test_maven_name_to_path() {
+        assert_eq!(
+            maven_name_to_path("com.example:foo:1.0"),
+            "com/example/foo/1.0/foo-1.0.jar"
+        );
+        assert_eq!(
+            maven_name_to_path("net.minecraftforge:forge:1.21.1-52.1.1:universal"),
+            "net/minecraftforge/forge/1.21.1-52.1.1/forge-1.21.1-52.1.1-universal.jar"
+        );
+        assert_eq!(
+            maven_name_to_path("de.oceanlabs.mcp:mcp_config:1.20.1@zip"),
+            "de/oceanlabs/mcp/mcp_config/1.20.1/mcp_config-1.20.1.zip"
+        );
+        assert_eq!(maven_name_to_path("invalid"), "");
+    }
+}
@@ -0,0 +1,399 @@
+pub mod maven_coordinate {
+    use std::cmp::Ordering;
+    use std::error::Error;
+    use std::fmt;
+    use std::str::FromStr;
+
+    /// The extension used when a coordinate does not specify one
+    pub const DEFAULT_EXTENSION: &str = "jar";
+
+    /// Error type for Maven coordinate parsing
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct MavenCoordinateError {
+        message: String,
+    }
+
+    impl MavenCoordinateError {
+        fn new(message: &str) -> Self {
+            MavenCoordinateError {
+                message: message.to_string(),
+            }
+        }
+    }
+
+    impl fmt::Display for MavenCoordinateError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "Invalid Maven coordinate: {}", self.message)
+        }
+    }
+
+    impl Error for MavenCoordinateError {}
+
+    /// A Maven artifact coordinate in the `group:artifact:version[:classifier][@extension]` format
+    ///
+    /// Examples:
+    /// - `org.ow2.asm:asm:9.8`
+    /// - `net.minecraftforge:forge:1.21.1-52.1.1:universal`
+    /// - `de.oceanlabs.mcp:mcp_config:1.20.1@zip`
+    /// - `org.lwjgl:lwjgl:3.3.3:natives-windows-arm64`
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct MavenCoordinate {
+        /// Group ID (e.g. "org.ow2.asm")
+        pub group_id: String,
+
+        /// Artifact ID (e.g. "asm")
+        pub artifact_id: String,
+
+        /// Version (e.g. "9.8")
+        pub version: String,
+
+        /// Optional classifier (e.g. "universal", "natives-linux")
+        pub classifier: Option<String>,
+
+        /// File extension without the leading dot (e.g. "jar", "zip")
+        pub extension: String,
+    }
+
+    impl MavenCoordinate {
+        /// Creates a new coordinate with no classifier and the default "jar" extension.
+        ///
+        /// # Arguments
+        ///
+        /// * `group_id` - The group ID
+        /// * `artifact_id` - The artifact ID
+        /// * `version` - The version
+        ///
+        /// # Returns
+        ///
+        /// * A new `MavenCoordinate`
+        pub fn new<S: Into<String>>(group_id: S, artifact_id: S, version: S) -> Self {
+            MavenCoordinate {
+                group_id: group_id.into(),
+                artifact_id: artifact_id.into(),
+                version: version.into(),
+                classifier: None,
+                extension: DEFAULT_EXTENSION.to_string(),
+            }
+        }
+
+        /// Sets the classifier.
+        ///
+        /// # Arguments
+        ///
+        /// * `classifier` - The classifier (e.g. "natives-linux")
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_classifier<S: Into<String>>(mut self, classifier: S) -> Self {
+            self.classifier = Some(classifier.into());
+            self
+        }
+
+        /// Sets the extension.
+        ///
+        /// # Arguments
+        ///
+        /// * `extension` - The extension without the leading dot (e.g. "zip")
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_extension<S: Into<String>>(mut self, extension: S) -> Self {
+            self.extension = extension.into();
+            self
+        }
+
+        /// Parses a coordinate from its string form.
+        ///
+        /// # Arguments
+        ///
+        /// * `coordinate` - A string like `group:artifact:version[:classifier][@extension]`
+        ///
+        /// # Returns
+        ///
+        /// * `Result<MavenCoordinate, MavenCoordinateError>` - The parsed coordinate or an error
+        pub fn parse(coordinate: &str) -> Result<Self, MavenCoordinateError> {
+            let coordinate = coordinate.trim();
+
+            // The extension is separated by '@' and always comes last
+            let (body, extension) = match coordinate.rsplit_once('@') {
+                Some((body, extension)) => {
+                    if extension.is_empty() || extension.contains(':') {
+                        return Err(MavenCoordinateError::new(&format!(
+                            "invalid extension in '{}'",
+                            coordinate
+                        )));
+                    }
+                    (body, extension)
+                }
+                None => (coordinate, DEFAULT_EXTENSION),
+            };
+
+            let parts: Vec<&str> = body.split(':').collect();
+            if parts.iter().any(|part| part.is_empty()) {
+                return Err(MavenCoordinateError::new(&format!(
+                    "empty segment in '{}'",
+                    coordinate
+                )));
+            }
+
+            let (group_id, artifact_id, version, classifier) = match parts.len() {
+                3 => (parts[0], parts[1], parts[2], None),
+                4 => (parts[0], parts[1], parts[2], Some(parts[3].to_string())),
+                _ => {
+                    return Err(MavenCoordinateError::new(&format!(
+                        "expected group:artifact:version[:classifier][@extension], got '{}'",
+                        coordinate
+                    )));
+                }
+            };
+
+            Ok(MavenCoordinate {
+                group_id: group_id.to_string(),
+                artifact_id: artifact_id.to_string(),
+                version: version.to_string(),
+                classifier,
+                extension: extension.to_string(),
+            })
+        }
+
+        /// Gets the file name of the artifact.
+        ///
+        /// Example: "forge-1.21.1-52.1.1-universal.jar"
+        pub fn file_name(&self) -> String {
+            match &self.classifier {
+                Some(classifier) => format!(
+                    "{}-{}-{}.{}",
+                    self.artifact_id, self.version, classifier, self.extension
+                ),
+                None => format!("{}-{}.{}", self.artifact_id, self.version, self.extension),
+            }
+        }
+
+        /// Gets the repository relative path of the artifact, always using '/' as separator.
+        ///
+        /// Example: "de/oceanlabs/mcp/mcp_config/1.20.1/mcp_config-1.20.1.zip"
+        pub fn to_path(&self) -> String {
+            format!(
+                "{}/{}/{}/{}",
+                self.group_id.replace('.', "/"),
+                self.artifact_id,
+                self.version,
+                self.file_name()
+            )
+        }
+
+        /// Gets the download URL of the artifact in a Maven repository.
+        ///
+        /// # Arguments
+        ///
+        /// * `repository` - The repository base URL, with or without a trailing slash
+        ///
+        /// # Returns
+        ///
+        /// * The full artifact URL
+        pub fn to_url(&self, repository: &str) -> String {
+            format!("{}/{}", repository.trim_end_matches('/'), self.to_path())
+        }
+    }
+
+    /// A single segment of a version string used for comparison
+    #[derive(Debug, PartialEq, Eq)]
+    enum VersionSegment<'a> {
+        Number(u64),
+        Qualifier(&'a str),
+    }
+
+    /// Splits a version string into numeric and qualifier segments
+    fn version_segments(version: &str) -> Vec<VersionSegment<'_>> {
+        version
+            .split(['.', '-', '_', '+'])
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| match segment.parse::<u64>() {
+                Ok(number) => VersionSegment::Number(number),
+                Err(_) => VersionSegment::Qualifier(segment),
+            })
+            .collect()
+    }
+
+    /// Compares two Maven version strings.
+    ///
+    /// Numeric segments are compared numerically and always rank above qualifiers,
+    /// so "1.10" is newer than "1.9" and "3.3.3" is newer than "3.3.3-beta".
+    /// A trailing numeric segment makes a version newer ("9.7.1" > "9.7"),
+    /// while a trailing qualifier makes it older ("1.0-SNAPSHOT" < "1.0").
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The first version
+    /// * `b` - The second version
+    ///
+    /// # Returns
+    ///
+    /// * The ordering of `a` relative to `b`
+    pub fn compare_versions(a: &str, b: &str) -> Ordering {
+        let left = version_segments(a);
+        let right = version_segments(b);
+
+        for index in 0..left.len().max(right.len()) {
+            let ordering = match (left.get(index), right.get(index)) {
+                (Some(VersionSegment::Number(x)), Some(VersionSegment::Number(y))) => x.cmp(y),
+                (Some(VersionSegment::Number(_)), Some(VersionSegment::Qualifier(_))) => {
+                    Ordering::Greater
+                }
+                (Some(VersionSegment::Qualifier(_)), Some(VersionSegment::Number(_))) => {
+                    Ordering::Less
+                }
+                (Some(VersionSegment::Qualifier(x)), Some(VersionSegment::Qualifier(y))) => {
+                    x.to_lowercase().cmp(&y.to_lowercase())
+                }
+                (Some(VersionSegment::Number(_)), None) => Ordering::Greater,
+                (Some(VersionSegment::Qualifier(_)), None) => Ordering::Less,
+                (None, Some(VersionSegment::Number(_))) => Ordering::Less,
+                (None, Some(VersionSegment::Qualifier(_))) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            };
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+
+        Ordering::Equal
+    }
+
+    impl FromStr for MavenCoordinate {
+        type Err = MavenCoordinateError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            MavenCoordinate::parse(s)
+        }
+    }
+
+    impl fmt::Display for MavenCoordinate {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}:{}:{}", self.group_id, self.artifact_id, self.version)?;
+            if let Some(classifier) = &self.classifier {
+                write!(f, ":{}", classifier)?;
+            }
+            if self.extension != DEFAULT_EXTENSION {
+                write!(f, "@{}", self.extension)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::maven_coordinate::{MavenCoordinate, compare_versions};
+    use std::cmp::Ordering;
+
+    /// Tests parsing a plain group:artifact:version coordinate
+    #[test]
+    fn test_parse_simple() {
+        let coordinate = MavenCoordinate::parse("org.ow2.asm:asm:9.8").unwrap();
+
+        assert_eq!(coordinate.group_id, "org.ow2.asm");
+        assert_eq!(coordinate.artifact_id, "asm");
+        assert_eq!(coordinate.version, "9.8");
+        assert_eq!(coordinate.classifier, None);
+        assert_eq!(coordinate.extension, "jar");
+        assert_eq!(coordinate.to_path(), "org/ow2/asm/asm/9.8/asm-9.8.jar");
+    }
+
+    /// Tests that the classifier is part of the path
+    #[test]
+    fn test_parse_classifier() {
+        let coordinate: MavenCoordinate = "net.minecraftforge:forge:1.21.1-52.1.1:universal"
+            .parse()
+            .unwrap();
+
+        assert_eq!(coordinate.classifier, Some("universal".to_string()));
+        assert_eq!(
+            coordinate.to_path(),
+            "net/minecraftforge/forge/1.21.1-52.1.1/forge-1.21.1-52.1.1-universal.jar"
+        );
+    }
+
+    /// Tests the @extension suffix with and without a classifier
+    #[test]
+    fn test_parse_extension() {
+        let coordinate = MavenCoordinate::parse("de.oceanlabs.mcp:mcp_config:1.20.1@zip").unwrap();
+        assert_eq!(coordinate.extension, "zip");
+        assert_eq!(
+            coordinate.to_path(),
+            "de/oceanlabs/mcp/mcp_config/1.20.1/mcp_config-1.20.1.zip"
+        );
+
+        let coordinate =
+            MavenCoordinate::parse("net.minecraft:client:1.20.1-20230612.114412:mappings@txt")
+                .unwrap();
+        assert_eq!(coordinate.classifier, Some("mappings".to_string()));
+        assert_eq!(coordinate.extension, "txt");
+        assert_eq!(
+            coordinate.file_name(),
+            "client-1.20.1-20230612.114412-mappings.txt"
+        );
+    }
+
+    /// Tests that Display produces the canonical string form
+    #[test]
+    fn test_display_round_trip() {
+        for input in [
+            "org.ow2.asm:asm:9.8",
+            "net.minecraftforge:forge:1.21.1-52.1.1:universal",
+            "de.oceanlabs.mcp:mcp_config:1.20.1@zip",
+            "net.minecraft:client:1.20.1:mappings@txt",
+        ] {
+            let coordinate = MavenCoordinate::parse(input).unwrap();
+            assert_eq!(coordinate.to_string(), input);
+            assert_eq!(
+                MavenCoordinate::parse(&coordinate.to_string()),
+                Ok(coordinate)
+            );
+        }
+    }
+
+    /// Tests building repository URLs
+    #[test]
+    fn test_to_url() {
+        let coordinate = MavenCoordinate::new("net.fabricmc", "fabric-loader", "0.16.14");
+        let expected = "https://maven.fabricmc.net/net/fabricmc/fabric-loader/0.16.14/fabric-loader-0.16.14.jar";
+
+        assert_eq!(coordinate.to_url("https://maven.fabricmc.net/"), expected);
+        assert_eq!(coordinate.to_url("https://maven.fabricmc.net"), expected);
+    }
+
+    /// Tests rejection of malformed coordinates
+    #[test]
+    fn test_invalid_coordinates() {
+        for input in [
+            "",
+            "asm",
+            "org.ow2.asm:asm",
+            "org.ow2.asm::9.8",
+            "a:b:c:d:e",
+            "a:b:c@",
+            "a:b:c@zip:x",
+        ] {
+            assert!(
+                MavenCoordinate::parse(input).is_err(),
+                "'{}' should be rejected",
+                input
+            );
+        }
+    }
+
+    /// Tests Maven version ordering
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("9.8", "9.7.1"), Ordering::Greater);
+        assert_eq!(compare_versions("1.10", "1.9"), Ordering::Greater);
+        assert_eq!(compare_versions("9.7", "9.7.1"), Ordering::Less);
+        assert_eq!(compare_versions("3.3.3", "3.3.3-beta"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0-SNAPSHOT", "1.0"), Ordering::Less);
+        assert_eq!(compare_versions("31.1-jre", "32.1.2-jre"), Ordering::Less);
+        assert_eq!(compare_versions("3.3.3", "3.3.3"), Ordering::Equal);
+    }
+}
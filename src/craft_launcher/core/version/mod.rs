@@ -17,4 +17,25 @@ pub mod version_handler;
 pub mod version_parser;
 
 pub mod library_struct;
-pub mod library_parser;
\ No newline at end of file
+pub mod library_parser;
+
+/// Library installer with Maven repository fallback and hash verification
+pub mod library_installer;
+
+/// Maven coordinates with classifier and extension support
+pub mod maven_coordinate;
+
+/// Conflict resolution for libraries declared by both a parent and a child version
+pub mod library_conflict;
+
+/// Evaluation of library and argument rules against a platform and launcher features
+pub mod rules;
+
+/// Merges a version with its `inheritsFrom` chain into a single launchable description
+pub mod version_resolver;
+
+/// Classpath computation for resolved versions
+pub mod classpath;
+
+/// JVM and game argument flattening and placeholder substitution
+pub mod launch_arguments;
@@ -1,4 +1,5 @@
 pub mod modern_fabric {
+    use crate::craft_launcher::core::version::maven_coordinate::maven_coordinate::MavenCoordinate;
     use serde::{Deserialize, Serialize};
 
     // Represents a Modern Fabric manifest file
@@ -93,14 +94,15 @@ pub mod modern_fabric {
         }
 
         /// Get the Fabric loader version from libraries
-        pub fn get_loader_version(&self) -> Option<&str> {
+        pub fn get_loader_version(&self) -> Option<String> {
             self.libraries
                 .iter()
-                .find(|lib| lib.name.starts_with("net.fabricmc:fabric-loader:"))
-                .map(|lib| {
-                    let parts: Vec<&str> = lib.name.split(':').collect();
-                    parts.get(2).map(|v| *v).unwrap_or("")
+                .filter_map(|lib| MavenCoordinate::parse(&lib.name).ok())
+                .find(|coordinate| {
+                    coordinate.group_id == "net.fabricmc"
+                        && coordinate.artifact_id == "fabric-loader"
                 })
+                .map(|coordinate| coordinate.version)
         }
     }
 }
@@ -193,7 +195,7 @@ mod tests {
 
         // Test helper methods
         assert_eq!(manifest.get_minecraft_version(), "1.21.2");
-        assert_eq!(manifest.get_loader_version(), Some("0.16.14"));
+        assert_eq!(manifest.get_loader_version().as_deref(), Some("0.16.14"));
 
         // Test serialization roundtrip
         let serialized = manifest.to_json().expect("Failed to serialize manifest");
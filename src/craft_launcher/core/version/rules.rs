@@ -0,0 +1,252 @@
+pub mod rules {
+    use crate::craft_launcher::core::version::base_version::Rule;
+    use regex::Regex;
+    use std::collections::HashMap;
+
+    /// The platform that rules in version JSON files are evaluated against
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Platform {
+        /// OS name as used in version JSON files ("windows", "osx" or "linux")
+        pub os_name: String,
+
+        /// OS version, matched against the regular expression in `os.version` rules
+        pub os_version: String,
+
+        /// CPU architecture ("x86", "x86_64", "arm64" or "arm32")
+        pub arch: String,
+    }
+
+    impl Platform {
+        /// Creates a platform description.
+        ///
+        /// # Arguments
+        ///
+        /// * `os_name` - OS name as used in version JSON files ("windows", "osx" or "linux")
+        /// * `arch` - CPU architecture ("x86", "x86_64", "arm64" or "arm32")
+        ///
+        /// # Returns
+        ///
+        /// * A new `Platform` with an empty OS version
+        pub fn new(os_name: &str, arch: &str) -> Self {
+            Platform {
+                os_name: os_name.to_string(),
+                os_version: String::new(),
+                arch: arch.to_string(),
+            }
+        }
+
+        /// Gets the platform this launcher is running on
+        pub fn current() -> Self {
+            let os_name = match std::env::consts::OS {
+                "macos" => "osx",
+                other => other,
+            };
+            let arch = match std::env::consts::ARCH {
+                "aarch64" => "arm64",
+                "arm" => "arm32",
+                other => other,
+            };
+            Platform::new(os_name, arch)
+        }
+
+        /// Gets the value substituted for `${arch}` in legacy `natives` classifiers ("32" or "64")
+        pub fn arch_bits(&self) -> &'static str {
+            match self.arch.as_str() {
+                "x86" | "arm32" => "32",
+                _ => "64",
+            }
+        }
+
+        /// Sets the OS version.
+        ///
+        /// # Arguments
+        ///
+        /// * `os_version` - The OS version (e.g. "10.0")
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_os_version(mut self, os_version: &str) -> Self {
+            self.os_version = os_version.to_string();
+            self
+        }
+    }
+
+    /// Everything a rule can be evaluated against: the platform and the launcher features
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct RuleContext {
+        /// The target platform
+        pub platform: Platform,
+
+        /// Enabled launcher features (e.g. "is_demo_user", "has_custom_resolution")
+        pub features: HashMap<String, bool>,
+    }
+
+    impl RuleContext {
+        /// Creates a rule context for the given platform with no features enabled
+        pub fn new(platform: Platform) -> Self {
+            RuleContext {
+                platform,
+                features: HashMap::new(),
+            }
+        }
+
+        /// Creates a rule context for the current platform with no features enabled
+        pub fn current() -> Self {
+            RuleContext::new(Platform::current())
+        }
+
+        /// Sets a launcher feature.
+        ///
+        /// # Arguments
+        ///
+        /// * `name` - The feature name (e.g. "has_custom_resolution")
+        /// * `enabled` - Whether the feature is enabled
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_feature(mut self, name: &str, enabled: bool) -> Self {
+            self.features.insert(name.to_string(), enabled);
+            self
+        }
+    }
+
+    impl Default for RuleContext {
+        fn default() -> Self {
+            RuleContext::current()
+        }
+    }
+
+    /// Checks whether the conditions of a single rule match the context
+    ///
+    /// A rule without conditions always matches. Features that are not set count as disabled.
+    pub fn rule_matches(rule: &Rule, context: &RuleContext) -> bool {
+        if let Some(os) = &rule.os {
+            if let Some(name) = &os.name
+                && name != &context.platform.os_name
+            {
+                return false;
+            }
+            if let Some(arch) = &os.arch
+                && arch != &context.platform.arch
+            {
+                return false;
+            }
+            if let Some(version) = &os.version {
+                let matches = Regex::new(version)
+                    .map(|regex| regex.is_match(&context.platform.os_version))
+                    .unwrap_or(false);
+                if !matches {
+                    return false;
+                }
+            }
+        }
+
+        if let Some(features) = &rule.features {
+            for (name, expected) in features {
+                let enabled = context.features.get(name).copied().unwrap_or(false);
+                if enabled != *expected {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Evaluates a list of rules the way the vanilla launcher does
+    ///
+    /// With no rules the item is allowed. Otherwise it starts out disallowed and
+    /// every matching rule sets the result to its action, so the last match wins.
+    ///
+    /// # Arguments
+    ///
+    /// * `rules` - The rules of a library or argument
+    /// * `context` - The platform and features to evaluate against
+    ///
+    /// # Returns
+    ///
+    /// * `true` if the library or argument applies
+    pub fn rules_allow(rules: &[Rule], context: &RuleContext) -> bool {
+        if rules.is_empty() {
+            return true;
+        }
+
+        let mut allowed = false;
+        for rule in rules {
+            if rule_matches(rule, context) {
+                allowed = rule.action == "allow";
+            }
+        }
+        allowed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rules::{Platform, RuleContext, rules_allow};
+    use crate::craft_launcher::core::version::base_version::Rule;
+
+    fn rules(json: &str) -> Vec<Rule> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    /// Tests the allow/disallow pattern vanilla uses for macOS-only libraries
+    #[test]
+    fn test_os_rules() {
+        let rules =
+            rules(r#"[{"action": "allow"}, {"action": "disallow", "os": {"name": "osx"}}]"#);
+
+        let linux = RuleContext::new(Platform::new("linux", "x86_64"));
+        let osx = RuleContext::new(Platform::new("osx", "arm64"));
+
+        assert!(rules_allow(&rules, &linux));
+        assert!(!rules_allow(&rules, &osx));
+        assert!(rules_allow(&[], &osx));
+    }
+
+    /// Tests architecture and OS version conditions
+    #[test]
+    fn test_arch_and_version_rules() {
+        let x86 = rules(r#"[{"action": "allow", "os": {"arch": "x86"}}]"#);
+        let windows_10 =
+            rules(r#"[{"action": "allow", "os": {"name": "windows", "version": "^10\\."}}]"#);
+
+        assert!(rules_allow(
+            &x86,
+            &RuleContext::new(Platform::new("windows", "x86"))
+        ));
+        assert!(!rules_allow(
+            &x86,
+            &RuleContext::new(Platform::new("windows", "x86_64"))
+        ));
+
+        let platform = Platform::new("windows", "x86_64").with_os_version("10.0");
+        assert!(rules_allow(&windows_10, &RuleContext::new(platform)));
+        let platform = Platform::new("windows", "x86_64").with_os_version("6.1");
+        assert!(!rules_allow(&windows_10, &RuleContext::new(platform)));
+    }
+
+    /// Tests feature conditions
+    #[test]
+    fn test_feature_rules() {
+        let rules = rules(r#"[{"action": "allow", "features": {"is_demo_user": true}}]"#);
+        let context = RuleContext::new(Platform::new("linux", "x86_64"));
+
+        assert!(!rules_allow(&rules, &context));
+        assert!(rules_allow(
+            &rules,
+            &context.with_feature("is_demo_user", true)
+        ));
+    }
+
+    /// Tests the `${arch}` value of each architecture
+    #[test]
+    fn test_arch_bits() {
+        assert_eq!(Platform::new("windows", "x86").arch_bits(), "32");
+        assert_eq!(Platform::new("linux", "arm32").arch_bits(), "32");
+        assert_eq!(Platform::new("windows", "x86_64").arch_bits(), "64");
+        assert_eq!(Platform::new("osx", "arm64").arch_bits(), "64");
+    }
+}
@@ -14,7 +14,7 @@ pub mod version_parser {
     use std::path::PathBuf;
 
     /// Represents different types of Minecraft versions and loaders
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub enum MinecraftVersion {
         /// Modern Vanilla Minecraft (1.13+)
         ModernVanilla(ModernVanillaVersion),
@@ -39,7 +39,7 @@ pub mod version_parser {
     }
 
     impl VersionParseError {
-        fn new(message: &str) -> Self {
+        pub fn new(message: &str) -> Self {
             VersionParseError {
                 message: message.to_string(),
             }
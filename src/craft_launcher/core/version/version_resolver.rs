@@ -0,0 +1,399 @@
+pub mod version_resolver {
+    use crate::craft_launcher::core::version::base_version::{ArgumentValue, LoggingClient};
+    use crate::craft_launcher::core::version::legacy::legacy_vanilla::legacy_vanilla;
+    use crate::craft_launcher::core::version::library_conflict::library_conflict::{
+        ConflictPolicy, DroppedLibrary, resolve_library_conflicts,
+    };
+    use crate::craft_launcher::core::version::library_parser::library_parser::{
+        LibraryInfo, convert_version_to_libraries,
+    };
+    use crate::craft_launcher::core::version::modern::modern_vanilla::modern_vanilla::{
+        AssetIndex, DownloadEntry, JavaVersion,
+    };
+    use crate::craft_launcher::core::version::version_parser::version_parser::{
+        MinecraftVersion, VersionParseError, parse_version,
+    };
+    use crate::craft_launcher::utils::file_operations::file_utils;
+    use serde_json::Value;
+    use std::collections::HashMap;
+    use std::error::Error;
+    use std::path::Path;
+
+    /// Maximum depth of an `inheritsFrom` chain before it is treated as a cycle
+    const MAX_INHERITANCE_DEPTH: usize = 16;
+
+    /// A version with its whole `inheritsFrom` chain merged into one launchable description
+    ///
+    /// Child values override parent values, argument lists are concatenated parent first,
+    /// and libraries are listed child first after conflict resolution.
+    #[derive(Debug, Clone)]
+    pub struct ResolvedVersion {
+        /// ID of the version that was requested
+        pub id: String,
+
+        /// IDs of the merged versions, from the requested version to the root
+        pub inheritance: Vec<String>,
+
+        /// ID of the version whose client jar is launched (`jar` override or the root version)
+        pub jar: String,
+
+        /// The main Java class to execute
+        pub main_class: String,
+
+        /// The time the requested version was released
+        pub release_time: String,
+
+        /// The type of the requested version (release, snapshot, etc)
+        pub type_: String,
+
+        /// Libraries of the whole chain, child libraries first
+        pub libraries: Vec<LibraryInfo>,
+
+        /// Libraries removed while resolving conflicts between the versions of the chain
+        pub dropped_libraries: Vec<DroppedLibrary>,
+
+        /// Game arguments (newer format)
+        pub game_arguments: Vec<ArgumentValue>,
+
+        /// JVM arguments (newer format)
+        pub jvm_arguments: Vec<ArgumentValue>,
+
+        /// Game arguments (legacy format)
+        pub minecraft_arguments: Option<String>,
+
+        /// The assets index information
+        pub asset_index: Option<AssetIndex>,
+
+        /// The assets directory name
+        pub assets: Option<String>,
+
+        /// Client JAR download information
+        pub client_download: Option<DownloadEntry>,
+
+        /// Client mappings download information
+        pub client_mappings: Option<DownloadEntry>,
+
+        /// Server JAR download information
+        pub server_download: Option<DownloadEntry>,
+
+        /// Java version requirements
+        pub java_version: Option<JavaVersion>,
+
+        /// Client logging configuration
+        pub logging: Option<LoggingClient>,
+    }
+
+    /// The fields of a single version JSON that take part in the merge
+    struct VersionLayer {
+        id: String,
+        inherits_from: Option<String>,
+        jar: Option<String>,
+        main_class: String,
+        release_time: String,
+        type_: String,
+        libraries: Vec<LibraryInfo>,
+        game_arguments: Vec<ArgumentValue>,
+        jvm_arguments: Vec<ArgumentValue>,
+        minecraft_arguments: Option<String>,
+        asset_index: Option<AssetIndex>,
+        assets: Option<String>,
+        client_download: Option<DownloadEntry>,
+        client_mappings: Option<DownloadEntry>,
+        server_download: Option<DownloadEntry>,
+        java_version: Option<JavaVersion>,
+        logging: Option<LoggingClient>,
+    }
+
+    /// Resolves a version and everything it inherits from
+    ///
+    /// Version JSON files are read from `root_dir/versions/<id>/<id>.json`.
+    ///
+    /// # Arguments
+    ///
+    /// * `root_dir` - Path to the root directory containing the versions folder
+    /// * `version_id` - The ID of the version to resolve
+    /// * `policy` - How to resolve libraries declared by both a parent and a child
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ResolvedVersion, Box<dyn Error>>` - The merged version or an error
+    pub fn resolve_version(
+        root_dir: &Path,
+        version_id: &str,
+        policy: ConflictPolicy,
+    ) -> Result<ResolvedVersion, Box<dyn Error>> {
+        let mut layers = Vec::new();
+        let mut next = Some(version_id.to_string());
+
+        while let Some(id) = next {
+            if layers.iter().any(|layer: &VersionLayer| layer.id == id) {
+                return Err(Box::new(VersionParseError::new(&format!(
+                    "Circular inheritsFrom chain at version {}",
+                    id
+                ))));
+            }
+            if layers.len() >= MAX_INHERITANCE_DEPTH {
+                return Err(Box::new(VersionParseError::new(&format!(
+                    "inheritsFrom chain of {} is too deep",
+                    version_id
+                ))));
+            }
+
+            let layer = load_layer(root_dir, &id)?;
+            next = layer.inherits_from.clone();
+            layers.push(layer);
+        }
+
+        // Start from the root version and apply each child on top of it
+        let mut layers = layers.into_iter().rev();
+        let root = layers.next().ok_or_else(|| {
+            VersionParseError::new(&format!("No version found for {}", version_id))
+        })?;
+
+        let mut resolved = ResolvedVersion {
+            id: root.id.clone(),
+            inheritance: vec![root.id.clone()],
+            jar: root.jar.unwrap_or_else(|| root.id.clone()),
+            main_class: root.main_class,
+            release_time: root.release_time,
+            type_: root.type_,
+            libraries: root.libraries,
+            dropped_libraries: Vec::new(),
+            game_arguments: root.game_arguments,
+            jvm_arguments: root.jvm_arguments,
+            minecraft_arguments: root.minecraft_arguments,
+            asset_index: root.asset_index,
+            assets: root.assets,
+            client_download: root.client_download,
+            client_mappings: root.client_mappings,
+            server_download: root.server_download,
+            java_version: root.java_version,
+            logging: root.logging,
+        };
+
+        for layer in layers {
+            let resolution =
+                resolve_library_conflicts(&resolved.libraries, &layer.libraries, policy)?;
+
+            resolved.id = layer.id.clone();
+            resolved.inheritance.insert(0, layer.id);
+            if let Some(jar) = layer.jar {
+                resolved.jar = jar;
+            }
+            resolved.main_class = layer.main_class;
+            resolved.release_time = layer.release_time;
+            resolved.type_ = layer.type_;
+            resolved.libraries = resolution.libraries;
+            resolved.dropped_libraries.extend(resolution.dropped);
+            resolved.game_arguments.extend(layer.game_arguments);
+            resolved.jvm_arguments.extend(layer.jvm_arguments);
+            resolved.minecraft_arguments =
+                layer.minecraft_arguments.or(resolved.minecraft_arguments);
+            resolved.asset_index = layer.asset_index.or(resolved.asset_index);
+            resolved.assets = layer.assets.or(resolved.assets);
+            resolved.client_download = layer.client_download.or(resolved.client_download);
+            resolved.client_mappings = layer.client_mappings.or(resolved.client_mappings);
+            resolved.server_download = layer.server_download.or(resolved.server_download);
+            resolved.java_version = layer.java_version.or(resolved.java_version);
+            resolved.logging = layer.logging.or(resolved.logging);
+        }
+
+        Ok(resolved)
+    }
+
+    /// Reads a single version JSON and extracts the fields used for merging
+    fn load_layer(root_dir: &Path, version_id: &str) -> Result<VersionLayer, Box<dyn Error>> {
+        let version_path = root_dir
+            .join("versions")
+            .join(version_id)
+            .join(format!("{}.json", version_id));
+
+        if !file_utils::exists(&version_path) {
+            return Err(Box::new(VersionParseError::new(&format!(
+                "Version file does not exist at path: {}",
+                version_path.display()
+            ))));
+        }
+
+        let json_str = file_utils::read_text(&version_path)?;
+        let version = parse_version(&json_str)?;
+
+        // The `jar` override is not part of the typed version models, so read it from the raw JSON
+        let raw: Value = serde_json::from_str(&json_str)?;
+        let jar = raw.get("jar").and_then(Value::as_str).map(str::to_string);
+
+        let libraries = convert_version_to_libraries(version.clone());
+        let mut layer = match version {
+            MinecraftVersion::ModernVanilla(v) => {
+                let arguments = v.base.arguments;
+                VersionLayer {
+                    id: v.base.id,
+                    inherits_from: v.base.inherits_from,
+                    jar,
+                    main_class: v.base.main_class,
+                    release_time: v.base.release_time,
+                    type_: v.base.type_,
+                    libraries: Vec::new(),
+                    game_arguments: arguments
+                        .as_ref()
+                        .and_then(|a| a.game.clone())
+                        .unwrap_or_default(),
+                    jvm_arguments: arguments.and_then(|a| a.jvm).unwrap_or_default(),
+                    minecraft_arguments: v.base.minecraft_arguments,
+                    asset_index: Some(v.asset_index),
+                    assets: Some(v.assets),
+                    client_download: Some(v.downloads.client),
+                    client_mappings: v.downloads.client_mappings,
+                    server_download: Some(v.downloads.server),
+                    java_version: Some(v.java_version),
+                    logging: v.base.logging.map(|l| l.client),
+                }
+            }
+            MinecraftVersion::LegacyVanilla(v) => {
+                let downloads = v.downloads;
+                VersionLayer {
+                    id: v.base.id,
+                    inherits_from: v.base.inherits_from,
+                    jar,
+                    main_class: v.base.main_class,
+                    release_time: v.base.release_time,
+                    type_: v.base.type_,
+                    libraries: Vec::new(),
+                    game_arguments: Vec::new(),
+                    jvm_arguments: Vec::new(),
+                    minecraft_arguments: v.base.minecraft_arguments,
+                    asset_index: v.asset_index.map(convert_asset_index),
+                    assets: v.assets,
+                    client_download: downloads
+                        .as_ref()
+                        .and_then(|d| d.client.clone())
+                        .map(convert_download),
+                    client_mappings: None,
+                    server_download: downloads.and_then(|d| d.server).map(convert_download),
+                    java_version: v.java_version.map(|j| JavaVersion {
+                        component: j.component,
+                        major_version: j.major_version,
+                    }),
+                    logging: v.base.logging.map(|l| l.client),
+                }
+            }
+            MinecraftVersion::ModernForge(v) => VersionLayer {
+                id: v.id,
+                inherits_from: Some(v.inherits_from),
+                jar,
+                main_class: v.main_class,
+                release_time: v.release_time,
+                type_: v.version_type,
+                libraries: Vec::new(),
+                game_arguments: string_arguments(v.arguments.game),
+                jvm_arguments: string_arguments(v.arguments.jvm),
+                logging: client_logging(&v.logging),
+                ..loader_defaults()
+            },
+            MinecraftVersion::LegacyForge(v) => VersionLayer {
+                id: v.id,
+                inherits_from: Some(v.inherits_from),
+                jar,
+                main_class: v.main_class,
+                release_time: v.release_time,
+                type_: v.release_type,
+                libraries: Vec::new(),
+                minecraft_arguments: Some(v.minecraft_arguments),
+                logging: client_logging(&v.logging),
+                ..loader_defaults()
+            },
+            MinecraftVersion::ModernFabric(v) => VersionLayer {
+                id: v.id,
+                inherits_from: Some(v.inherits_from),
+                jar,
+                main_class: v.main_class,
+                release_time: v.release_time,
+                type_: v.release_type,
+                libraries: Vec::new(),
+                game_arguments: string_arguments(v.arguments.game),
+                jvm_arguments: string_arguments(v.arguments.jvm),
+                ..loader_defaults()
+            },
+            MinecraftVersion::LegacyFabric(v) => VersionLayer {
+                id: v.id,
+                inherits_from: Some(v.inherits_from),
+                jar,
+                main_class: v.main_class,
+                release_time: v.release_time,
+                type_: v.release_type,
+                libraries: Vec::new(),
+                ..loader_defaults()
+            },
+            MinecraftVersion::NeoForge(v) => VersionLayer {
+                id: v.id,
+                inherits_from: Some(v.inherits_from),
+                jar,
+                main_class: v.main_class,
+                release_time: v.release_time,
+                type_: v.version_type,
+                libraries: Vec::new(),
+                game_arguments: string_arguments(v.arguments.game),
+                jvm_arguments: string_arguments(v.arguments.jvm),
+                ..loader_defaults()
+            },
+        };
+
+        layer.libraries = libraries;
+        Ok(layer)
+    }
+
+    /// Default values for the fields that mod loader versions do not declare
+    fn loader_defaults() -> VersionLayer {
+        VersionLayer {
+            id: String::new(),
+            inherits_from: None,
+            jar: None,
+            main_class: String::new(),
+            release_time: String::new(),
+            type_: String::new(),
+            libraries: Vec::new(),
+            game_arguments: Vec::new(),
+            jvm_arguments: Vec::new(),
+            minecraft_arguments: None,
+            asset_index: None,
+            assets: None,
+            client_download: None,
+            client_mappings: None,
+            server_download: None,
+            java_version: None,
+            logging: None,
+        }
+    }
+
+    /// Wraps plain string arguments of mod loader versions
+    fn string_arguments(arguments: Vec<String>) -> Vec<ArgumentValue> {
+        arguments.into_iter().map(ArgumentValue::String).collect()
+    }
+
+    /// Reads the client logging configuration from an untyped logging object
+    fn client_logging(logging: &HashMap<String, Value>) -> Option<LoggingClient> {
+        logging
+            .get("client")
+            .and_then(|client| serde_json::from_value(client.clone()).ok())
+    }
+
+    /// Converts a legacy asset index into the shared representation
+    fn convert_asset_index(index: legacy_vanilla::AssetIndex) -> AssetIndex {
+        AssetIndex {
+            id: index.id,
+            sha1: index.sha1,
+            size: index.size,
+            total_size: index.total_size,
+            url: index.url,
+        }
+    }
+
+    /// Converts a legacy download entry into the shared representation
+    fn convert_download(entry: legacy_vanilla::DownloadEntry) -> DownloadEntry {
+        DownloadEntry {
+            sha1: entry.sha1,
+            size: entry.size,
+            url: entry.url,
+        }
+    }
+}
@@ -1,22 +1,44 @@
 pub mod arguments_builder {
+    use crate::craft_launcher::core::disposable::Disposable;
+    use crate::craft_launcher::core::process_registry::process_registry::is_process_running;
+    use crate::craft_launcher::core::version::launch_arguments::launch_arguments::join_paths;
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::io;
     use std::path::{Path, PathBuf};
+    use std::process::Command;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    /// Counter that keeps argument file names unique within the process
+    static ARGUMENT_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
 
     /// A builder for Java command line arguments.
     /// This struct helps to construct command line arguments for Java applications
     /// including executable path, JVM arguments, classpath, main class and program arguments.
     pub struct JavaArgumentsBuilder {
+        /// Wrapper commands the Java executable is run through, outermost first
+        wrapper_args: Vec<String>,
         /// The Java executable path (java or javaw)
         executable: String,
         /// JVM arguments that come before the classpath
         pre_classpath_args: Vec<String>,
         /// Files and directories to be included in the classpath
         classpath_entries: Vec<PathBuf>,
+        /// Files and directories to be included in the module path
+        module_path_entries: Vec<PathBuf>,
         /// Whether to check if classpath entries exist before adding them
         check_exists: bool,
         /// Main class to execute
         main_class: Option<String>,
         /// Program arguments that come after the main class
         program_args: Vec<String>,
+        /// Directory to write a JVM argument file into, if one should be used
+        argument_file_dir: Option<PathBuf>,
+        /// Whether the argument file is kept after launch (for debugging)
+        keep_argument_file: bool,
+        /// Environment variables to set (Some) or remove (None)
+        env: BTreeMap<String, Option<String>>,
     }
 
     impl JavaArgumentsBuilder {
@@ -27,12 +49,17 @@ pub mod arguments_builder {
         /// * A new `JavaArgumentsBuilder` instance with "java" as the default executable
         pub fn new() -> Self {
             JavaArgumentsBuilder {
+                wrapper_args: Vec::new(),
                 executable: "java".to_string(),
                 pre_classpath_args: Vec::new(),
                 classpath_entries: Vec::new(),
+                module_path_entries: Vec::new(),
                 check_exists: true,
                 main_class: None,
                 program_args: Vec::new(),
+                argument_file_dir: None,
+                keep_argument_file: false,
+                env: BTreeMap::new(),
             }
         }
 
@@ -142,6 +169,48 @@ pub mod arguments_builder {
             self
         }
 
+        /// Adds a module path entry if it exists (when check_exists is true) or unconditionally (when check_exists is false).
+        ///
+        /// Modern Forge and NeoForge load BootstrapLauncher and its dependencies from the module path.
+        ///
+        /// # Arguments
+        ///
+        /// * `path` - Path to add to the module path
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn add_module_path_entry<P: AsRef<Path>>(mut self, path: P) -> Self {
+            let path_ref = path.as_ref();
+
+            if !self.check_exists || path_ref.exists() {
+                self.module_path_entries.push(path_ref.to_path_buf());
+            }
+
+            self
+        }
+
+        /// Adds multiple module path entries.
+        ///
+        /// # Arguments
+        ///
+        /// * `paths` - Paths to add to the module path
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn add_module_path_entries<I, P>(mut self, paths: I) -> Self
+        where
+            I: IntoIterator<Item = P>,
+            P: AsRef<Path>,
+        {
+            for path in paths {
+                self = self.add_module_path_entry(path);
+            }
+
+            self
+        }
+
         /// Sets the main class to execute.
         ///
         /// # Arguments
@@ -190,39 +259,189 @@ pub mod arguments_builder {
             self
         }
 
+        /// Writes JVM arguments and the classpath into an argument file when preparing.
+        ///
+        /// The command line then references the file with Java's `@file` syntax, which
+        /// keeps it short for classpaths of hundreds of entries. Requires Java 9 or later.
+        ///
+        /// # Arguments
+        ///
+        /// * `dir` - Directory the argument file is written into
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_argument_file<P: AsRef<Path>>(mut self, dir: P) -> Self {
+            self.argument_file_dir = Some(dir.as_ref().to_path_buf());
+            self
+        }
+
+        /// Sets whether the argument file is kept after launch.
+        ///
+        /// # Arguments
+        ///
+        /// * `keep` - If true, the file is not removed when the prepared command is disposed
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn keep_argument_file(mut self, keep: bool) -> Self {
+            self.keep_argument_file = keep;
+            self
+        }
+
+        /// Adds a wrapper command the Java executable is run through.
+        ///
+        /// Wrappers chain in the order they are added, so adding `gamemoderun` and then
+        /// `mangohud` runs `gamemoderun mangohud java ...`.
+        ///
+        /// # Arguments
+        ///
+        /// * `command` - The wrapper program followed by its arguments
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn add_wrapper<I, S>(mut self, command: I) -> Self
+        where
+            I: IntoIterator<Item = S>,
+            S: Into<String>,
+        {
+            self.wrapper_args
+                .extend(command.into_iter().map(|arg| arg.into()));
+            self
+        }
+
+        /// Sets an environment variable for the Java process.
+        ///
+        /// # Arguments
+        ///
+        /// * `key` - The variable name (e.g., "__GL_THREADED_OPTIMIZATIONS")
+        /// * `value` - The value
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_env<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+            self.env.insert(key.into(), Some(value.into()));
+            self
+        }
+
+        /// Removes an environment variable the Java process would inherit.
+        ///
+        /// # Arguments
+        ///
+        /// * `key` - The variable name (e.g., "_JAVA_OPTIONS")
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn remove_env<K: Into<String>>(mut self, key: K) -> Self {
+            self.env.insert(key.into(), None);
+            self
+        }
+
+        /// Gets the environment overrides; None values are removed from the environment.
+        pub fn env(&self) -> &BTreeMap<String, Option<String>> {
+            &self.env
+        }
+
+        /// Gets the JVM arguments, module path and classpath options, without the executable.
+        fn jvm_part(&self) -> Vec<String> {
+            let mut args = self.pre_classpath_args.clone();
+
+            // Add module path if there are any entries
+            if !self.module_path_entries.is_empty() {
+                args.push("-p".to_string());
+                args.push(join_paths(&self.module_path_entries));
+            }
+
+            // Add classpath if there are any entries
+            if !self.classpath_entries.is_empty() {
+                args.push("-cp".to_string());
+                args.push(join_paths(&self.classpath_entries));
+            }
+
+            args
+        }
+
+        /// Builds the content of a JVM argument file holding the JVM arguments and classpath.
+        ///
+        /// # Returns
+        ///
+        /// * One quoted argument per line
+        pub fn build_argument_file(&self) -> String {
+            let mut content = String::new();
+            for arg in self.jvm_part() {
+                content.push_str(&quote_argument_file_arg(&arg));
+                content.push('\n');
+            }
+            content
+        }
+
+        /// Prepares the command line, writing the argument file if one is configured.
+        ///
+        /// Without an argument file this is the same as `build`.
+        ///
+        /// # Returns
+        ///
+        /// * `io::Result<PreparedCommand>` - The command, which owns the argument file
+        pub fn prepare(&self) -> io::Result<PreparedCommand> {
+            let Some(dir) = &self.argument_file_dir else {
+                return Ok(PreparedCommand {
+                    command: self.build(),
+                    env: self.env.clone(),
+                    argument_file: None,
+                    keep_argument_file: self.keep_argument_file,
+                });
+            };
+
+            fs::create_dir_all(dir)?;
+            let nanos = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos())
+                .unwrap_or(0);
+            let counter = ARGUMENT_FILE_COUNTER.fetch_add(1, Ordering::Relaxed);
+            let path = dir.join(format!(
+                "jvm-{}-{}-{}.args",
+                std::process::id(),
+                nanos,
+                counter
+            ));
+            fs::write(&path, self.build_argument_file())?;
+
+            let mut command = self.wrapper_args.clone();
+            command.push(self.executable.clone());
+            command.push(format!("@{}", path.to_string_lossy()));
+            if let Some(ref main_class) = self.main_class {
+                command.push(main_class.clone());
+            }
+            command.extend(self.program_args.clone());
+
+            Ok(PreparedCommand {
+                command,
+                env: self.env.clone(),
+                argument_file: Some(path),
+                keep_argument_file: self.keep_argument_file,
+            })
+        }
+
         /// Builds the complete command line as a vector of strings.
         ///
         /// # Returns
         ///
-        /// * A vector of command line arguments starting with the executable
+        /// * A vector of command line arguments starting with the wrappers or the executable
         pub fn build(&self) -> Vec<String> {
             let mut command: Vec<String> = Vec::new();
 
+            // Add wrapper commands
+            command.extend(self.wrapper_args.clone());
+
             // Add the executable
             command.push(self.executable.clone());
 
-            // Add JVM arguments
-            command.extend(self.pre_classpath_args.clone());
-
-            // Add classpath if there are any entries
-            if !self.classpath_entries.is_empty() {
-                command.push("-cp".to_string());
-
-                // Join classpath entries with appropriate separator
-                #[cfg(target_os = "windows")]
-                let separator = ";";
-                #[cfg(not(target_os = "windows"))]
-                let separator = ":";
-
-                let classpath = self
-                    .classpath_entries
-                    .iter()
-                    .map(|p| p.to_string_lossy().into_owned())
-                    .collect::<Vec<_>>()
-                    .join(separator);
-
-                command.push(classpath);
-            }
+            // Add JVM arguments and the classpath
+            command.extend(self.jvm_part());
 
             // Add main class if specified
             if let Some(ref main_class) = self.main_class {
@@ -271,6 +490,127 @@ pub mod arguments_builder {
         }
     }
 
+    /// A command line ready to be spawned, owning its JVM argument file
+    ///
+    /// The argument file is removed when the command is disposed, unless the builder
+    /// was told to keep it for debugging. Dropping the command keeps the file, since a
+    /// process started from it may not have read it yet; `remove_stale_argument_files`
+    /// cleans up files left behind by launchers that are no longer running.
+    #[derive(Debug)]
+    pub struct PreparedCommand {
+        /// The command line starting with the wrappers or the executable
+        pub command: Vec<String>,
+        /// Environment variables to set (Some) or remove (None)
+        pub env: BTreeMap<String, Option<String>>,
+        /// The argument file referenced by the command, if any
+        pub argument_file: Option<PathBuf>,
+        /// Whether the argument file survives disposal
+        keep_argument_file: bool,
+    }
+
+    impl PreparedCommand {
+        /// Creates a process command for the command line and its environment overrides
+        pub fn to_command(&self) -> Command {
+            let mut command = Command::new(&self.command[0]);
+            command.args(&self.command[1..]);
+            for (key, value) in &self.env {
+                match value {
+                    Some(value) => command.env(key, value),
+                    None => command.env_remove(key),
+                };
+            }
+            command
+        }
+    }
+
+    impl Disposable for PreparedCommand {
+        fn dispose(&mut self) {
+            if self.keep_argument_file {
+                return;
+            }
+            if let Some(path) = self.argument_file.take() {
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+
+    /// Removes argument files left behind by launcher processes that are no longer running
+    ///
+    /// Argument files are named after the process that wrote them, so files of this
+    /// process and of other running launchers are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory argument files are written to
+    ///
+    /// # Returns
+    ///
+    /// * `io::Result<Vec<PathBuf>>` - The removed files
+    pub fn remove_stale_argument_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut removed = Vec::new();
+        if !dir.is_dir() {
+            return Ok(removed);
+        }
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            let Some(pid) = name
+                .strip_prefix("jvm-")
+                .filter(|_| name.ends_with(".args"))
+                .and_then(|rest| rest.split('-').next())
+                .and_then(|pid| pid.parse::<u32>().ok())
+            else {
+                continue;
+            };
+            if pid == std::process::id() || is_process_running(pid) {
+                continue;
+            }
+            fs::remove_file(&path)?;
+            removed.push(path);
+        }
+        Ok(removed)
+    }
+
+    /// Quotes an argument for a JVM argument file.
+    ///
+    /// Arguments without whitespace, quotes, backslashes or `#` are written as is.
+    /// Others are wrapped in double quotes, with backslashes and double quotes escaped,
+    /// since the JVM treats `\` as an escape character inside quotes and `#` as a comment.
+    ///
+    /// # Arguments
+    ///
+    /// * `arg` - The argument to quote
+    ///
+    /// # Returns
+    ///
+    /// * The argument as it should appear in the file
+    pub fn quote_argument_file_arg(arg: &str) -> String {
+        let needs_quotes = arg.is_empty()
+            || arg
+                .chars()
+                .any(|c| c.is_whitespace() || matches!(c, '"' | '\'' | '\\' | '#'));
+        if !needs_quotes {
+            return arg.to_string();
+        }
+
+        let mut quoted = String::with_capacity(arg.len() + 2);
+        quoted.push('"');
+        for c in arg.chars() {
+            match c {
+                '\\' => quoted.push_str("\\\\"),
+                '"' => quoted.push_str("\\\""),
+                '\n' => quoted.push_str("\\n"),
+                '\r' => quoted.push_str("\\r"),
+                '\t' => quoted.push_str("\\t"),
+                other => quoted.push(other),
+            }
+        }
+        quoted.push('"');
+        quoted
+    }
+
     /// Creates a shortcut to initialize a new JavaArgumentsBuilder
     ///
     /// # Returns
@@ -283,7 +623,10 @@ pub mod arguments_builder {
 
 #[cfg(test)]
 mod tests {
-    use super::arguments_builder::java_args;
+    use super::arguments_builder::{
+        java_args, quote_argument_file_arg, remove_stale_argument_files,
+    };
+    use crate::craft_launcher::core::disposable::Disposable;
 
     #[test]
     /// Tests building basic Java command with a main class
@@ -392,4 +735,131 @@ mod tests {
         assert!(command_str.contains("-Duser.dir=C:\\Program Files\\Java"));
         assert!(command_str.contains("my lib.jar"));
     }
+
+    #[test]
+    /// Tests quoting and escaping of argument file entries
+    fn test_quote_argument_file_arg() {
+        assert_eq!(quote_argument_file_arg("-Xmx2G"), "-Xmx2G");
+        assert_eq!(quote_argument_file_arg(""), "\"\"");
+        assert_eq!(
+            quote_argument_file_arg("C:\\Program Files\\Java"),
+            "\"C:\\\\Program Files\\\\Java\""
+        );
+        assert_eq!(
+            quote_argument_file_arg("-Dname=say \"hi\""),
+            "\"-Dname=say \\\"hi\\\"\""
+        );
+        assert_eq!(quote_argument_file_arg("#tag"), "\"#tag\"");
+    }
+
+    #[test]
+    /// Tests that the argument file holds JVM arguments and the classpath and is cleaned up
+    fn test_prepare_with_argument_file() {
+        let dir = std::env::temp_dir().join(format!("argfile_test_{}", std::process::id()));
+        let builder = java_args()
+            .check_exists(false) // Don't check existence for testing
+            .add_jvm_arg("-Xmx2G")
+            .add_classpath_entry("my lib.jar")
+            .with_main_class("com.example.Main")
+            .add_program_arg("--demo")
+            .with_argument_file(&dir);
+
+        let mut prepared = builder.prepare().unwrap();
+        let path = prepared.argument_file.clone().unwrap();
+        assert_eq!(prepared.command.len(), 4);
+        assert_eq!(prepared.command[1], format!("@{}", path.to_string_lossy()));
+        assert_eq!(prepared.command[2], "com.example.Main");
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "-Xmx2G\n-cp\n\"my lib.jar\"\n"
+        );
+
+        prepared.dispose();
+        assert!(!path.exists());
+
+        // Dropping the command keeps the file for a process that may still read it
+        let prepared = builder.prepare().unwrap();
+        let dropped = prepared.argument_file.clone().unwrap();
+        drop(prepared);
+        assert!(dropped.exists());
+
+        // Kept for debugging
+        let mut prepared = builder.keep_argument_file(true).prepare().unwrap();
+        let path = prepared.argument_file.clone().unwrap();
+        prepared.dispose();
+        assert!(path.exists());
+
+        // Only files of launchers that are no longer running are stale
+        let stale = dir.join("jvm-4194305-1-0.args");
+        std::fs::write(&stale, "").unwrap();
+        std::fs::write(dir.join("notes.txt"), "").unwrap();
+        assert_eq!(remove_stale_argument_files(&dir).unwrap(), vec![stale]);
+        assert!(dropped.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    /// Tests wrapper chains and environment overrides
+    fn test_wrappers_and_env() {
+        let builder = java_args()
+            .add_wrapper(["gamemoderun"])
+            .add_wrapper(["firejail", "--noprofile"])
+            .with_main_class("com.example.Main")
+            .with_env("__GL_THREADED_OPTIMIZATIONS", "1")
+            .remove_env("_JAVA_OPTIONS");
+
+        assert_eq!(
+            builder.build(),
+            vec![
+                "gamemoderun",
+                "firejail",
+                "--noprofile",
+                "java",
+                "com.example.Main"
+            ]
+        );
+
+        let prepared = builder.prepare().unwrap();
+        assert_eq!(
+            prepared.env.get("__GL_THREADED_OPTIMIZATIONS"),
+            Some(&Some("1".to_string()))
+        );
+        assert_eq!(prepared.env.get("_JAVA_OPTIONS"), Some(&None));
+        let command = prepared.to_command();
+        assert_eq!(command.get_program(), "gamemoderun");
+        let envs: Vec<_> = command.get_envs().collect();
+        assert!(envs.contains(&("_JAVA_OPTIONS".as_ref(), None)));
+    }
+
+    #[test]
+    /// Tests that the module path comes before the classpath
+    fn test_module_path() {
+        let command = java_args()
+            .check_exists(false) // Don't check existence for testing
+            .add_jvm_arg("--add-modules")
+            .add_jvm_arg("ALL-MODULE-PATH")
+            .add_module_path_entries(["bootstraplauncher.jar", "securejarhandler.jar"])
+            .add_classpath_entry("forge.jar")
+            .with_main_class("cpw.mods.bootstraplauncher.BootstrapLauncher")
+            .build();
+
+        #[cfg(target_os = "windows")]
+        let module_path = "bootstraplauncher.jar;securejarhandler.jar";
+        #[cfg(not(target_os = "windows"))]
+        let module_path = "bootstraplauncher.jar:securejarhandler.jar";
+
+        assert_eq!(
+            command,
+            vec![
+                "java",
+                "--add-modules",
+                "ALL-MODULE-PATH",
+                "-p",
+                module_path,
+                "-cp",
+                "forge.jar",
+                "cpw.mods.bootstraplauncher.BootstrapLauncher",
+            ]
+        );
+    }
 }
@@ -1,17 +1,269 @@
 pub mod arguments_merger {
-    use std::collections::HashMap;
     use std::path::PathBuf;
 
     use crate::arguments_builder::arguments_builder::JavaArgumentsBuilder;
 
+    /// Options whose value is passed as the next argument (e.g. `--add-opens <value>`)
+    const SEPARATE_VALUE_OPTIONS: &[&str] = &[
+        "-p",
+        "--module-path",
+        "--upgrade-module-path",
+        "--add-modules",
+        "--limit-modules",
+        "--add-opens",
+        "--add-exports",
+        "--add-reads",
+        "--patch-module",
+        "-cp",
+        "-classpath",
+        "--class-path",
+    ];
+
+    /// Options that may appear several times with different values
+    const REPEATABLE_OPTIONS: &[&str] = &[
+        "--add-modules",
+        "--add-opens",
+        "--add-exports",
+        "--add-reads",
+        "--patch-module",
+        "-javaagent",
+        "-agentlib",
+        "-agentpath",
+        "-Xlog",
+    ];
+
+    /// Size options written without a separator (e.g. `-Xmx2G`)
+    const SIZE_OPTIONS: &[&str] = &["-Xmx", "-Xms", "-Xss", "-Xmn"];
+
+    /// `-XX` flags that select the garbage collector; only one of them can be enabled
+    const GC_SELECTORS: &[&str] = &[
+        "UseSerialGC",
+        "UseParallelGC",
+        "UseParallelOldGC",
+        "UseConcMarkSweepGC",
+        "UseG1GC",
+        "UseZGC",
+        "UseShenandoahGC",
+        "UseEpsilonGC",
+    ];
+
+    /// The syntactic form of a JVM option
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum JvmOptionKind {
+        /// A system property (`-Dname=value` or `-Dname`)
+        Property,
+
+        /// A size option (`-Xmx2G`, `-Xms`, `-Xss`, `-Xmn`)
+        Size,
+
+        /// A boolean `-XX` flag (`-XX:+Name` or `-XX:-Name`)
+        XxBoolean(bool),
+
+        /// A valued `-XX` flag (`-XX:Name=value`)
+        XxValue,
+
+        /// An option followed by its value as a separate argument (`--add-opens value`)
+        SeparateValue,
+
+        /// An option with an attached value (`--add-opens=value` or `-javaagent:path`)
+        Attached,
+
+        /// Any other flag, kept verbatim
+        Flag,
+    }
+
+    /// A single JVM option, possibly spanning two arguments
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct JvmOption {
+        /// The option name without its value (e.g. "-Xmx", "--add-opens", "MaxRAMPercentage")
+        pub name: String,
+
+        /// The option value, if any
+        pub value: Option<String>,
+
+        /// The syntactic form
+        pub kind: JvmOptionKind,
+
+        /// The arguments exactly as given
+        args: Vec<String>,
+    }
+
+    impl JvmOption {
+        /// Gets the arguments of this option as they should appear on the command line
+        pub fn to_args(&self) -> Vec<String> {
+            self.args.clone()
+        }
+
+        /// Whether the option may appear several times with different values
+        pub fn is_repeatable(&self) -> bool {
+            REPEATABLE_OPTIONS.contains(&self.name.as_str())
+        }
+
+        /// Gets the key used to decide whether a later option overrides this one
+        ///
+        /// Repeatable options are keyed by name and value, so only exact duplicates
+        /// collapse. `-XX:+Name`, `-XX:-Name` and `-XX:Name=value` share a key.
+        pub fn key(&self) -> String {
+            match &self.kind {
+                JvmOptionKind::Property => format!("-D{}", self.name),
+                JvmOptionKind::XxBoolean(_) | JvmOptionKind::XxValue => {
+                    format!("-XX:{}", self.name)
+                }
+                JvmOptionKind::Flag => self.args.join(" "),
+                _ if self.is_repeatable() => format!(
+                    "{}={}",
+                    self.name,
+                    self.value.as_deref().unwrap_or_default()
+                ),
+                _ => canonical_name(&self.name).to_string(),
+            }
+        }
+
+        /// Whether this option replaces an earlier one
+        ///
+        /// Options with the same key replace each other. Since only one garbage
+        /// collector can be active, enabling one also replaces every other selector.
+        pub fn overrides(&self, earlier: &JvmOption) -> bool {
+            self.key() == earlier.key() || (self.selects_gc() && earlier.is_gc_selector())
+        }
+
+        /// Whether this is `-XX:+Name` or `-XX:-Name` for a garbage collector selector
+        fn is_gc_selector(&self) -> bool {
+            matches!(self.kind, JvmOptionKind::XxBoolean(_))
+                && GC_SELECTORS.contains(&self.name.as_str())
+        }
+
+        /// Whether this option enables a garbage collector
+        fn selects_gc(&self) -> bool {
+            self.kind == JvmOptionKind::XxBoolean(true) && self.is_gc_selector()
+        }
+    }
+
+    /// Parses JVM arguments into typed options
+    ///
+    /// Options that take a separate value consume the following argument.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - The JVM arguments in command line order
+    ///
+    /// # Returns
+    ///
+    /// * The options in the same order
+    pub fn parse_jvm_options<I, S>(args: I) -> Vec<JvmOption>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut options = Vec::new();
+        let mut args = args.into_iter().map(Into::into);
+
+        while let Some(arg) = args.next() {
+            if SEPARATE_VALUE_OPTIONS.contains(&arg.as_str()) {
+                let value = args.next();
+                let mut option_args = vec![arg.clone()];
+                option_args.extend(value.clone());
+                options.push(JvmOption {
+                    name: arg,
+                    value,
+                    kind: JvmOptionKind::SeparateValue,
+                    args: option_args,
+                });
+            } else {
+                options.push(parse_single_option(arg));
+            }
+        }
+
+        options
+    }
+
+    /// Parses an option that is contained in one argument
+    fn parse_single_option(arg: String) -> JvmOption {
+        let (name, value, kind) = if let Some(property) = arg.strip_prefix("-D") {
+            match property.split_once('=') {
+                Some((name, value)) => (
+                    name.to_string(),
+                    Some(value.to_string()),
+                    JvmOptionKind::Property,
+                ),
+                None => (property.to_string(), None, JvmOptionKind::Property),
+            }
+        } else if let Some(flag) = arg.strip_prefix("-XX:") {
+            if let Some(name) = flag.strip_prefix('+') {
+                (name.to_string(), None, JvmOptionKind::XxBoolean(true))
+            } else if let Some(name) = flag.strip_prefix('-') {
+                (name.to_string(), None, JvmOptionKind::XxBoolean(false))
+            } else if let Some((name, value)) = flag.split_once('=') {
+                (
+                    name.to_string(),
+                    Some(value.to_string()),
+                    JvmOptionKind::XxValue,
+                )
+            } else {
+                (arg.clone(), None, JvmOptionKind::Flag)
+            }
+        } else if let Some(prefix) = SIZE_OPTIONS.iter().find(|prefix| arg.starts_with(**prefix)) {
+            (
+                prefix.to_string(),
+                Some(arg[prefix.len()..].to_string()),
+                JvmOptionKind::Size,
+            )
+        } else if let Some((name, value)) = arg
+            .split_once('=')
+            .filter(|(name, _)| name.starts_with("--"))
+        {
+            (
+                name.to_string(),
+                Some(value.to_string()),
+                JvmOptionKind::Attached,
+            )
+        } else if let Some((name, value)) = arg.split_once(':').filter(|(name, _)| {
+            matches!(
+                *name,
+                "-javaagent" | "-agentlib" | "-agentpath" | "-Xlog" | "-Xbootclasspath/a"
+            )
+        }) {
+            (
+                name.to_string(),
+                Some(value.to_string()),
+                JvmOptionKind::Attached,
+            )
+        } else if let Some((name, value)) = arg.split_once('=') {
+            (
+                name.to_string(),
+                Some(value.to_string()),
+                JvmOptionKind::Attached,
+            )
+        } else {
+            (arg.clone(), None, JvmOptionKind::Flag)
+        };
+
+        JvmOption {
+            name,
+            value,
+            kind,
+            args: vec![arg],
+        }
+    }
+
+    /// Maps option aliases to one name so they override each other
+    fn canonical_name(name: &str) -> &str {
+        match name {
+            "-p" => "--module-path",
+            "-cp" | "-classpath" => "--class-path",
+            other => other,
+        }
+    }
+
     /// A structure to handle merging of Java arguments from multiple sources.
     /// When merging arguments from different sources, newer arguments take precedence over older ones.
     /// This is useful when combining default arguments with user-provided arguments.
+    ///
+    /// JVM options keep the order in which they were first added. A newer option
+    /// replaces an older one with the same key in place.
     pub struct JavaArgumentsMerger {
-        /// Maps of argument name to its value
-        arguments_map: HashMap<String, String>,
-        /// JVM arguments that don't follow the key=value pattern
-        flag_arguments: Vec<String>,
+        /// JVM options in order of first appearance
+        jvm_options: Vec<JvmOption>,
         /// Classpath entries to be merged
         classpath_entries: Vec<PathBuf>,
         /// Program arguments to be appended
@@ -26,8 +278,7 @@ pub mod arguments_merger {
         /// * A new `JavaArgumentsMerger` instance
         pub fn new() -> Self {
             JavaArgumentsMerger {
-                arguments_map: HashMap::new(),
-                flag_arguments: Vec::new(),
+                jvm_options: Vec::new(),
                 classpath_entries: Vec::new(),
                 program_args: Vec::new(),
             }
@@ -46,81 +297,50 @@ pub mod arguments_merger {
             I: IntoIterator<Item = S>,
             S: Into<String>,
         {
-            for arg in args {
-                let arg_str = arg.into();
-
-                // Handle key=value type arguments
-                if let Some(pos) = arg_str.find('=') {
-                    let (key, value) = arg_str.split_at(pos);
-                    self.arguments_map
-                        .insert(key.to_string(), value[1..].to_string());
-                } else {
-                    // Handle special JVM flags that should be treated as overridable
-                    if arg_str.starts_with("-Xmx")
-                        || arg_str.starts_with("-Xms")
-                        || arg_str.starts_with("-Xss")
-                    {
-                        // Extract the prefix (-Xmx, -Xms, -Xss)
-                        let prefix = &arg_str[0..4];
-
-                        // Remove any existing arguments with the same prefix
-                        self.flag_arguments
-                            .retain(|existing| !existing.starts_with(prefix));
-                        // Add the new argument
-                        self.flag_arguments.push(arg_str);
-                    } else if arg_str.starts_with("-XX:") {
-                        // Handle -XX: flags
-                        // Extract the feature name (everything up to the first : or =)
-                        if let Some(colon_pos) = arg_str[4..].find(':') {
-                            let feature = &arg_str[0..(4 + colon_pos + 1)]; // Include the colon
-
-                            // Remove any existing arguments with the same feature
-                            self.flag_arguments
-                                .retain(|existing| !existing.starts_with(feature));
-                            // Add the new argument
-                            self.flag_arguments.push(arg_str);
-                        } else if let Some(equals_pos) = arg_str[4..].find('=') {
-                            let feature = &arg_str[0..(4 + equals_pos + 1)]; // Include the equals
-
-                            // Remove any existing arguments with the same feature
-                            self.flag_arguments
-                                .retain(|existing| !existing.starts_with(feature));
-                            // Add the new argument
-                            self.flag_arguments.push(arg_str);
-                        } else if arg_str.contains('+') || arg_str.contains('-') {
-                            // Handle boolean flags like -XX:+UseG1GC or -XX:-UseConcMarkSweepGC
-                            // Extract the feature name without the + or - sign
-                            let plus_pos = arg_str[4..].find('+');
-                            let minus_pos = arg_str[4..].find('-');
-
-                            if let Some(pos) = plus_pos.or(minus_pos) {
-                                let feature_base = &arg_str[0..(4 + pos)]; // Without the + or -
-
-                                // Remove any existing arguments with the same feature base (with either + or -)
-                                self.flag_arguments.retain(|existing| {
-                                    !(existing.starts_with(feature_base)
-                                        && (existing.chars().nth(4 + pos) == Some('+')
-                                            || existing.chars().nth(4 + pos) == Some('-')))
-                                });
-                                // Add the new argument
-                                self.flag_arguments.push(arg_str);
-                            } else {
-                                // For other -XX flags that don't match the patterns above
-                                self.flag_arguments.push(arg_str);
-                            }
-                        } else {
-                            // For other -XX flags that don't match the patterns above
-                            self.flag_arguments.push(arg_str);
-                        }
-                    } else {
-                        // For all other flags that don't need special handling
-                        self.flag_arguments.push(arg_str);
-                    }
-                }
+            for option in parse_jvm_options(args) {
+                self.add_jvm_option(option);
+            }
+            self
+        }
+
+        /// Adds a parsed JVM option, replacing the existing options it overrides.
+        ///
+        /// The option takes the place of the first one it overrides; the others are removed.
+        ///
+        /// # Arguments
+        ///
+        /// * `option` - The option to add
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn add_jvm_option(&mut self, option: JvmOption) -> &mut Self {
+            let position = self
+                .jvm_options
+                .iter()
+                .position(|existing| option.overrides(existing));
+            let mut index = 0;
+            self.jvm_options.retain(|existing| {
+                let keep = Some(index) == position || !option.overrides(existing);
+                index += 1;
+                keep
+            });
+            match position {
+                Some(index) => self.jvm_options[index] = option,
+                None => self.jvm_options.push(option),
             }
             self
         }
 
+        /// Gets the merged JVM options in order.
+        ///
+        /// # Returns
+        ///
+        /// * The typed JVM options
+        pub fn jvm_options(&self) -> &[JvmOption] {
+            &self.jvm_options
+        }
+
         /// Adds classpath entries to the merger.
         ///
         /// # Arguments
@@ -169,14 +389,11 @@ pub mod arguments_merger {
         ///
         /// * Self for method chaining
         pub fn merge(&mut self, other: &JavaArgumentsMerger) -> &mut Self {
-            // Merge key-value arguments (newer overrides older)
-            for (key, value) in &other.arguments_map {
-                self.arguments_map.insert(key.clone(), value.clone());
+            // Merge JVM options (newer overrides older)
+            for option in &other.jvm_options {
+                self.add_jvm_option(option.clone());
             }
 
-            // Process flag arguments - use add_jvm_args to ensure proper overriding
-            self.add_jvm_args(other.flag_arguments.clone());
-
             // Append classpath entries
             // Remove duplicates to avoid double entries
             for path in &other.classpath_entries {
@@ -255,17 +472,10 @@ pub mod arguments_merger {
         ///
         /// * A vector of JVM argument strings
         pub fn build_jvm_args(&self) -> Vec<String> {
-            let mut result = Vec::new();
-
-            // Add all flag arguments
-            result.extend(self.flag_arguments.clone());
-
-            // Add all key-value arguments
-            for (key, value) in &self.arguments_map {
-                result.push(format!("{}={}", key, value));
-            }
-
-            result
+            self.jvm_options
+                .iter()
+                .flat_map(JvmOption::to_args)
+                .collect()
         }
 
         /// Gets the merged classpath entries.
@@ -323,7 +533,9 @@ pub mod arguments_merger {
 
 #[cfg(test)]
 mod tests {
-    use super::arguments_merger::{JavaArgumentsMerger, java_args_merger};
+    use super::arguments_merger::{
+        JavaArgumentsMerger, JvmOptionKind, java_args_merger, parse_jvm_options,
+    };
     use std::path::PathBuf;
 
     #[test]
@@ -524,4 +736,156 @@ mod tests {
         assert_eq!(program_args[2], "--server");
         assert_eq!(program_args[3], "play.example.com");
     }
+
+    #[test]
+    /// Tests that merged JVM arguments keep a deterministic order
+    fn test_jvm_args_order() {
+        let mut merger = java_args_merger();
+        merger.add_jvm_args(vec!["-Dz.last=1", "-Xmx1G", "-Da.first=2", "-Dm.middle=3"]);
+        merger.add_jvm_args(vec!["-Da.first=4", "-Xmx2G"]);
+
+        assert_eq!(
+            merger.build_jvm_args(),
+            vec!["-Dz.last=1", "-Xmx2G", "-Da.first=4", "-Dm.middle=3"]
+        );
+    }
+
+    #[test]
+    /// Tests options that take their value as the next argument
+    fn test_separate_value_options() {
+        let mut merger = java_args_merger();
+        merger.add_jvm_args(vec![
+            "-p",
+            "/libs/a.jar",
+            "--add-opens",
+            "java.base/java.util=ALL-UNNAMED",
+            "--add-opens",
+            "java.base/java.lang=ALL-UNNAMED",
+            "--add-exports=java.base/sun.security.util=ALL-UNNAMED",
+        ]);
+        merger.add_jvm_args(vec![
+            "--module-path",
+            "/libs/b.jar",
+            "--add-opens",
+            "java.base/java.util=ALL-UNNAMED",
+        ]);
+
+        assert_eq!(
+            merger.build_jvm_args(),
+            vec![
+                "--module-path",
+                "/libs/b.jar",
+                "--add-opens",
+                "java.base/java.util=ALL-UNNAMED",
+                "--add-opens",
+                "java.base/java.lang=ALL-UNNAMED",
+                "--add-exports=java.base/sun.security.util=ALL-UNNAMED",
+            ]
+        );
+    }
+
+    #[test]
+    /// Tests the -Xmn, -XX:+/- and -XX:name=value forms
+    fn test_override_forms() {
+        let mut merger = java_args_merger();
+        merger.add_jvm_args(vec![
+            "-Xmn128M",
+            "-XX:MaxRAMPercentage=50",
+            "-XX:+UseStringDeduplication",
+            "-XX:+UseG1GC",
+            "-XX:+UnlockExperimentalVMOptions",
+        ]);
+        merger.add_jvm_args(vec![
+            "-Xmn256M",
+            "-XX:MaxRAMPercentage=75.0",
+            "-XX:-UseStringDeduplication",
+            "-XX:+UseZGC",
+        ]);
+
+        assert_eq!(
+            merger.build_jvm_args(),
+            vec![
+                "-Xmn256M",
+                "-XX:MaxRAMPercentage=75.0",
+                "-XX:-UseStringDeduplication",
+                "-XX:+UseZGC",
+                "-XX:+UnlockExperimentalVMOptions",
+            ]
+        );
+    }
+
+    #[test]
+    /// Tests that both signs of a collector flag override each other and that
+    /// enabling a collector removes the other selectors
+    fn test_gc_selectors() {
+        let mut merger = java_args_merger();
+        merger.add_jvm_args(vec!["-XX:+UseG1GC", "-XX:MaxGCPauseMillis=50"]);
+        merger.add_jvm_args(vec!["-XX:-UseG1GC", "-XX:-UseShenandoahGC"]);
+        assert_eq!(
+            merger.build_jvm_args(),
+            vec![
+                "-XX:-UseG1GC",
+                "-XX:MaxGCPauseMillis=50",
+                "-XX:-UseShenandoahGC",
+            ]
+        );
+
+        merger.add_jvm_args(vec!["-XX:+UseZGC"]);
+        assert_eq!(
+            merger.build_jvm_args(),
+            vec!["-XX:+UseZGC", "-XX:MaxGCPauseMillis=50"]
+        );
+    }
+
+    #[test]
+    /// Tests that -Xlog may be given several times with different selections
+    fn test_repeated_xlog() {
+        let mut merger = java_args_merger();
+        merger.add_jvm_args(vec!["-Xlog:gc*:file=gc.log", "-Xlog:safepoint:file=sp.log"]);
+        merger.add_jvm_args(vec!["-Xlog:gc*:file=gc.log", "-Xlog:disable"]);
+
+        assert_eq!(
+            merger.build_jvm_args(),
+            vec![
+                "-Xlog:gc*:file=gc.log",
+                "-Xlog:safepoint:file=sp.log",
+                "-Xlog:disable",
+            ]
+        );
+    }
+
+    #[test]
+    /// Tests parsing options into their kinds
+    fn test_parse_jvm_options() {
+        let options = parse_jvm_options(vec![
+            "-Dfoo=bar",
+            "-Xss4M",
+            "-XX:-UseAdaptiveSizePolicy",
+            "-XX:G1NewSizePercent=20",
+            "--add-modules",
+            "ALL-MODULE-PATH",
+            "-javaagent:agent.jar=opt",
+            "-server",
+        ]);
+
+        let kinds: Vec<JvmOptionKind> = options.iter().map(|o| o.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                JvmOptionKind::Property,
+                JvmOptionKind::Size,
+                JvmOptionKind::XxBoolean(false),
+                JvmOptionKind::XxValue,
+                JvmOptionKind::SeparateValue,
+                JvmOptionKind::Attached,
+                JvmOptionKind::Flag,
+            ]
+        );
+        assert_eq!(options[0].name, "foo");
+        assert_eq!(options[1].value.as_deref(), Some("4M"));
+        assert_eq!(options[4].value.as_deref(), Some("ALL-MODULE-PATH"));
+        assert_eq!(options[5].name, "-javaagent");
+        assert_eq!(options[5].value.as_deref(), Some("agent.jar=opt"));
+        assert!(options[5].is_repeatable());
+    }
 }
@@ -0,0 +1,412 @@
+pub mod java_discovery {
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+
+    /// Name of the java executable on this platform
+    #[cfg(windows)]
+    pub const JAVA_EXECUTABLE: &str = "java.exe";
+
+    /// Name of the java executable on this platform
+    #[cfg(not(windows))]
+    pub const JAVA_EXECUTABLE: &str = "java";
+
+    /// How deep managed and system runtime folders are searched for Java homes
+    const MAX_SEARCH_DEPTH: usize = 4;
+
+    /// Where a Java runtime was found
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum JavaSource {
+        /// The `JAVA_HOME` environment variable
+        JavaHome,
+
+        /// A directory on `PATH`
+        Path,
+
+        /// A system wide JVM directory such as `/usr/lib/jvm`
+        System,
+
+        /// An SDKMAN candidate directory
+        Sdkman,
+
+        /// An asdf install directory
+        Asdf,
+
+        /// A runtime folder managed by a launcher
+        Managed,
+
+        /// A directory added by the caller
+        Custom,
+    }
+
+    /// A Java runtime found on the host
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct JavaRuntime {
+        /// Path to the java executable
+        pub executable: PathBuf,
+
+        /// The Java home directory (the parent of `bin`)
+        pub home: PathBuf,
+
+        /// Full version string (e.g. "17.0.8" or "1.8.0_392")
+        pub version: String,
+
+        /// Major version (e.g. 17 or 8)
+        pub major_version: u32,
+
+        /// Vendor of the runtime (e.g. "Eclipse Adoptium")
+        pub vendor: Option<String>,
+
+        /// Architecture using the names of rule evaluation ("x86_64", "arm64", "x86" or "arm32")
+        pub arch: Option<String>,
+
+        /// Where the runtime was found
+        pub source: JavaSource,
+    }
+
+    /// Finds Java runtimes on the host and caches the result
+    ///
+    /// Searched locations, in order:
+    /// - `JAVA_HOME`
+    /// - every directory on `PATH`
+    /// - `/usr/lib/jvm`, `/usr/java`, `/Library/Java/JavaVirtualMachines` and the Windows
+    ///   `Program Files` Java folders
+    /// - `~/.sdkman/candidates/java` and `~/.asdf/installs/java`
+    /// - launcher managed runtime folders added with `add_managed_dir`
+    /// - directories added with `add_search_dir`
+    #[derive(Debug, Clone)]
+    pub struct JavaDiscovery {
+        include_environment: bool,
+        search_dirs: Vec<(PathBuf, JavaSource)>,
+        cache: Option<Vec<JavaRuntime>>,
+    }
+
+    impl JavaDiscovery {
+        /// Creates a discovery that searches the environment and the well known locations
+        pub fn new() -> Self {
+            let mut discovery = JavaDiscovery::empty();
+            discovery.include_environment = true;
+
+            for dir in [
+                "/usr/lib/jvm",
+                "/usr/lib64/jvm",
+                "/usr/java",
+                "/opt/java",
+                "/Library/Java/JavaVirtualMachines",
+                "C:\\Program Files\\Java",
+                "C:\\Program Files\\Eclipse Adoptium",
+                "C:\\Program Files\\Microsoft",
+                "C:\\Program Files\\Zulu",
+            ] {
+                discovery.add_search_dir(Path::new(dir), JavaSource::System);
+            }
+
+            if let Some(home) = home_dir() {
+                discovery.add_search_dir(
+                    &home.join(".sdkman").join("candidates").join("java"),
+                    JavaSource::Sdkman,
+                );
+                discovery.add_search_dir(
+                    &home.join(".asdf").join("installs").join("java"),
+                    JavaSource::Asdf,
+                );
+                discovery.add_search_dir(
+                    &home.join(".minecraft").join("runtime"),
+                    JavaSource::Managed,
+                );
+            }
+
+            discovery
+        }
+
+        /// Creates a discovery that searches nothing until directories are added
+        pub fn empty() -> Self {
+            JavaDiscovery {
+                include_environment: false,
+                search_dirs: Vec::new(),
+                cache: None,
+            }
+        }
+
+        /// Adds a directory that contains Java homes, at any depth up to four levels.
+        ///
+        /// # Arguments
+        ///
+        /// * `dir` - The directory to search
+        /// * `source` - The source reported for runtimes found there
+        ///
+        /// # Returns
+        ///
+        /// * `&mut Self` for method chaining
+        pub fn add_search_dir(&mut self, dir: &Path, source: JavaSource) -> &mut Self {
+            self.search_dirs.push((dir.to_path_buf(), source));
+            self.cache = None;
+            self
+        }
+
+        /// Adds the runtime folder of a launcher root directory (`root_dir/runtime`).
+        ///
+        /// # Arguments
+        ///
+        /// * `root_dir` - Path to the root directory where game data is stored
+        ///
+        /// # Returns
+        ///
+        /// * `&mut Self` for method chaining
+        pub fn add_managed_dir(&mut self, root_dir: &Path) -> &mut Self {
+            self.add_search_dir(&root_dir.join("runtime"), JavaSource::Managed)
+        }
+
+        /// Gets the discovered runtimes, searching only on the first call
+        pub fn runtimes(&mut self) -> &[JavaRuntime] {
+            if self.cache.is_none() {
+                self.cache = Some(self.discover());
+            }
+            self.cache.as_deref().unwrap_or(&[])
+        }
+
+        /// Searches again, replacing the cached runtimes
+        pub fn refresh(&mut self) -> &[JavaRuntime] {
+            self.cache = None;
+            self.runtimes()
+        }
+
+        /// Searches every configured location
+        fn discover(&self) -> Vec<JavaRuntime> {
+            let mut candidates: Vec<(PathBuf, JavaSource)> = Vec::new();
+
+            if self.include_environment {
+                if let Some(java_home) = std::env::var_os("JAVA_HOME") {
+                    candidates.push((PathBuf::from(java_home), JavaSource::JavaHome));
+                }
+                if let Some(path) = std::env::var_os("PATH") {
+                    for dir in std::env::split_paths(&path) {
+                        let executable = dir.join(JAVA_EXECUTABLE);
+                        if executable.is_file() {
+                            // Follow symlinks such as /usr/bin/java -> /usr/lib/jvm/.../bin/java
+                            let executable = fs::canonicalize(&executable).unwrap_or(executable);
+                            if let Some(home) = executable.parent().and_then(Path::parent) {
+                                candidates.push((home.to_path_buf(), JavaSource::Path));
+                            }
+                        }
+                    }
+                }
+            }
+
+            for (dir, source) in &self.search_dirs {
+                for home in find_java_homes(dir, MAX_SEARCH_DEPTH) {
+                    candidates.push((home, *source));
+                }
+            }
+
+            let mut runtimes: Vec<JavaRuntime> = Vec::new();
+            for (home, source) in candidates {
+                let Some(runtime) = inspect_java_home(&home, source) else {
+                    continue;
+                };
+                let canonical = fs::canonicalize(&runtime.home).unwrap_or(runtime.home.clone());
+                let duplicate = runtimes.iter().any(|known| {
+                    fs::canonicalize(&known.home).unwrap_or(known.home.clone()) == canonical
+                });
+                if !duplicate {
+                    runtimes.push(runtime);
+                }
+            }
+
+            runtimes
+        }
+    }
+
+    impl Default for JavaDiscovery {
+        fn default() -> Self {
+            JavaDiscovery::new()
+        }
+    }
+
+    /// Reads the details of a Java home
+    ///
+    /// The `release` file is used when present. Otherwise the executable is run with
+    /// `-XshowSettings:properties -version` and its output is parsed.
+    ///
+    /// # Arguments
+    ///
+    /// * `home` - The Java home directory
+    /// * `source` - Where the home was found
+    ///
+    /// # Returns
+    ///
+    /// * The runtime, or None if the directory is not a usable Java home
+    pub fn inspect_java_home(home: &Path, source: JavaSource) -> Option<JavaRuntime> {
+        let executable = home.join("bin").join(JAVA_EXECUTABLE);
+        if !executable.is_file() {
+            return None;
+        }
+
+        let properties = match fs::read_to_string(home.join("release")) {
+            Ok(content) => {
+                let release = parse_release_file(&content);
+                let mut properties = HashMap::new();
+                if let Some(version) = release.get("JAVA_VERSION") {
+                    properties.insert("java.version".to_string(), version.clone());
+                }
+                if let Some(vendor) = release
+                    .get("IMPLEMENTOR")
+                    .or_else(|| release.get("JAVA_VENDOR"))
+                {
+                    properties.insert("java.vendor".to_string(), vendor.clone());
+                }
+                if let Some(arch) = release.get("OS_ARCH") {
+                    properties.insert("os.arch".to_string(), arch.clone());
+                }
+                properties
+            }
+            Err(_) => {
+                let output = Command::new(&executable)
+                    .arg("-XshowSettings:properties")
+                    .arg("-version")
+                    .output()
+                    .ok()?;
+                // The settings are printed to stderr
+                let text = format!(
+                    "{}{}",
+                    String::from_utf8_lossy(&output.stderr),
+                    String::from_utf8_lossy(&output.stdout)
+                );
+                parse_properties_output(&text)
+            }
+        };
+
+        let version = properties.get("java.version")?.clone();
+        let major_version = parse_major_version(&version)?;
+
+        Some(JavaRuntime {
+            executable,
+            home: home.to_path_buf(),
+            version,
+            major_version,
+            vendor: properties.get("java.vendor").cloned(),
+            arch: properties.get("os.arch").map(|arch| normalize_arch(arch)),
+            source,
+        })
+    }
+
+    /// Parses the `KEY="value"` lines of a Java `release` file
+    pub fn parse_release_file(content: &str) -> HashMap<String, String> {
+        content
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| {
+                (
+                    key.trim().to_string(),
+                    value.trim().trim_matches('"').to_string(),
+                )
+            })
+            .collect()
+    }
+
+    /// Parses the `key = value` lines printed by `java -XshowSettings:properties`
+    ///
+    /// Continuation lines of multi-valued properties are ignored.
+    pub fn parse_properties_output(output: &str) -> HashMap<String, String> {
+        output
+            .lines()
+            .filter_map(|line| line.trim().split_once(" = "))
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .collect()
+    }
+
+    /// Gets the major version of a Java version string
+    ///
+    /// Example: "1.8.0_392" becomes 8 and "17.0.8+7" becomes 17
+    pub fn parse_major_version(version: &str) -> Option<u32> {
+        let mut parts = version.split(|c: char| !c.is_ascii_digit());
+        let first: u32 = parts.next()?.parse().ok()?;
+        if first == 1 {
+            parts.next()?.parse().ok()
+        } else {
+            Some(first)
+        }
+    }
+
+    /// Converts a JVM `os.arch` value to the names used by rule evaluation
+    pub fn normalize_arch(arch: &str) -> String {
+        match arch.to_lowercase().as_str() {
+            "amd64" | "x86_64" | "x64" => "x86_64".to_string(),
+            "aarch64" | "arm64" => "arm64".to_string(),
+            "x86" | "i386" | "i486" | "i586" | "i686" => "x86".to_string(),
+            "arm" | "arm32" | "aarch32" => "arm32".to_string(),
+            other => other.to_string(),
+        }
+    }
+
+    /// Finds directories that contain `bin/java` below a directory
+    fn find_java_homes(dir: &Path, depth: usize) -> Vec<PathBuf> {
+        if dir.join("bin").join(JAVA_EXECUTABLE).is_file() {
+            return vec![dir.to_path_buf()];
+        }
+        if depth == 0 {
+            return Vec::new();
+        }
+
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut children: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect();
+        children.sort();
+
+        children
+            .iter()
+            .flat_map(|child| find_java_homes(child, depth - 1))
+            .collect()
+    }
+
+    /// Gets the home directory of the current user
+    fn home_dir() -> Option<PathBuf> {
+        directories::BaseDirs::new().map(|dirs| dirs.home_dir().to_path_buf())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::java_discovery::{
+        normalize_arch, parse_major_version, parse_properties_output, parse_release_file,
+    };
+
+    /// Tests major version parsing for both version schemes
+    #[test]
+    fn test_parse_major_version() {
+        assert_eq!(parse_major_version("1.8.0_392"), Some(8));
+        assert_eq!(parse_major_version("17.0.8"), Some(17));
+        assert_eq!(parse_major_version("21"), Some(21));
+        assert_eq!(parse_major_version("17.0.8+7-LTS"), Some(17));
+        assert_eq!(parse_major_version("unknown"), None);
+    }
+
+    /// Tests parsing a `release` file
+    #[test]
+    fn test_parse_release_file() {
+        let release = parse_release_file(
+            "IMPLEMENTOR=\"Eclipse Adoptium\"\nJAVA_VERSION=\"17.0.8\"\nOS_ARCH=\"aarch64\"\n",
+        );
+
+        assert_eq!(release.get("IMPLEMENTOR").unwrap(), "Eclipse Adoptium");
+        assert_eq!(release.get("JAVA_VERSION").unwrap(), "17.0.8");
+        assert_eq!(normalize_arch(release.get("OS_ARCH").unwrap()), "arm64");
+    }
+
+    /// Tests parsing `java -XshowSettings:properties -version` output
+    #[test]
+    fn test_parse_properties_output() {
+        let output = "Property settings:\n    file.separator = /\n    java.class.path = \n    java.library.path = /usr/java/packages/lib\n        /usr/lib64\n    java.vendor = Oracle Corporation\n    java.version = 1.8.0_392\n    os.arch = amd64\n\nopenjdk version \"1.8.0_392\"\n";
+        let properties = parse_properties_output(output);
+
+        assert_eq!(properties.get("java.version").unwrap(), "1.8.0_392");
+        assert_eq!(properties.get("java.vendor").unwrap(), "Oracle Corporation");
+        assert_eq!(normalize_arch(properties.get("os.arch").unwrap()), "x86_64");
+    }
+}
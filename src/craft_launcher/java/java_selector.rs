@@ -0,0 +1,490 @@
+pub mod java_selector {
+    use crate::craft_launcher::core::version::version_resolver::version_resolver::ResolvedVersion;
+    use crate::craft_launcher::java::java_discovery::java_discovery::{
+        JavaRuntime, JavaSource, inspect_java_home,
+    };
+    use std::cmp::Ordering;
+    use std::error::Error;
+    use std::fmt;
+    use std::path::{Path, PathBuf};
+
+    /// Versions released before this date predate `javaVersion` and run on Java 8
+    const JAVA_16_SINCE: &str = "2021-06-08";
+
+    /// First release date of versions that need Java 17 (1.18)
+    const JAVA_17_SINCE: &str = "2021-11-30";
+
+    /// First release date of versions that need Java 21 (1.20.5)
+    const JAVA_21_SINCE: &str = "2024-04-23";
+
+    /// The Java runtime a version needs
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct JavaRequirement {
+        /// The required major version
+        pub major_version: u32,
+
+        /// The Mojang runtime component (e.g. "java-runtime-gamma"), if known
+        pub component: Option<String>,
+
+        /// Whether the requirement was inferred from the release time
+        pub inferred: bool,
+    }
+
+    impl JavaRequirement {
+        /// Creates a requirement for a major version with no runtime component
+        pub fn new(major_version: u32) -> Self {
+            JavaRequirement {
+                major_version,
+                component: None,
+                inferred: false,
+            }
+        }
+
+        /// Gets the requirement of a resolved version
+        ///
+        /// The `javaVersion` field is used when present. Versions without it are
+        /// mapped by their release time, so legacy versions get Java 8 (`jre-legacy`).
+        pub fn for_version(version: &ResolvedVersion) -> Self {
+            if let Some(java_version) = &version.java_version {
+                return JavaRequirement {
+                    major_version: java_version.major_version.max(0) as u32,
+                    component: Some(java_version.component.clone()),
+                    inferred: false,
+                };
+            }
+
+            let (major_version, component) = infer_java_version(&version.release_time);
+            JavaRequirement {
+                major_version,
+                component: Some(component.to_string()),
+                inferred: true,
+            }
+        }
+
+        /// Checks how well a runtime major version satisfies this requirement
+        pub fn compatibility(&self, major_version: u32) -> Compatibility {
+            match major_version.cmp(&self.major_version) {
+                Ordering::Equal => Compatibility::Exact,
+                // Java 8 versions (and Forge/LaunchWrapper on top of them) break on 9+,
+                // while module based versions keep working on newer runtimes.
+                Ordering::Greater if self.major_version >= 16 => Compatibility::Newer,
+                _ => Compatibility::Incompatible,
+            }
+        }
+    }
+
+    impl fmt::Display for JavaRequirement {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "Java {}", self.major_version)?;
+            if let Some(component) = &self.component {
+                write!(f, " ({})", component)?;
+            }
+            Ok(())
+        }
+    }
+
+    /// How a runtime relates to a requirement
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub enum Compatibility {
+        /// The major version matches
+        Exact,
+
+        /// A newer major version that the game is known to run on
+        Newer,
+
+        /// A runtime the game will most likely not run on
+        Incompatible,
+    }
+
+    /// Options for selecting a runtime, usually stored per instance
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct JavaSelectionOptions {
+        /// Use this java executable instead of selecting one
+        pub java_path: Option<PathBuf>,
+
+        /// Require this major version instead of the version's own requirement
+        pub major_version: Option<u32>,
+
+        /// Refuse runtimes that are not compatible with the requirement
+        pub strict: bool,
+    }
+
+    impl JavaSelectionOptions {
+        /// Sets the java executable override.
+        ///
+        /// # Arguments
+        ///
+        /// * `java_path` - Path to a java executable
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_java_path(mut self, java_path: &Path) -> Self {
+            self.java_path = Some(java_path.to_path_buf());
+            self
+        }
+
+        /// Sets the major version override.
+        ///
+        /// # Arguments
+        ///
+        /// * `major_version` - The major version to require
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_major_version(mut self, major_version: u32) -> Self {
+            self.major_version = Some(major_version);
+            self
+        }
+
+        /// Sets strict mode.
+        ///
+        /// # Arguments
+        ///
+        /// * `strict` - Whether incompatible runtimes are refused
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_strict(mut self, strict: bool) -> Self {
+            self.strict = strict;
+            self
+        }
+    }
+
+    /// The runtime picked for a version
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct JavaSelection {
+        /// The selected runtime
+        pub runtime: JavaRuntime,
+
+        /// The requirement it was selected for
+        pub requirement: JavaRequirement,
+
+        /// How the runtime relates to the requirement
+        pub compatibility: Compatibility,
+    }
+
+    /// Error type for Java selection
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum JavaSelectionError {
+        /// No runtimes were found at all
+        NoRuntimes(JavaRequirement),
+
+        /// No runtime satisfies the requirement in strict mode
+        NoCompatibleRuntime {
+            /// The requirement
+            requirement: JavaRequirement,
+
+            /// Major versions of the runtimes that were considered
+            available: Vec<u32>,
+        },
+
+        /// The java executable override is not a usable runtime
+        InvalidJavaPath(PathBuf),
+
+        /// The java executable override does not satisfy the requirement in strict mode
+        IncompatibleJavaPath {
+            /// The override
+            path: PathBuf,
+
+            /// The requirement
+            requirement: JavaRequirement,
+
+            /// Major version of the override
+            found: u32,
+        },
+    }
+
+    impl fmt::Display for JavaSelectionError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                JavaSelectionError::NoRuntimes(requirement) => write!(
+                    f,
+                    "No Java runtime found; this version needs {}",
+                    requirement
+                ),
+                JavaSelectionError::NoCompatibleRuntime {
+                    requirement,
+                    available,
+                } => {
+                    let available: Vec<String> =
+                        available.iter().map(|major| major.to_string()).collect();
+                    write!(
+                        f,
+                        "This version needs {}, but only Java {} is installed",
+                        requirement,
+                        available.join(", ")
+                    )
+                }
+                JavaSelectionError::InvalidJavaPath(path) => {
+                    write!(f, "{} is not a usable Java runtime", path.display())
+                }
+                JavaSelectionError::IncompatibleJavaPath {
+                    path,
+                    requirement,
+                    found,
+                } => write!(
+                    f,
+                    "{} is Java {}, but this version needs {}",
+                    path.display(),
+                    found,
+                    requirement
+                ),
+            }
+        }
+    }
+
+    impl Error for JavaSelectionError {}
+
+    /// Selects the Java runtime to launch a version with
+    ///
+    /// Exact major version matches win over newer compatible runtimes, which win over
+    /// incompatible ones. Ties prefer the Mojang runtime component of the version, then
+    /// launcher managed runtimes, then the lowest newer major version. Incompatible
+    /// runtimes are only returned when strict mode is off.
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - The resolved version to launch
+    /// * `runtimes` - Discovered and managed runtimes
+    /// * `options` - Per-instance overrides
+    ///
+    /// # Returns
+    ///
+    /// * `Result<JavaSelection, JavaSelectionError>` - The selected runtime or an error
+    pub fn select_java(
+        version: &ResolvedVersion,
+        runtimes: &[JavaRuntime],
+        options: &JavaSelectionOptions,
+    ) -> Result<JavaSelection, JavaSelectionError> {
+        let mut requirement = JavaRequirement::for_version(version);
+        if let Some(major_version) = options.major_version
+            && major_version != requirement.major_version
+        {
+            requirement = JavaRequirement::new(major_version);
+        }
+        select_runtime(&requirement, runtimes, options)
+    }
+
+    /// Selects a runtime for a requirement
+    ///
+    /// See `select_java`; `options.major_version` is ignored here.
+    pub fn select_runtime(
+        requirement: &JavaRequirement,
+        runtimes: &[JavaRuntime],
+        options: &JavaSelectionOptions,
+    ) -> Result<JavaSelection, JavaSelectionError> {
+        if let Some(java_path) = &options.java_path {
+            let runtime = inspect_java_path(java_path)
+                .ok_or_else(|| JavaSelectionError::InvalidJavaPath(java_path.clone()))?;
+            let compatibility = requirement.compatibility(runtime.major_version);
+            if options.strict && compatibility == Compatibility::Incompatible {
+                return Err(JavaSelectionError::IncompatibleJavaPath {
+                    path: java_path.clone(),
+                    requirement: requirement.clone(),
+                    found: runtime.major_version,
+                });
+            }
+            return Ok(JavaSelection {
+                runtime,
+                requirement: requirement.clone(),
+                compatibility,
+            });
+        }
+
+        if runtimes.is_empty() {
+            return Err(JavaSelectionError::NoRuntimes(requirement.clone()));
+        }
+
+        let best = runtimes
+            .iter()
+            .map(|runtime| (requirement.compatibility(runtime.major_version), runtime))
+            .filter(|(compatibility, _)| {
+                !options.strict || *compatibility != Compatibility::Incompatible
+            })
+            .min_by(|(a_compat, a), (b_compat, b)| {
+                a_compat
+                    .cmp(b_compat)
+                    .then_with(|| {
+                        major_distance(requirement, a).cmp(&major_distance(requirement, b))
+                    })
+                    .then_with(|| {
+                        matches_component(requirement, b).cmp(&matches_component(requirement, a))
+                    })
+                    .then_with(|| {
+                        (b.source == JavaSource::Managed).cmp(&(a.source == JavaSource::Managed))
+                    })
+            });
+
+        match best {
+            Some((compatibility, runtime)) => Ok(JavaSelection {
+                runtime: runtime.clone(),
+                requirement: requirement.clone(),
+                compatibility,
+            }),
+            None => {
+                let mut available: Vec<u32> = runtimes
+                    .iter()
+                    .map(|runtime| runtime.major_version)
+                    .collect();
+                available.sort_unstable();
+                available.dedup();
+                Err(JavaSelectionError::NoCompatibleRuntime {
+                    requirement: requirement.clone(),
+                    available,
+                })
+            }
+        }
+    }
+
+    /// Infers the Java major version and runtime component from a release time
+    ///
+    /// # Arguments
+    ///
+    /// * `release_time` - An ISO 8601 release time (e.g. "2013-04-18T15:00:00+00:00")
+    ///
+    /// # Returns
+    ///
+    /// * The major version and the Mojang runtime component
+    pub fn infer_java_version(release_time: &str) -> (u32, &'static str) {
+        // ISO 8601 dates compare correctly as strings
+        let date = release_time.get(..10).unwrap_or(release_time);
+        if date.is_empty() || date < JAVA_16_SINCE {
+            (8, "jre-legacy")
+        } else if date < JAVA_17_SINCE {
+            (16, "java-runtime-alpha")
+        } else if date < JAVA_21_SINCE {
+            (17, "java-runtime-gamma")
+        } else {
+            (21, "java-runtime-delta")
+        }
+    }
+
+    /// Reads the runtime of a java executable override
+    fn inspect_java_path(java_path: &Path) -> Option<JavaRuntime> {
+        let home = java_path.parent().and_then(Path::parent)?;
+        let mut runtime = inspect_java_home(home, JavaSource::Custom)?;
+        runtime.executable = java_path.to_path_buf();
+        Some(runtime)
+    }
+
+    /// Distance between the runtime and the required major version
+    fn major_distance(requirement: &JavaRequirement, runtime: &JavaRuntime) -> u32 {
+        runtime.major_version.abs_diff(requirement.major_version)
+    }
+
+    /// Whether a managed runtime was installed for the required component
+    fn matches_component(requirement: &JavaRequirement, runtime: &JavaRuntime) -> bool {
+        match &requirement.component {
+            Some(component) => runtime
+                .home
+                .components()
+                .any(|part| part.as_os_str() == component.as_str()),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::java_selector::{
+        Compatibility, JavaRequirement, JavaSelectionError, JavaSelectionOptions,
+        infer_java_version, select_runtime,
+    };
+    use crate::craft_launcher::java::java_discovery::java_discovery::{JavaRuntime, JavaSource};
+    use std::path::PathBuf;
+
+    fn runtime(major_version: u32, home: &str, source: JavaSource) -> JavaRuntime {
+        JavaRuntime {
+            executable: PathBuf::from(home).join("bin").join("java"),
+            home: PathBuf::from(home),
+            version: major_version.to_string(),
+            major_version,
+            vendor: None,
+            arch: None,
+            source,
+        }
+    }
+
+    /// Tests inferring the requirement of versions without `javaVersion`
+    #[test]
+    fn test_infer_java_version() {
+        assert_eq!(
+            infer_java_version("2017-06-02T13:50:27+00:00"),
+            (8, "jre-legacy")
+        );
+        assert_eq!(infer_java_version("2021-07-06T12:01:34+00:00").0, 16);
+        assert_eq!(infer_java_version("2023-06-12T13:25:51+00:00").0, 17);
+        assert_eq!(infer_java_version("2024-06-13T08:24:03+00:00").0, 21);
+        assert_eq!(infer_java_version("").0, 8);
+    }
+
+    /// Tests the compatibility rules for legacy and modern requirements
+    #[test]
+    fn test_compatibility() {
+        let legacy = JavaRequirement::new(8);
+        assert_eq!(legacy.compatibility(8), Compatibility::Exact);
+        assert_eq!(legacy.compatibility(21), Compatibility::Incompatible);
+
+        let modern = JavaRequirement::new(17);
+        assert_eq!(modern.compatibility(21), Compatibility::Newer);
+        assert_eq!(modern.compatibility(11), Compatibility::Incompatible);
+    }
+
+    /// Tests that exact matches and managed components are preferred
+    #[test]
+    fn test_select_runtime() {
+        let runtimes = vec![
+            runtime(21, "/usr/lib/jvm/java-21", JavaSource::System),
+            runtime(17, "/usr/lib/jvm/java-17", JavaSource::System),
+            runtime(
+                17,
+                "/mc/runtime/java-runtime-gamma/linux/java-runtime-gamma",
+                JavaSource::Managed,
+            ),
+        ];
+        let requirement = JavaRequirement {
+            component: Some("java-runtime-gamma".to_string()),
+            ..JavaRequirement::new(17)
+        };
+
+        let selection =
+            select_runtime(&requirement, &runtimes, &JavaSelectionOptions::default()).unwrap();
+        assert_eq!(selection.compatibility, Compatibility::Exact);
+        assert_eq!(selection.runtime.source, JavaSource::Managed);
+
+        let selection =
+            select_runtime(&JavaRequirement::new(16), &runtimes, &Default::default()).unwrap();
+        assert_eq!(selection.compatibility, Compatibility::Newer);
+        assert_eq!(selection.runtime.major_version, 17);
+    }
+
+    /// Tests strict mode refusing incompatible runtimes
+    #[test]
+    fn test_strict_mode() {
+        let runtimes = vec![
+            runtime(21, "/usr/lib/jvm/java-21", JavaSource::System),
+            runtime(17, "/usr/lib/jvm/java-17", JavaSource::System),
+        ];
+        let requirement = JavaRequirement::new(8);
+
+        let selection = select_runtime(&requirement, &runtimes, &Default::default()).unwrap();
+        assert_eq!(selection.compatibility, Compatibility::Incompatible);
+        assert_eq!(selection.runtime.major_version, 17);
+
+        let strict = JavaSelectionOptions::default().with_strict(true);
+        assert_eq!(
+            select_runtime(&requirement, &runtimes, &strict).unwrap_err(),
+            JavaSelectionError::NoCompatibleRuntime {
+                requirement: requirement.clone(),
+                available: vec![17, 21],
+            }
+        );
+        assert!(matches!(
+            select_runtime(&requirement, &[], &strict).unwrap_err(),
+            JavaSelectionError::NoRuntimes(_)
+        ));
+    }
+}
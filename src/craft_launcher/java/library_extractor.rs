@@ -1,15 +1,23 @@
 pub mod library_extractor {
-    use crate::craft_launcher::core::version::library_parser::library_parser::{
-        LibraryInfo, convert_version_to_libraries,
+    use crate::craft_launcher::core::process_registry::process_registry::is_process_running;
+    use crate::craft_launcher::core::version::base_version::{Extract, Library as BaseLibrary};
+    use crate::craft_launcher::core::version::library_conflict::library_conflict::ConflictPolicy;
+    use crate::craft_launcher::core::version::library_installer::library_installer::DEFAULT_MAVEN_REPOSITORIES;
+    use crate::craft_launcher::core::version::library_parser::library_parser::LibraryInfo;
+    use crate::craft_launcher::core::version::maven_coordinate::maven_coordinate::MavenCoordinate;
+    use crate::craft_launcher::core::version::rules::rules::{RuleContext, rules_allow};
+    use crate::craft_launcher::core::version::version_resolver::version_resolver::{
+        ResolvedVersion, resolve_version,
     };
-    use crate::craft_launcher::core::version::version_parser::version_parser::parse_version_from_file;
     use crate::craft_launcher::utils::file_operations::file_utils;
-    use crate::craft_launcher::utils::networking::networking;
+    use crate::craft_launcher::utils::networking::networking::{self, DownloadError};
     use sha2::{Digest, Sha256};
-    use std::collections::HashMap;
+    use std::collections::HashSet;
     use std::fs::{self, File};
-    use std::io::{self, Cursor, Read, Write};
+    use std::io;
     use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
     /// Error type for library extraction operations
     #[derive(Debug)]
@@ -21,23 +29,261 @@ pub mod library_extractor {
         ZipError(zip::result::ZipError),
     }
 
+    impl std::fmt::Display for LibraryExtractionError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                LibraryExtractionError::IoError(e) => write!(f, "IO error: {}", e),
+                LibraryExtractionError::NetworkingError(e) => write!(f, "Download failed: {}", e),
+                LibraryExtractionError::VersionParsingError(e) => {
+                    write!(f, "Failed to parse version: {}", e)
+                }
+                LibraryExtractionError::NoNativeLibrariesFound => {
+                    write!(f, "No native libraries found")
+                }
+                LibraryExtractionError::ZipError(e) => write!(f, "Invalid native archive: {}", e),
+            }
+        }
+    }
+
+    impl std::error::Error for LibraryExtractionError {}
+
     impl From<io::Error> for LibraryExtractionError {
         fn from(error: io::Error) -> Self {
             LibraryExtractionError::IoError(error)
         }
     }
 
+    impl From<DownloadError> for LibraryExtractionError {
+        fn from(error: DownloadError) -> Self {
+            match error {
+                DownloadError::IoError(e) => LibraryExtractionError::IoError(e),
+                other => LibraryExtractionError::NetworkingError(other.to_string()),
+            }
+        }
+    }
+
     impl From<zip::result::ZipError> for LibraryExtractionError {
         fn from(error: zip::result::ZipError) -> Self {
             LibraryExtractionError::ZipError(error)
         }
     }
 
+    /// A native library artifact selected for a target platform
+    #[derive(Debug, Clone)]
+    pub struct NativeLibrary {
+        /// Name of the library that provides the natives
+        pub name: String,
+
+        /// The classifier selected for the platform (e.g. "natives-windows-arm64")
+        pub classifier: String,
+
+        /// Path of the artifact relative to the libraries directory
+        pub path: String,
+
+        /// URL to download the artifact
+        pub url: Option<String>,
+
+        /// SHA-1 hash of the artifact, if known
+        pub sha1: Option<String>,
+
+        /// Files to exclude from extraction
+        pub extract: Option<Extract>,
+    }
+
+    /// Selects the native library artifacts for a target platform
+    ///
+    /// Two schemes are supported:
+    /// - Legacy (before 1.19): a library maps OS names to classifiers in `natives`,
+    ///   where `${arch}` is replaced with "32" or "64", and the artifact is looked up
+    ///   in `downloads.classifiers`.
+    /// - Modern (1.19+): natives are ordinary libraries with a `natives-*` classifier
+    ///   gated by `rules`. When architecture specific variants such as `-arm64` or `-x86`
+    ///   exist, the one matching the platform replaces the generic x86_64 variant.
+    ///
+    /// # Arguments
+    ///
+    /// * `libraries` - The libraries of a version
+    /// * `context` - The platform and features to evaluate rules against
+    ///
+    /// # Returns
+    ///
+    /// * The native artifacts for the platform, in library order
+    pub fn select_native_libraries(
+        libraries: &[LibraryInfo],
+        context: &RuleContext,
+    ) -> Vec<NativeLibrary> {
+        let mut legacy = Vec::new();
+        let mut modern: Vec<(MavenCoordinate, NativeLibrary)> = Vec::new();
+
+        for library in libraries {
+            if !rules_allow(library.rules(), context) {
+                continue;
+            }
+
+            if let LibraryInfo::Base(base) = library
+                && base.natives.is_some()
+            {
+                if let Some(native) = select_legacy_native(base, context) {
+                    legacy.push(native);
+                }
+                continue;
+            }
+
+            let Ok(coordinate) = library.coordinate() else {
+                continue;
+            };
+            let Some(classifier) = coordinate.classifier.clone() else {
+                continue;
+            };
+            if !classifier.starts_with("natives-") {
+                continue;
+            }
+
+            let (os, _) = classifier_platform(&classifier);
+            if os.is_some_and(|os| os != context.platform.os_name) {
+                continue;
+            }
+
+            let Some(path) = library.artifact_path() else {
+                continue;
+            };
+            let (url, sha1) = match library {
+                LibraryInfo::Base(base) => {
+                    let artifact = base.downloads.as_ref().and_then(|d| d.artifact.as_ref());
+                    (
+                        artifact.map(|a| a.url.clone()),
+                        artifact.map(|a| a.sha1.clone()),
+                    )
+                }
+                LibraryInfo::Generic { url, sha1, .. } => (url.clone(), sha1.clone()),
+            };
+
+            modern.push((
+                coordinate,
+                NativeLibrary {
+                    name: library.name().to_string(),
+                    classifier,
+                    path,
+                    url,
+                    sha1,
+                    extract: match library {
+                        LibraryInfo::Base(base) => base.extract.clone(),
+                        LibraryInfo::Generic { .. } => None,
+                    },
+                },
+            ));
+        }
+
+        // Keep the variant for the platform architecture, falling back to the generic one
+        let arch = context.platform.arch.as_str();
+        let selected_modern = modern
+            .iter()
+            .filter(
+                |(coordinate, native)| match classifier_platform(&native.classifier).1 {
+                    Some(native_arch) => native_arch == arch,
+                    None => !modern.iter().any(|(other, other_native)| {
+                        other.group_id == coordinate.group_id
+                            && other.artifact_id == coordinate.artifact_id
+                            && other.version == coordinate.version
+                            && classifier_platform(&other_native.classifier).1 == Some(arch)
+                    }),
+                },
+            )
+            .map(|(_, native)| native.clone());
+
+        legacy.into_iter().chain(selected_modern).collect()
+    }
+
+    /// Selects the classifier artifact of a library using the legacy `natives` map
+    fn select_legacy_native(library: &BaseLibrary, context: &RuleContext) -> Option<NativeLibrary> {
+        let classifier = library
+            .natives
+            .as_ref()?
+            .get(&context.platform.os_name)?
+            .replace("${arch}", context.platform.arch_bits());
+
+        let classifiers = library
+            .downloads
+            .as_ref()
+            .and_then(|d| d.classifiers.as_ref());
+
+        match classifiers {
+            // The version JSON lists the classifiers that exist for this library
+            Some(classifiers) => {
+                let artifact = classifiers.get(&classifier)?;
+                Some(NativeLibrary {
+                    name: library.name.clone(),
+                    classifier,
+                    path: artifact.path.clone(),
+                    url: Some(artifact.url.clone()),
+                    sha1: Some(artifact.sha1.clone()),
+                    extract: library.extract.clone(),
+                })
+            }
+            None => {
+                // Libraries without download information are resolved from their Maven repository
+                let coordinate = MavenCoordinate::parse(&library.name)
+                    .ok()?
+                    .with_classifier(classifier.clone());
+                let repository = library
+                    .url
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_MAVEN_REPOSITORIES[0].to_string());
+                Some(NativeLibrary {
+                    name: library.name.clone(),
+                    classifier,
+                    path: coordinate.to_path(),
+                    url: Some(coordinate.to_url(&repository)),
+                    sha1: None,
+                    extract: library.extract.clone(),
+                })
+            }
+        }
+    }
+
+    /// Splits a `natives-*` classifier into its OS and architecture
+    ///
+    /// The OS is returned with the name used in rules ("windows", "osx" or "linux").
+    /// A classifier without an architecture suffix targets x86_64 and returns None.
+    ///
+    /// Example: "natives-macos-arm64" becomes (Some("osx"), Some("arm64"))
+    pub fn classifier_platform(classifier: &str) -> (Option<&'static str>, Option<&'static str>) {
+        let rest = classifier.strip_prefix("natives-").unwrap_or(classifier);
+        let (os, arch) = match rest.split_once('-') {
+            Some((os, arch)) => (os, Some(arch)),
+            None => (rest, None),
+        };
+
+        let os = match os {
+            "windows" => Some("windows"),
+            "macos" | "osx" => Some("osx"),
+            "linux" => Some("linux"),
+            _ => None,
+        };
+        let arch = match arch {
+            Some("arm64") | Some("aarch64") => Some("arm64"),
+            Some("arm32") => Some("arm32"),
+            Some("x86") => Some("x86"),
+            Some("x86_64") => Some("x86_64"),
+            _ => None,
+        };
+        (os, arch)
+    }
+
+    /// Name of the marker file written once a native directory is completely extracted
+    const COMPLETE_MARKER: &str = ".complete";
+
+    /// Age after which a temporary directory is removed even if its process seems alive
+    const TEMP_DIR_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+    /// File extensions of native libraries that are extracted
+    const NATIVE_EXTENSIONS: [&str; 4] = [".dll", ".so", ".dylib", ".jnilib"];
+
     /// Extracts native library files for a specific Minecraft version
     ///
     /// This function downloads and extracts native library files for the specified Minecraft version
-    /// and the current operating system. It creates a zip archive containing all native libraries
-    /// and then extracts them to a directory named after the SHA-256 hash of the zip file.
+    /// and the current operating system into `bin/<key>`, where the key is a SHA-256 hash of the
+    /// native jars and their extraction rules. An existing directory with the same key is reused.
     ///
     /// # Arguments
     ///
@@ -51,19 +297,262 @@ pub mod library_extractor {
         root_dir: &Path,
         version_id: &str,
     ) -> Result<PathBuf, LibraryExtractionError> {
-        // Define paths
-        let libraries_dir = root_dir.join("libraries");
-        let versions_dir = root_dir.join("versions");
+        extract_native_libraries_for(root_dir, version_id, &RuleContext::current())
+    }
+
+    /// Extracts native library files for a specific Minecraft version and target platform
+    ///
+    /// Same as `extract_native_libraries`, but the platform is given explicitly.
+    /// Libraries inherited through `inheritsFrom` are included.
+    ///
+    /// Extraction happens in a private temporary directory that is renamed into place,
+    /// so two launches of the same version at once never see a half extracted directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `root_dir` - Path to the root directory where game data is stored
+    /// * `version_id` - The Minecraft version ID (e.g., "1.16.5")
+    /// * `context` - The platform to select native libraries for
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PathBuf, LibraryExtractionError>` - The path to the directory containing extracted native libraries
+    pub fn extract_native_libraries_for(
+        root_dir: &Path,
+        version_id: &str,
+        context: &RuleContext,
+    ) -> Result<PathBuf, LibraryExtractionError> {
+        let version = resolve(root_dir, version_id)?;
+        extract_native_libraries_from(root_dir, &version.libraries, context)
+    }
+
+    /// Extracts the native library files of already resolved libraries
+    ///
+    /// Same as `extract_native_libraries_for`, but uses the given libraries instead of
+    /// resolving a version, so a caller that resolved the version with its own conflict
+    /// policy extracts exactly the natives of that resolution.
+    ///
+    /// # Arguments
+    ///
+    /// * `root_dir` - Path to the root directory where game data is stored
+    /// * `libraries` - The libraries of the resolved version
+    /// * `context` - The platform to select native libraries for
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PathBuf, LibraryExtractionError>` - The path to the directory containing extracted native libraries
+    pub fn extract_native_libraries_from(
+        root_dir: &Path,
+        libraries: &[LibraryInfo],
+        context: &RuleContext,
+    ) -> Result<PathBuf, LibraryExtractionError> {
         let bin_dir = root_dir.join("bin");
+        fs::create_dir_all(&bin_dir)?;
+
+        let native_libraries = native_jars(root_dir, libraries, context, true)?;
+
+        // Some Minecraft versions may not require native libraries
+        if native_libraries.is_empty() {
+            return Err(LibraryExtractionError::NoNativeLibrariesFound);
+        }
+
+        let key = native_cache_key(&native_libraries)?;
+        let hash_dir = bin_dir.join(&key);
 
-        // Create bin directory if it doesn't exist
-        if !bin_dir.exists() {
-            fs::create_dir_all(&bin_dir)?;
+        // Reuse a directory that was completely extracted before
+        if hash_dir.join(COMPLETE_MARKER).exists() {
+            return Ok(hash_dir);
         }
 
-        // Construct version JSON path
-        let version_dir = versions_dir.join(version_id);
-        let version_json_path = version_dir.join(format!("{}.json", version_id));
+        let temp_dir = bin_dir.join(format!(
+            "{}.tmp-{}-{}",
+            key,
+            std::process::id(),
+            unique_suffix()
+        ));
+        if let Err(e) = extract_into(&native_libraries, &temp_dir) {
+            let _ = fs::remove_dir_all(&temp_dir);
+            return Err(e);
+        }
+
+        // A leftover directory without a marker is an interrupted extraction
+        if hash_dir.exists() && !hash_dir.join(COMPLETE_MARKER).exists() {
+            let _ = fs::remove_dir_all(&hash_dir);
+        }
+
+        if fs::rename(&temp_dir, &hash_dir).is_err() {
+            // Another launch finished first, use its directory
+            let _ = fs::remove_dir_all(&temp_dir);
+            if !hash_dir.join(COMPLETE_MARKER).exists() {
+                return Err(LibraryExtractionError::IoError(io::Error::other(format!(
+                    "Failed to move native libraries into {}",
+                    hash_dir.display()
+                ))));
+            }
+        }
+
+        Ok(hash_dir)
+    }
+
+    /// Gets the directory natives of a version would be extracted into, without extracting
+    ///
+    /// # Arguments
+    ///
+    /// * `root_dir` - Path to the root directory where game data is stored
+    /// * `version_id` - ID of the Minecraft version
+    /// * `context` - The platform to select native libraries for
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(path))` - The cache directory, which may not exist yet
+    /// * `Ok(None)` - The version has no native libraries for the platform
+    /// * `Err(LibraryExtractionError)` - A native jar is missing or the version cannot be resolved
+    pub fn native_directory_for(
+        root_dir: &Path,
+        version_id: &str,
+        context: &RuleContext,
+    ) -> Result<Option<PathBuf>, LibraryExtractionError> {
+        let version = resolve(root_dir, version_id)?;
+        native_directory_for_libraries(root_dir, &version.libraries, context)
+    }
+
+    /// Gets the directory natives of already resolved libraries would be extracted into
+    ///
+    /// # Arguments
+    ///
+    /// * `root_dir` - Path to the root directory where game data is stored
+    /// * `libraries` - The libraries of the resolved version
+    /// * `context` - The platform to select native libraries for
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(path))` - The cache directory, which may not exist yet
+    /// * `Ok(None)` - The libraries have no natives for the platform
+    /// * `Err(LibraryExtractionError)` - A native jar is missing
+    pub fn native_directory_for_libraries(
+        root_dir: &Path,
+        libraries: &[LibraryInfo],
+        context: &RuleContext,
+    ) -> Result<Option<PathBuf>, LibraryExtractionError> {
+        let native_libraries = native_jars(root_dir, libraries, context, false)?;
+        if native_libraries.is_empty() {
+            return Ok(None);
+        }
+        let key = native_cache_key(&native_libraries)?;
+        Ok(Some(root_dir.join("bin").join(key)))
+    }
+
+    /// Removes native directories in `bin` that no installed version uses
+    ///
+    /// The keys of all versions in `root_dir/versions` are computed for the given platform
+    /// from the native jars present on disk. Hash directories with any other key, temporary
+    /// directories of processes that are no longer running (or older than a day) and old
+    /// `native-libs-*.zip` files are removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `root_dir` - Path to the root directory where game data is stored
+    /// * `context` - The platform to compute the native directories for
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<PathBuf>, LibraryExtractionError>` - The removed paths
+    pub fn prune_native_directories(
+        root_dir: &Path,
+        context: &RuleContext,
+    ) -> Result<Vec<PathBuf>, LibraryExtractionError> {
+        let bin_dir = root_dir.join("bin");
+        let versions_dir = root_dir.join("versions");
+        if !bin_dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut used_keys = Vec::new();
+        if versions_dir.is_dir() {
+            for entry in fs::read_dir(&versions_dir)? {
+                let entry = entry?;
+                let Some(version_id) = entry.file_name().to_str().map(str::to_string) else {
+                    continue;
+                };
+                // Versions that cannot be resolved or have missing jars keep nothing alive
+                if let Ok(version) = resolve(root_dir, &version_id)
+                    && let Ok(natives) = native_jars(root_dir, &version.libraries, context, false)
+                    && !natives.is_empty()
+                    && let Ok(key) = native_cache_key(&natives)
+                {
+                    used_keys.push(key);
+                }
+            }
+        }
+
+        let mut removed = Vec::new();
+        for entry in fs::read_dir(&bin_dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            let key = name.split(".tmp-").next().unwrap_or(&name);
+            let is_hash = key.len() == 64 && key.chars().all(|c| c.is_ascii_hexdigit());
+            let is_temp = name.contains(".tmp-");
+            let is_debug_zip = name.starts_with("native-libs-") && name.ends_with(".zip");
+
+            let remove = if is_debug_zip {
+                true
+            } else if is_hash && path.is_dir() {
+                if is_temp {
+                    is_abandoned_temp(&name, &path)
+                } else {
+                    !used_keys.iter().any(|k| k == key)
+                }
+            } else {
+                false
+            };
+
+            if remove {
+                if path.is_dir() {
+                    fs::remove_dir_all(&path)?;
+                } else {
+                    fs::remove_file(&path)?;
+                }
+                removed.push(path);
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Checks whether a temporary extraction directory was left behind
+    ///
+    /// A directory is abandoned when the process named in it is no longer running,
+    /// or when it is older than `TEMP_DIR_MAX_AGE` in case the PID was reused.
+    fn is_abandoned_temp(name: &str, path: &Path) -> bool {
+        let pid = name
+            .split(".tmp-")
+            .nth(1)
+            .and_then(|rest| rest.split('-').next())
+            .and_then(|pid| pid.parse::<u32>().ok());
+        // Temporary directories of this process may still be in use
+        if pid == Some(std::process::id()) {
+            return false;
+        }
+        let running = pid.is_some_and(is_process_running);
+        let expired = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > TEMP_DIR_MAX_AGE);
+        !running || expired
+    }
+
+    /// Resolves a version together with the versions it inherits from
+    fn resolve(
+        root_dir: &Path,
+        version_id: &str,
+    ) -> Result<ResolvedVersion, LibraryExtractionError> {
+        let version_json_path = root_dir
+            .join("versions")
+            .join(version_id)
+            .join(format!("{}.json", version_id));
 
         // Check if the version JSON exists
         if !file_utils::exists(&version_json_path) {
@@ -76,208 +565,319 @@ pub mod library_extractor {
             )));
         }
 
-        // Parse the version JSON
-        let version = parse_version_from_file(&version_json_path).map_err(|e| {
+        resolve_version(root_dir, version_id, ConflictPolicy::ChildWins).map_err(|e| {
             LibraryExtractionError::VersionParsingError(format!(
                 "Failed to parse version JSON: {:?}",
                 e
             ))
-        })?;
+        })
+    }
+
+    /// Gets the native jars of libraries, in selection order
+    ///
+    /// Missing jars are downloaded when `download` is set, otherwise they are an error.
+    fn native_jars(
+        root_dir: &Path,
+        libraries: &[LibraryInfo],
+        context: &RuleContext,
+        download: bool,
+    ) -> Result<Vec<(PathBuf, Option<Extract>)>, LibraryExtractionError> {
+        let libraries_dir = root_dir.join("libraries");
+        let mut native_libraries = Vec::new();
+        for native in select_native_libraries(libraries, context) {
+            let native_lib_path = libraries_dir.join(&native.path);
+
+            // Download native library if needed
+            if !native_lib_path.exists() {
+                if !download {
+                    return Err(LibraryExtractionError::IoError(io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("Native library not found: {}", native_lib_path.display()),
+                    )));
+                }
+                let Some(download_url) = &native.url else {
+                    return Err(LibraryExtractionError::NetworkingError(format!(
+                        "No download URL for native library: {}",
+                        native.name
+                    )));
+                };
+                download_native(download_url, native.sha1.as_deref(), &native_lib_path)?;
+            }
 
-        // Convert version to libraries
-        let libraries = convert_version_to_libraries(version);
+            native_libraries.push((native_lib_path, native.extract));
+        }
 
-        // Get the current OS name
-        let os_name = std::env::consts::OS;
+        Ok(native_libraries)
+    }
 
-        // Map OS name to classifier prefix
-        let classifier_prefix = match os_name {
-            "windows" => "natives-windows",
-            "macos" => "natives-macos",
-            "linux" => "natives-linux",
-            _ => {
-                return Err(LibraryExtractionError::NoNativeLibrariesFound);
-            }
+    /// Downloads a native jar, checking its SHA-1 hash when known, and writes it atomically
+    fn download_native(
+        url: &str,
+        sha1: Option<&str>,
+        dest: &Path,
+    ) -> Result<(), LibraryExtractionError> {
+        let bytes = match sha1 {
+            Some(sha1) => networking::fetch_verified(url, sha1)?,
+            None => networking::fetch_bytes(url).map_err(|e| {
+                LibraryExtractionError::NetworkingError(format!(
+                    "Failed to download native library from {}: {}",
+                    url, e
+                ))
+            })?,
         };
+        file_utils::write_atomically(dest, &bytes)?;
+        Ok(())
+    }
 
-        // Collect native libraries for current OS
-        let mut native_libraries = Vec::new();
-        for library in &libraries {
-            match library {
-                LibraryInfo::Base(base_lib) => {
-                    // Process native libraries
-                    if let Some(natives) = &base_lib.natives {
-                        if let Some(classifier) = natives.get(os_name) {
-                            if let Some(downloads) = &base_lib.downloads {
-                                if let Some(classifiers) = &downloads.classifiers {
-                                    // Check if classifier equals the classifier_prefix or contains it
-                                    if classifier == classifier_prefix
-                                        || classifiers.contains_key(classifier_prefix)
-                                    {
-                                        // Try to get the classifier directly first
-                                        let artifact =
-                                            if classifiers.contains_key(classifier_prefix) {
-                                                classifiers.get(classifier_prefix)
-                                            } else {
-                                                classifiers.get(classifier)
-                                            };
-
-                                        if let Some(artifact) = artifact {
-                                            let native_lib_path =
-                                                libraries_dir.join(&artifact.path);
-                                            let download_url = &artifact.url;
-
-                                            // Download native library if needed
-                                            if !native_lib_path.exists() {
-                                                if let Some(parent) = native_lib_path.parent() {
-                                                    if !parent.exists() {
-                                                        fs::create_dir_all(parent)?;
-                                                    }
-                                                }
-                                                let result = networking::download_file(
-                                                    download_url,
-                                                    &native_lib_path,
-                                                );
-                                                if let Err(e) = result {
-                                                    return Err(
-                                                        LibraryExtractionError::NetworkingError(
-                                                            format!(
-                                                                "Failed to download native library: {:?}",
-                                                                e
-                                                            ),
-                                                        ),
-                                                    );
-                                                }
-                                            }
-
-                                            // Add to our list of native libraries
-                                            native_libraries
-                                                .push((native_lib_path, base_lib.extract.clone()));
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-                LibraryInfo::Generic { .. } => {
-                    // Most generic libraries don't have native components
+    /// Computes the cache key of a set of native jars
+    ///
+    /// The key covers the content hash of every jar, in order, and its exclusion rules,
+    /// so it changes whenever the extracted files would change.
+    fn native_cache_key(
+        native_libraries: &[(PathBuf, Option<Extract>)],
+    ) -> Result<String, LibraryExtractionError> {
+        let mut hasher = Sha256::new();
+        for (path, extract) in native_libraries {
+            hasher.update(file_utils::calculate_sha1(&path.to_path_buf())?.as_bytes());
+            hasher.update(b"\n");
+            if let Some(extract) = extract {
+                for exclude in &extract.exclude {
+                    hasher.update(exclude.as_bytes());
+                    hasher.update(b"\n");
                 }
             }
+            hasher.update(b"\0");
         }
+        Ok(format!("{:x}", hasher.finalize()))
+    }
 
-        // Some Minecraft versions may not require native libraries
-        if native_libraries.is_empty() {
-            return Err(LibraryExtractionError::NoNativeLibrariesFound);
-        }
+    /// Extracts the native files of the jars into a directory and marks it complete
+    fn extract_into(
+        native_libraries: &[(PathBuf, Option<Extract>)],
+        target_dir: &Path,
+    ) -> Result<(), LibraryExtractionError> {
+        fs::create_dir_all(target_dir)?;
+        let mut extracted_files = HashSet::new();
+
+        for (lib_path, extract_info) in native_libraries {
+            // Open the JAR file (which is a zip file)
+            let jar_file = File::open(lib_path)?;
+            let mut jar_archive = zip::ZipArchive::new(jar_file)?;
+
+            // Get list of files to exclude from extraction
+            let excluded_files: &[String] = match extract_info {
+                Some(extract) => &extract.exclude,
+                None => &[],
+            };
+
+            for i in 0..jar_archive.len() {
+                let mut file = jar_archive.by_index(i)?;
+                let outpath = file.name().to_string();
+
+                // Skip directories and excluded files
+                if file.is_dir()
+                    || outpath.contains("META-INF")
+                    || excluded_files
+                        .iter()
+                        .any(|ex| outpath.starts_with(ex.as_str()))
+                {
+                    continue;
+                }
 
-        // Create a temporary buffer for our zip file
-        let mut zip_buffer = Vec::new();
-        {
-            // Create a new zip file
-            let mut zip = zip::ZipWriter::new(Cursor::new(&mut zip_buffer));
-            let options = zip::write::FileOptions::<()>::default()
-                .compression_method(zip::CompressionMethod::Deflated)
-                .unix_permissions(0o755);
-
-            let mut extracted_files = HashMap::new();
-
-            // Extract and add each native library to the zip
-            for (lib_path, extract_info) in native_libraries {
-                // Open the JAR file (which is a zip file)
-                let jar_file = File::open(&lib_path)?;
-                let mut jar_archive = zip::ZipArchive::new(jar_file)?;
-
-                // Get list of files to exclude from extraction
-                let excluded_files = if let Some(extract) = extract_info {
-                    extract.exclude
-                } else {
-                    vec![]
+                // Only include native library files
+                if !NATIVE_EXTENSIONS.iter().any(|ext| outpath.ends_with(ext)) {
+                    continue;
+                }
+
+                // Extract just the filename, the first jar providing a file wins
+                let Some(filename) = Path::new(&outpath)
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .map(str::to_string)
+                else {
+                    continue;
                 };
+                if !extracted_files.insert(filename.clone()) {
+                    continue;
+                }
 
-                // Process each file in the JAR
-                for i in 0..jar_archive.len() {
-                    let mut file = jar_archive.by_index(i)?;
-                    let outpath = file.name().to_string();
-
-                    // Skip directories and excluded files
-                    if file.is_dir()
-                        || outpath.contains("META-INF")
-                        || excluded_files.iter().any(|ex| outpath.contains(ex))
-                    {
-                        continue;
-                    }
+                let target_path = target_dir.join(&filename);
+                let mut outfile = File::create(&target_path)?;
+                io::copy(&mut file, &mut outfile)?;
 
-                    // Only include DLL, SO, and DYLIB files
-                    let is_native_lib = outpath.ends_with(".dll")
-                        || outpath.ends_with(".so")
-                        || outpath.ends_with(".dylib");
-
-                    if is_native_lib {
-                        // Extract just the filename
-                        let filename = Path::new(&outpath)
-                            .file_name()
-                            .and_then(|n| n.to_str())
-                            .unwrap_or_else(|| &outpath);
-
-                        // Skip if we already have this file (prevent duplicates)
-                        if extracted_files.contains_key(filename) {
-                            continue;
-                        }
-
-                        // Read the file data
-                        let mut buffer = Vec::new();
-                        file.read_to_end(&mut buffer)?;
-
-                        // Add to our zip
-                        zip.start_file(filename, options)?;
-                        zip.write_all(&buffer)?;
-
-                        // Mark as extracted
-                        extracted_files.insert(filename.to_string(), true);
-                    }
+                // Set file permissions (Unix only)
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::PermissionsExt;
+                    let mode = (file.unix_mode().unwrap_or(0o755) & 0o777) | 0o644;
+                    fs::set_permissions(&target_path, fs::Permissions::from_mode(mode))?;
                 }
             }
-
-            // Finalize the zip
-            zip.finish()?;
         }
 
-        // Hash the zip file
-        let mut hasher = Sha256::new();
-        hasher.update(&zip_buffer);
-        let hash = format!("{:x}", hasher.finalize());
+        fs::write(target_dir.join(COMPLETE_MARKER), b"")?;
+        Ok(())
+    }
 
-        // Save the zip file (optional, but useful for debugging)
-        let zip_path = bin_dir.join(format!("native-libs-{}.zip", version_id));
-        fs::write(&zip_path, &zip_buffer)?;
+    /// Gets a suffix that keeps temporary directory names of one process apart
+    fn unique_suffix() -> String {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        format!("{}-{}", nanos, COUNTER.fetch_add(1, Ordering::Relaxed))
+    }
+}
 
-        // Create hash directory
-        let hash_dir = bin_dir.join(&hash);
-        if hash_dir.exists() {
-            fs::remove_dir_all(&hash_dir)?;
-        }
-        fs::create_dir_all(&hash_dir)?;
+#[cfg(test)]
+mod tests {
+    use super::library_extractor::{classifier_platform, select_native_libraries};
+    use crate::craft_launcher::core::version::library_parser::library_parser::LibraryInfo;
+    use crate::craft_launcher::core::version::rules::rules::{Platform, RuleContext};
+    use serde_json::json;
 
-        // Extract ZIP to hash directory
-        let mut zip_archive = zip::ZipArchive::new(Cursor::new(zip_buffer))?;
-        for i in 0..zip_archive.len() {
-            let mut file = zip_archive.by_index(i)?;
-            let outpath = hash_dir.join(file.name());
+    fn context(os_name: &str, arch: &str) -> RuleContext {
+        RuleContext::new(Platform::new(os_name, arch))
+    }
 
-            let mut outfile = File::create(&outpath)?;
-            io::copy(&mut file, &mut outfile)?;
+    fn classifier_artifact(classifier: &str) -> serde_json::Value {
+        json!({
+            "path": format!("org/lwjgl/lwjgl/lwjgl-platform/2.9.4/lwjgl-platform-2.9.4-{}.jar", classifier),
+            "sha1": "0000000000000000000000000000000000000000",
+            "size": 1,
+            "url": format!("https://libraries.minecraft.net/{}.jar", classifier)
+        })
+    }
 
-            // Set file permissions (Unix only)
-            #[cfg(unix)]
-            {
-                use std::os::unix::fs::PermissionsExt;
-                if let Some(mode) = file.unix_mode() {
-                    fs::set_permissions(&outpath, fs::Permissions::from_mode(mode))?;
-                }
-            }
-        }
+    fn modern_native(classifier: &str, os_name: &str) -> LibraryInfo {
+        let path = format!("org/lwjgl/lwjgl/3.3.3/lwjgl-3.3.3-{}.jar", classifier);
+        LibraryInfo::Base(
+            serde_json::from_value(json!({
+                "name": format!("org.lwjgl:lwjgl:3.3.3:{}", classifier),
+                "downloads": {
+                    "artifact": {
+                        "path": path,
+                        "sha1": "0000000000000000000000000000000000000000",
+                        "size": 1,
+                        "url": format!("https://libraries.minecraft.net/{}", path)
+                    }
+                },
+                "rules": [{"action": "allow", "os": {"name": os_name}}]
+            }))
+            .unwrap(),
+        )
+    }
 
-        Ok(hash_dir)
+    fn classifiers(libraries: &[LibraryInfo], context: &RuleContext) -> Vec<String> {
+        select_native_libraries(libraries, context)
+            .into_iter()
+            .map(|native| native.classifier)
+            .collect()
+    }
+
+    /// Tests `${arch}` substitution in the legacy natives map
+    #[test]
+    fn test_legacy_arch_placeholder() {
+        let library = LibraryInfo::Base(
+            serde_json::from_value(json!({
+                "name": "org.lwjgl.lwjgl:lwjgl-platform:2.9.4",
+                "natives": {
+                    "linux": "natives-linux",
+                    "windows": "natives-windows-${arch}"
+                },
+                "downloads": {
+                    "classifiers": {
+                        "natives-linux": classifier_artifact("natives-linux"),
+                        "natives-windows-32": classifier_artifact("natives-windows-32"),
+                        "natives-windows-64": classifier_artifact("natives-windows-64")
+                    }
+                },
+                "extract": {"exclude": ["META-INF/"]}
+            }))
+            .unwrap(),
+        );
+        let libraries = vec![library];
+
+        assert_eq!(
+            classifiers(&libraries, &context("windows", "x86")),
+            vec!["natives-windows-32"]
+        );
+        assert_eq!(
+            classifiers(&libraries, &context("windows", "x86_64")),
+            vec!["natives-windows-64"]
+        );
+        assert_eq!(
+            classifiers(&libraries, &context("linux", "x86_64")),
+            vec!["natives-linux"]
+        );
+        assert!(classifiers(&libraries, &context("osx", "arm64")).is_empty());
+
+        let native = &select_native_libraries(&libraries, &context("linux", "x86_64"))[0];
+        assert_eq!(
+            native.path,
+            "org/lwjgl/lwjgl/lwjgl-platform/2.9.4/lwjgl-platform-2.9.4-natives-linux.jar"
+        );
+        assert!(native.extract.is_some());
+    }
+
+    /// Tests rule-gated natives of 1.19+ with architecture specific variants
+    #[test]
+    fn test_modern_arch_variants() {
+        let libraries = vec![
+            LibraryInfo::Generic {
+                name: "org.lwjgl:lwjgl:3.3.3".to_string(),
+                path: None,
+                url: None,
+                sha1: None,
+                size: 0,
+            },
+            modern_native("natives-linux", "linux"),
+            modern_native("natives-macos", "osx"),
+            modern_native("natives-macos-arm64", "osx"),
+            modern_native("natives-windows", "windows"),
+            modern_native("natives-windows-x86", "windows"),
+            modern_native("natives-windows-arm64", "windows"),
+        ];
+
+        assert_eq!(
+            classifiers(&libraries, &context("linux", "x86_64")),
+            vec!["natives-linux"]
+        );
+        assert_eq!(
+            classifiers(&libraries, &context("osx", "arm64")),
+            vec!["natives-macos-arm64"]
+        );
+        assert_eq!(
+            classifiers(&libraries, &context("osx", "x86_64")),
+            vec!["natives-macos"]
+        );
+        assert_eq!(
+            classifiers(&libraries, &context("windows", "x86")),
+            vec!["natives-windows-x86"]
+        );
+        assert_eq!(
+            classifiers(&libraries, &context("windows", "arm64")),
+            vec!["natives-windows-arm64"]
+        );
+        // No arm64 build for Linux in this list, so the generic one is used
+        assert_eq!(
+            classifiers(&libraries, &context("linux", "arm64")),
+            vec!["natives-linux"]
+        );
+    }
+
+    /// Tests splitting classifiers into OS and architecture
+    #[test]
+    fn test_classifier_platform() {
+        assert_eq!(classifier_platform("natives-linux"), (Some("linux"), None));
+        assert_eq!(
+            classifier_platform("natives-macos-arm64"),
+            (Some("osx"), Some("arm64"))
+        );
+        assert_eq!(
+            classifier_platform("natives-windows-x86"),
+            (Some("windows"), Some("x86"))
+        );
     }
 }
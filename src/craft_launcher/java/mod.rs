@@ -6,3 +6,12 @@ pub mod arguments_merger;
 
 // Extract native library files
 pub mod library_extractor;
+
+// Java runtime discovery on the host
+pub mod java_discovery;
+
+// Install Java runtimes from Mojang's runtime manifest
+pub mod runtime_installer;
+
+// Pick the Java runtime a version needs
+pub mod java_selector;
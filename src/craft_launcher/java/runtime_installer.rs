@@ -0,0 +1,592 @@
+pub mod runtime_installer {
+    use crate::craft_launcher::core::version::rules::rules::Platform;
+    use crate::craft_launcher::utils::file_operations::file_utils::{self, sha1_hex};
+    use crate::craft_launcher::utils::networking::networking::{self, DownloadError};
+    use serde::{Deserialize, Serialize};
+    use std::collections::{BTreeMap, HashMap};
+    use std::error::Error;
+    use std::fmt;
+    use std::fs;
+    use std::io;
+    use std::path::{Component, Path, PathBuf};
+
+    /// URL of Mojang's Java runtime index
+    pub const JAVA_RUNTIME_INDEX_URL: &str = "https://launchermeta.mojang.com/v1/products/java-runtime/2ec0cc96c44e5a76b9c8b7c39df7210883d12871/all.json";
+
+    /// Name of the file that records the installed runtime version
+    const VERSION_FILE: &str = ".version";
+
+    /// The runtime index: platform -> component -> entries
+    pub type RuntimeIndex = HashMap<String, HashMap<String, Vec<RuntimeIndexEntry>>>;
+
+    /// A runtime available for a platform and component
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct RuntimeIndexEntry {
+        /// Download information of the component manifest
+        pub manifest: RuntimeDownload,
+
+        /// Version information of the runtime
+        pub version: RuntimeVersionInfo,
+    }
+
+    /// Version information of a runtime
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct RuntimeVersionInfo {
+        /// Version name (e.g. "17.0.8")
+        pub name: String,
+
+        /// Release time
+        pub released: String,
+    }
+
+    /// A downloadable file with hash and size
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct RuntimeDownload {
+        /// The SHA-1 hash of the file
+        pub sha1: String,
+
+        /// The size of the file in bytes
+        pub size: i64,
+
+        /// The URL to download the file
+        pub url: String,
+    }
+
+    /// The manifest of a runtime component
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct RuntimeManifest {
+        /// Files keyed by their path relative to the runtime directory
+        pub files: BTreeMap<String, RuntimeFile>,
+    }
+
+    /// An entry of a runtime manifest
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(tag = "type", rename_all = "lowercase")]
+    pub enum RuntimeFile {
+        /// A regular file
+        File {
+            /// Whether the file must be executable
+            #[serde(default)]
+            executable: bool,
+
+            /// Download variants of the file
+            downloads: RuntimeFileDownloads,
+        },
+
+        /// A directory
+        Directory,
+
+        /// A symbolic link
+        Link {
+            /// The link target, relative to the link
+            target: String,
+        },
+    }
+
+    /// Download variants of a runtime file
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct RuntimeFileDownloads {
+        /// The uncompressed file
+        pub raw: RuntimeDownload,
+
+        /// The LZMA compressed file
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub lzma: Option<RuntimeDownload>,
+    }
+
+    /// Error type for Java runtime installation
+    #[derive(Debug)]
+    pub enum RuntimeInstallError {
+        /// File system error
+        IoError(io::Error),
+
+        /// A download failed
+        NetworkingError(String),
+
+        /// The index or a manifest could not be parsed
+        ParseError(String),
+
+        /// The index has no runtime for the platform and component
+        ComponentNotFound {
+            /// The runtime platform key (e.g. "linux")
+            platform: String,
+
+            /// The component (e.g. "java-runtime-gamma")
+            component: String,
+        },
+
+        /// The current platform has no Mojang runtimes
+        UnsupportedPlatform(String),
+
+        /// A downloaded file does not match its expected hash
+        HashMismatch {
+            /// The file or URL that failed
+            path: String,
+
+            /// The expected SHA-1 hash
+            expected: String,
+
+            /// The actual SHA-1 hash
+            actual: String,
+        },
+
+        /// An LZMA download could not be decompressed
+        DecompressionError(String),
+
+        /// A manifest path points outside the runtime directory
+        InvalidPath(String),
+    }
+
+    impl fmt::Display for RuntimeInstallError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                RuntimeInstallError::IoError(e) => write!(f, "IO error: {}", e),
+                RuntimeInstallError::NetworkingError(e) => write!(f, "Download failed: {}", e),
+                RuntimeInstallError::ParseError(e) => write!(f, "Invalid runtime metadata: {}", e),
+                RuntimeInstallError::ComponentNotFound {
+                    platform,
+                    component,
+                } => write!(
+                    f,
+                    "No Java runtime {} available for platform {}",
+                    component, platform
+                ),
+                RuntimeInstallError::UnsupportedPlatform(platform) => {
+                    write!(f, "No Java runtimes are published for {}", platform)
+                }
+                RuntimeInstallError::HashMismatch {
+                    path,
+                    expected,
+                    actual,
+                } => write!(
+                    f,
+                    "Hash mismatch for {}: expected {}, got {}",
+                    path, expected, actual
+                ),
+                RuntimeInstallError::DecompressionError(e) => {
+                    write!(f, "Failed to decompress runtime file: {}", e)
+                }
+                RuntimeInstallError::InvalidPath(path) => {
+                    write!(f, "Invalid path in runtime manifest: {}", path)
+                }
+            }
+        }
+    }
+
+    impl Error for RuntimeInstallError {}
+
+    impl From<DownloadError> for RuntimeInstallError {
+        fn from(error: DownloadError) -> Self {
+            match error {
+                DownloadError::NetworkingError { url, message } => {
+                    RuntimeInstallError::NetworkingError(format!("{}: {}", url, message))
+                }
+                DownloadError::HashMismatch {
+                    url,
+                    expected,
+                    actual,
+                } => RuntimeInstallError::HashMismatch {
+                    path: url,
+                    expected,
+                    actual,
+                },
+                DownloadError::IoError(e) => RuntimeInstallError::IoError(e),
+            }
+        }
+    }
+
+    impl From<io::Error> for RuntimeInstallError {
+        fn from(error: io::Error) -> Self {
+            RuntimeInstallError::IoError(error)
+        }
+    }
+
+    /// A Java runtime installed by `JavaRuntimeInstaller`
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct InstalledRuntime {
+        /// The component (e.g. "java-runtime-gamma")
+        pub component: String,
+
+        /// The runtime version name
+        pub version: String,
+
+        /// The runtime directory (`runtime/<component>`)
+        pub directory: PathBuf,
+
+        /// Path to the java executable
+        pub executable: PathBuf,
+    }
+
+    /// Installs Mojang's Java runtimes under `root_dir/runtime/<component>`
+    ///
+    /// The platform index lists a manifest per platform and component. The manifest
+    /// lists every file, directory and link of the runtime. Files that already exist
+    /// with the expected hash are kept, so installing again only repairs what changed.
+    #[derive(Debug, Clone)]
+    pub struct JavaRuntimeInstaller {
+        runtime_dir: PathBuf,
+        index_url: String,
+        platform: Option<String>,
+    }
+
+    impl JavaRuntimeInstaller {
+        /// Creates an installer for the current platform using Mojang's index
+        ///
+        /// # Arguments
+        ///
+        /// * `root_dir` - Path to the root directory where game data is stored
+        pub fn new(root_dir: &Path) -> Self {
+            JavaRuntimeInstaller {
+                runtime_dir: root_dir.join("runtime"),
+                index_url: JAVA_RUNTIME_INDEX_URL.to_string(),
+                platform: runtime_platform(&Platform::current()).map(str::to_string),
+            }
+        }
+
+        /// Sets the URL of the runtime index.
+        ///
+        /// # Arguments
+        ///
+        /// * `index_url` - The URL of an index in the format of Mojang's `all.json`
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_index_url(mut self, index_url: &str) -> Self {
+            self.index_url = index_url.to_string();
+            self
+        }
+
+        /// Sets the runtime platform key.
+        ///
+        /// # Arguments
+        ///
+        /// * `platform` - A platform key of the index (e.g. "linux" or "mac-os-arm64")
+        ///
+        /// # Returns
+        ///
+        /// * Self for method chaining
+        pub fn with_platform(mut self, platform: &str) -> Self {
+            self.platform = Some(platform.to_string());
+            self
+        }
+
+        /// Gets the directory a component is installed into
+        pub fn component_dir(&self, component: &str) -> PathBuf {
+            self.runtime_dir.join(component)
+        }
+
+        /// Downloads and parses the runtime index
+        pub fn fetch_index(&self) -> Result<RuntimeIndex, RuntimeInstallError> {
+            let bytes = networking::fetch_bytes(&self.index_url)
+                .map_err(|e| RuntimeInstallError::NetworkingError(e.to_string()))?;
+            serde_json::from_slice(&bytes)
+                .map_err(|e| RuntimeInstallError::ParseError(e.to_string()))
+        }
+
+        /// Installs a runtime component
+        ///
+        /// # Arguments
+        ///
+        /// * `component` - The component from the version's `javaVersion` (e.g. "java-runtime-gamma")
+        ///
+        /// # Returns
+        ///
+        /// * `Result<InstalledRuntime, RuntimeInstallError>` - The installed runtime or an error
+        pub fn install(&self, component: &str) -> Result<InstalledRuntime, RuntimeInstallError> {
+            let platform = self.platform.clone().ok_or_else(|| {
+                RuntimeInstallError::UnsupportedPlatform(format!(
+                    "{} {}",
+                    std::env::consts::OS,
+                    std::env::consts::ARCH
+                ))
+            })?;
+
+            let index = self.fetch_index()?;
+            let entry = index
+                .get(&platform)
+                .and_then(|components| components.get(component))
+                .and_then(|entries| entries.first())
+                .ok_or_else(|| RuntimeInstallError::ComponentNotFound {
+                    platform: platform.clone(),
+                    component: component.to_string(),
+                })?;
+
+            let manifest_bytes = download_verified(&entry.manifest)?;
+            let manifest: RuntimeManifest = serde_json::from_slice(&manifest_bytes)
+                .map_err(|e| RuntimeInstallError::ParseError(e.to_string()))?;
+
+            let directory = self.component_dir(component);
+            self.install_manifest(&manifest, &directory)?;
+            fs::write(directory.join(VERSION_FILE), &entry.version.name)?;
+
+            Ok(InstalledRuntime {
+                component: component.to_string(),
+                version: entry.version.name.clone(),
+                executable: runtime_executable(&directory),
+                directory,
+            })
+        }
+
+        /// Creates the directories, files and links of a manifest
+        fn install_manifest(
+            &self,
+            manifest: &RuntimeManifest,
+            directory: &Path,
+        ) -> Result<(), RuntimeInstallError> {
+            fs::create_dir_all(directory)?;
+
+            // Directories first, then files, then links whose targets now exist
+            for (path, file) in &manifest.files {
+                if let RuntimeFile::Directory = file {
+                    fs::create_dir_all(safe_join(directory, path)?)?;
+                }
+            }
+
+            for (path, file) in &manifest.files {
+                if let RuntimeFile::File {
+                    executable,
+                    downloads,
+                } = file
+                {
+                    let target = safe_join(directory, path)?;
+                    install_file(&target, downloads)?;
+                    if *executable {
+                        set_executable(&target)?;
+                    }
+                }
+            }
+
+            for (path, file) in &manifest.files {
+                if let RuntimeFile::Link { target } = file {
+                    check_link_target(path, target)?;
+                    create_link(&safe_join(directory, path)?, target)?;
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Gets the index platform key for a platform
+    ///
+    /// # Arguments
+    ///
+    /// * `platform` - The platform to map
+    ///
+    /// # Returns
+    ///
+    /// * The platform key (e.g. "windows-x64"), or None if Mojang publishes no runtimes for it
+    pub fn runtime_platform(platform: &Platform) -> Option<&'static str> {
+        match (platform.os_name.as_str(), platform.arch.as_str()) {
+            ("linux", "x86_64") => Some("linux"),
+            ("linux", "x86") => Some("linux-i386"),
+            ("osx", "x86_64") => Some("mac-os"),
+            ("osx", "arm64") => Some("mac-os-arm64"),
+            ("windows", "x86_64") => Some("windows-x64"),
+            ("windows", "x86") => Some("windows-x86"),
+            ("windows", "arm64") => Some("windows-arm64"),
+            _ => None,
+        }
+    }
+
+    /// Gets the java executable of an installed runtime directory
+    ///
+    /// macOS runtimes keep their home inside `jre.bundle/Contents/Home`.
+    pub fn runtime_executable(directory: &Path) -> PathBuf {
+        let executable = if cfg!(windows) { "java.exe" } else { "java" };
+        let bundle = directory
+            .join("jre.bundle")
+            .join("Contents")
+            .join("Home")
+            .join("bin")
+            .join(executable);
+        if bundle.exists() {
+            bundle
+        } else {
+            directory.join("bin").join(executable)
+        }
+    }
+
+    /// Gets the installed version name of a runtime directory, if any
+    pub fn installed_version(directory: &Path) -> Option<String> {
+        fs::read_to_string(directory.join(VERSION_FILE))
+            .ok()
+            .map(|version| version.trim().to_string())
+    }
+
+    /// Downloads a file, preferring the LZMA variant, unless it already exists with the right hash
+    fn install_file(
+        target: &Path,
+        downloads: &RuntimeFileDownloads,
+    ) -> Result<(), RuntimeInstallError> {
+        if target.is_file() && sha1_hex(&fs::read(target)?) == downloads.raw.sha1 {
+            return Ok(());
+        }
+
+        let bytes = match &downloads.lzma {
+            Some(lzma) => {
+                let compressed = download_verified(lzma)?;
+                let mut bytes = Vec::new();
+                lzma_rs::lzma_decompress(&mut io::Cursor::new(compressed), &mut bytes)
+                    .map_err(|e| RuntimeInstallError::DecompressionError(e.to_string()))?;
+                let actual = sha1_hex(&bytes);
+                if actual != downloads.raw.sha1 {
+                    return Err(RuntimeInstallError::HashMismatch {
+                        path: target.display().to_string(),
+                        expected: downloads.raw.sha1.clone(),
+                        actual,
+                    });
+                }
+                bytes
+            }
+            None => download_verified(&downloads.raw)?,
+        };
+
+        // Replace through a temporary file so a running runtime never sees a partial file
+        file_utils::write_atomically(target, &bytes)?;
+        Ok(())
+    }
+
+    /// Downloads a file and checks its SHA-1 hash
+    fn download_verified(download: &RuntimeDownload) -> Result<Vec<u8>, RuntimeInstallError> {
+        Ok(networking::fetch_verified(&download.url, &download.sha1)?)
+    }
+
+    /// Joins a manifest path to the runtime directory, rejecting paths that escape it
+    fn safe_join(directory: &Path, path: &str) -> Result<PathBuf, RuntimeInstallError> {
+        let relative = Path::new(path);
+        let is_safe = relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+        if !is_safe || path.is_empty() {
+            return Err(RuntimeInstallError::InvalidPath(path.to_string()));
+        }
+        Ok(directory.join(relative))
+    }
+
+    /// Checks that a link target stays inside the runtime directory
+    ///
+    /// The target must be relative and, resolved from the directory holding the link,
+    /// must not climb above the runtime directory.
+    pub(crate) fn check_link_target(path: &str, target: &str) -> Result<(), RuntimeInstallError> {
+        let invalid = || RuntimeInstallError::InvalidPath(format!("{} -> {}", path, target));
+        let mut depth = Path::new(path).components().count().saturating_sub(1);
+        for component in Path::new(target).components() {
+            match component {
+                Component::Normal(_) => depth += 1,
+                Component::CurDir => {}
+                Component::ParentDir => depth = depth.checked_sub(1).ok_or_else(invalid)?,
+                Component::RootDir | Component::Prefix(_) => return Err(invalid()),
+            }
+        }
+        if target.is_empty() {
+            return Err(invalid());
+        }
+        Ok(())
+    }
+
+    /// Marks a file as executable (Unix only)
+    fn set_executable(path: &Path) -> io::Result<()> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+        }
+        #[cfg(not(unix))]
+        let _ = path;
+        Ok(())
+    }
+
+    /// Creates a symbolic link, replacing an existing one (Unix only)
+    ///
+    /// Windows runtimes do not contain links, so they are skipped there.
+    fn create_link(path: &Path, target: &str) -> io::Result<()> {
+        #[cfg(unix)]
+        {
+            if let Ok(existing) = fs::read_link(path) {
+                if existing == Path::new(target) {
+                    return Ok(());
+                }
+                fs::remove_file(path)?;
+            }
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            std::os::unix::fs::symlink(target, path)?;
+        }
+        #[cfg(not(unix))]
+        let _ = (path, target);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::runtime_installer::{
+        RuntimeFile, RuntimeInstallError, RuntimeManifest, check_link_target, runtime_platform,
+    };
+    use crate::craft_launcher::core::version::rules::rules::Platform;
+
+    /// Tests mapping platforms to index keys
+    #[test]
+    fn test_runtime_platform() {
+        assert_eq!(
+            runtime_platform(&Platform::new("linux", "x86_64")),
+            Some("linux")
+        );
+        assert_eq!(
+            runtime_platform(&Platform::new("osx", "arm64")),
+            Some("mac-os-arm64")
+        );
+        assert_eq!(
+            runtime_platform(&Platform::new("windows", "x86")),
+            Some("windows-x86")
+        );
+        assert_eq!(runtime_platform(&Platform::new("linux", "arm64")), None);
+    }
+
+    /// Tests that link targets may not leave the runtime directory
+    #[test]
+    fn test_check_link_target() {
+        assert!(check_link_target("lib/libjli.so", "../bin/java").is_ok());
+        assert!(check_link_target("bin/java", "./java-real").is_ok());
+        assert!(check_link_target("legal/java.base/LICENSE", "../../conf/LICENSE").is_ok());
+        assert!(matches!(
+            check_link_target("lib/libjli.so", "../../bin/sh"),
+            Err(RuntimeInstallError::InvalidPath(_))
+        ));
+        assert!(check_link_target("java", "../java").is_err());
+        assert!(check_link_target("bin/java", "/usr/bin/java").is_err());
+        assert!(check_link_target("bin/java", "").is_err());
+    }
+
+    /// Tests parsing the three kinds of manifest entries
+    #[test]
+    fn test_parse_manifest() {
+        let manifest: RuntimeManifest = serde_json::from_str(
+            r#"{"files": {
+                "bin": {"type": "directory"},
+                "bin/java": {"type": "file", "executable": true, "downloads": {
+                    "raw": {"sha1": "a", "size": 1, "url": "http://localhost/raw"},
+                    "lzma": {"sha1": "b", "size": 1, "url": "http://localhost/lzma"}
+                }},
+                "lib/libjli.so": {"type": "link", "target": "../bin/java"}
+            }}"#,
+        )
+        .unwrap();
+
+        assert!(matches!(manifest.files["bin"], RuntimeFile::Directory));
+        assert!(matches!(
+            manifest.files["bin/java"],
+            RuntimeFile::File {
+                executable: true,
+                ..
+            }
+        ));
+        assert!(matches!(
+            &manifest.files["lib/libjli.so"],
+            RuntimeFile::Link { target } if target == "../bin/java"
+        ));
+    }
+}
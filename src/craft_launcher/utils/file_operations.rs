@@ -11,7 +11,8 @@ pub mod file_utils {
     use std::{
         fs::{self},
         io::{Read, Write},
-        path::PathBuf,
+        path::{Path, PathBuf},
+        sync::atomic::{AtomicU64, Ordering},
     };
 
     /// This module use crate::craft_launcher::utils::directory::directory for directory operations.
@@ -108,6 +109,66 @@ pub mod file_utils {
         Ok(metadata.len())
     }
 
+    /**
+     * Calculate the SHA-1 hash of a file.
+     * path: The path to the file.
+     * Returns Ok(hash) with the lowercase hex encoded hash if successful, Err(e) otherwise.
+     */
+    pub fn calculate_sha1(path: &PathBuf) -> std::io::Result<String> {
+        use sha1::{Digest, Sha1};
+
+        let mut file = fs::File::open(path)?;
+        let mut hasher = Sha1::new();
+        std::io::copy(&mut file, &mut hasher)?;
+        Ok(format!("{:x}", hasher.finalize()))
+    }
+
+    /**
+     * Calculate the SHA-1 hash of a byte buffer.
+     * bytes: The data to hash.
+     * Returns the lowercase hex encoded hash.
+     */
+    pub fn sha1_hex(bytes: &[u8]) -> String {
+        use sha1::{Digest, Sha1};
+
+        format!("{:x}", Sha1::digest(bytes))
+    }
+
+    /**
+     * Write binary data to a file atomically.
+     * path: The path to the file.
+     * data: The data to write.
+     * The data is written to a temporary file next to the destination, flushed to disk
+     * and renamed over it, so the file is never seen half written.
+     * Parent directories are created as needed.
+     * Returns Ok(()) if the write was successful, Err(e) otherwise.
+     */
+    pub fn write_atomically(path: &Path, data: &[u8]) -> std::io::Result<()> {
+        static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+        temp_name.push(format!(
+            ".{}-{}.part",
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let temp_path = path.with_file_name(temp_name);
+
+        let result = (|| {
+            let mut file = fs::File::create(&temp_path)?;
+            file.write_all(data)?;
+            file.sync_all()?;
+            fs::rename(&temp_path, path)
+        })();
+        if result.is_err() {
+            let _ = fs::remove_file(&temp_path);
+        }
+        result
+    }
+
     /**
      * Get the size of a file in bytes.
      * path: The path to the file.
@@ -393,4 +454,23 @@ mod tests {
         // Clean up the temporary directory
         fs::remove_dir_all(&temp_dir).expect("Failed to clean up temporary directory.");
     }
+
+    #[test]
+    /// Tests replacing a file atomically and hashing bytes
+    fn test_write_atomically() {
+        use super::file_utils::{sha1_hex, write_atomically};
+        use std::fs;
+
+        let temp_dir = std::env::temp_dir().join("file_operations_atomic_test");
+        let _ = fs::remove_dir_all(&temp_dir);
+        let path = temp_dir.join("nested").join("file.bin");
+
+        write_atomically(&path, b"first").expect("Failed to write file.");
+        write_atomically(&path, b"second").expect("Failed to replace file.");
+        assert_eq!(fs::read(&path).unwrap(), b"second");
+        assert_eq!(fs::read_dir(path.parent().unwrap()).unwrap().count(), 1);
+        assert_eq!(sha1_hex(b"second"), "352f7829a2384b001cc12b0c2613c756454a1f6a");
+
+        fs::remove_dir_all(&temp_dir).expect("Failed to clean up temporary directory.");
+    }
 }
@@ -5,7 +5,7 @@
  */
 pub mod networking {
     /// This module uses std::fs and std::path::PathBuf for file system operations.
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
 
     /// This module use crate::craft_launcher::utils::file_utils::file_utils for file operations.
     use crate::craft_launcher::utils::file_operations::file_utils;
@@ -37,6 +37,108 @@ pub mod networking {
         Ok(())
     }
 
+    /**
+     * Fetches the raw bytes of a URL.
+     * url: The URL to fetch.
+     * Returns the response body as bytes.
+     * Unlike download_file, a non-success HTTP status (e.g. 404) is returned as an error
+     * instead of being written out as file content.
+     */
+    pub fn fetch_bytes(url: &str) -> Result<Vec<u8>, reqwest::Error> {
+        let response = reqwest::blocking::get(url)?.error_for_status()?;
+        let bytes = response.bytes()?;
+        Ok(bytes.to_vec())
+    }
+
+    /**
+     * Error type for downloads checked against a SHA-1 hash.
+     */
+    #[derive(Debug)]
+    pub enum DownloadError {
+        /// The request failed or the server answered with an error status
+        NetworkingError { url: String, message: String },
+
+        /// The downloaded content does not match the expected hash
+        HashMismatch {
+            url: String,
+            expected: String,
+            actual: String,
+        },
+
+        /// The file could not be read or written
+        IoError(std::io::Error),
+    }
+
+    impl std::fmt::Display for DownloadError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                DownloadError::NetworkingError { url, message } => {
+                    write!(f, "Failed to download {}: {}", url, message)
+                }
+                DownloadError::HashMismatch {
+                    url,
+                    expected,
+                    actual,
+                } => write!(
+                    f,
+                    "SHA-1 mismatch for {}: expected {}, got {}",
+                    url, expected, actual
+                ),
+                DownloadError::IoError(e) => write!(f, "Download IO error: {}", e),
+            }
+        }
+    }
+
+    impl std::error::Error for DownloadError {}
+
+    impl From<std::io::Error> for DownloadError {
+        fn from(error: std::io::Error) -> Self {
+            DownloadError::IoError(error)
+        }
+    }
+
+    /**
+     * Fetches the raw bytes of a URL and checks their SHA-1 hash.
+     * url: The URL to fetch.
+     * sha1: The expected lowercase or uppercase hex encoded SHA-1 hash.
+     * Returns the response body if it matches the hash.
+     */
+    pub fn fetch_verified(url: &str, sha1: &str) -> Result<Vec<u8>, DownloadError> {
+        let bytes = fetch_bytes(url).map_err(|e| DownloadError::NetworkingError {
+            url: url.to_string(),
+            message: e.to_string(),
+        })?;
+        let actual = file_utils::sha1_hex(&bytes);
+        if !actual.eq_ignore_ascii_case(sha1) {
+            return Err(DownloadError::HashMismatch {
+                url: url.to_string(),
+                expected: sha1.to_string(),
+                actual,
+            });
+        }
+        Ok(bytes)
+    }
+
+    /**
+     * Downloads a file unless it already exists with the expected SHA-1 hash.
+     * path: The destination path.
+     * url: The URL to download from.
+     * sha1: The expected hex encoded SHA-1 hash.
+     * The download is verified before it atomically replaces the destination, so a
+     * failed or corrupt download never leaves a file behind.
+     * Returns Ok(true) if the file was downloaded, Ok(false) if it was already present.
+     */
+    pub fn ensure_file(path: &Path, url: &str, sha1: &str) -> Result<bool, DownloadError> {
+        if path.is_file()
+            && file_utils::calculate_sha1(&path.to_path_buf())?.eq_ignore_ascii_case(sha1)
+        {
+            return Ok(false);
+        }
+        let bytes = fetch_verified(url, sha1)?;
+        file_utils::write_atomically(path, &bytes)?;
+        Ok(true)
+    }
+
     /**
      * C language version of read_file_from_url.
      * Reads a file from a URL and returns its content as a string.
@@ -0,0 +1,223 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::temp_dir;
+    use craft_launcher_rust::craft_launcher::core::account::account_store::account_store::{
+        AccountKind, AccountStore, AccountStoreError, StoreKey, StoredAccount,
+        account_store_add_offline_c, account_store_export_account_c, account_store_free_c,
+        account_store_list_c, account_store_open_c, account_store_save_c,
+        account_store_string_free_c, generate_key_file,
+    };
+    use craft_launcher_rust::craft_launcher::core::account::microsoft_account::microsoft_account::MicrosoftAccount;
+    use craft_launcher_rust::craft_launcher::core::account::offline_account::offline_account::OfflineAccount;
+    use craft_launcher_rust::craft_launcher::utils::file_operations::file_utils;
+    use std::ffi::{CStr, CString};
+
+    fn microsoft() -> MicrosoftAccount {
+        MicrosoftAccount {
+            username: "Notch".to_string(),
+            uuid: "069a79f444e94726a5befca90e38aaf5".to_string(),
+            access_token: "secret-access-token".to_string(),
+            expires_at: 0,
+            refresh_token: Some("secret-refresh-token".to_string()),
+            xuid: None,
+            client_id: "client-1".to_string(),
+        }
+    }
+
+    fn contains(haystack: &[u8], needle: &str) -> bool {
+        haystack
+            .windows(needle.len())
+            .any(|window| window == needle.as_bytes())
+    }
+
+    /// Tests saving, reopening and the default account with a passphrase
+    #[test]
+    fn test_passphrase_store() {
+        let dir = temp_dir("account_store_passphrase");
+        let path = dir.join("accounts.dat");
+        let key = StoreKey::Passphrase("correct horse".to_string());
+
+        let mut store = AccountStore::open(&path, &key).unwrap();
+        assert!(store.accounts().is_empty());
+        store.upsert(StoredAccount::Microsoft(microsoft()));
+        store.upsert(StoredAccount::Offline(
+            OfflineAccount::new("Steve").unwrap(),
+        ));
+        store
+            .set_default("5627dd98-e6be-3c21-b8a8-e92344183641")
+            .unwrap();
+        assert!(matches!(
+            store.set_default("unknown"),
+            Err(AccountStoreError::NotFound(_))
+        ));
+        store.save().unwrap();
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert!(!contains(&bytes, "secret-refresh-token"));
+        assert!(!contains(&bytes, "Notch"));
+
+        let mut store = AccountStore::open(&path, &key).unwrap();
+        assert_eq!(store.accounts().len(), 2);
+        let debug = format!("{:?} {:?}", store, key);
+        assert!(debug.contains("accounts.dat") && debug.contains("accounts: 2"));
+        assert!(!debug.contains("secret-access-token"));
+        assert!(!debug.contains("secret-refresh-token"));
+        assert!(!debug.contains("correct horse"));
+        assert_eq!(store.default_account().unwrap().username(), "Steve");
+        let summaries = store.summaries();
+        assert_eq!(summaries[0].kind, AccountKind::Microsoft);
+        assert!(!summaries[0].is_default && summaries[1].is_default);
+
+        let mut updated = microsoft();
+        updated.access_token = "new-token".to_string();
+        store.upsert(StoredAccount::Microsoft(updated));
+        assert_eq!(store.accounts().len(), 2);
+        assert!(store.remove("5627DD98E6BE3C21B8A8E92344183641").is_some());
+        assert_eq!(store.default_account().unwrap().username(), "Notch");
+
+        assert!(matches!(
+            AccountStore::open(&path, &StoreKey::Passphrase("wrong".to_string())),
+            Err(AccountStoreError::WrongKey)
+        ));
+        let mut tampered = bytes.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        std::fs::write(&path, tampered).unwrap();
+        assert!(matches!(
+            AccountStore::open(&path, &key),
+            Err(AccountStoreError::WrongKey)
+        ));
+    }
+
+    /// Tests key files and switching a store to another key
+    #[test]
+    fn test_key_file_store() {
+        let dir = temp_dir("account_store_key_file");
+        let path = dir.join("accounts.dat");
+        let key_path = dir.join("keys/store.key");
+        generate_key_file(&key_path).unwrap();
+        assert!(generate_key_file(&key_path).is_err());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&key_path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let key = StoreKey::KeyFile(key_path.clone());
+        let mut store = AccountStore::open(&path, &key).unwrap();
+        store.upsert(StoredAccount::Offline(OfflineAccount::new("Alex").unwrap()));
+        store.save().unwrap();
+        assert_eq!(AccountStore::open(&path, &key).unwrap().accounts().len(), 1);
+        assert!(matches!(
+            AccountStore::open(&path, &StoreKey::Passphrase("anything".to_string())),
+            Err(AccountStoreError::WrongKey)
+        ));
+
+        let short_key = dir.join("short.key");
+        std::fs::write(&short_key, "short").unwrap();
+        assert!(matches!(
+            AccountStore::open(&dir.join("other.dat"), &StoreKey::KeyFile(short_key)),
+            Err(AccountStoreError::WeakKeyFile(5))
+        ));
+
+        let passphrase = StoreKey::Passphrase("new passphrase".to_string());
+        let mut store = AccountStore::open(&path, &key).unwrap();
+        store.change_key(&passphrase).unwrap();
+        store.save().unwrap();
+        assert_eq!(
+            AccountStore::open(&path, &passphrase).unwrap().accounts()[0].username(),
+            "Alex"
+        );
+    }
+
+    /// Tests importing and migrating account lists written as plain JSON
+    #[test]
+    fn test_import_plain_json() {
+        let dir = temp_dir("account_store_import");
+        let plain = dir.join("accounts.json");
+        let accounts = vec![
+            StoredAccount::Microsoft(microsoft()),
+            StoredAccount::Offline(OfflineAccount::new("Steve").unwrap()),
+        ];
+        file_utils::write_struct_to_file_as_json(&plain, &accounts).unwrap();
+
+        let key = StoreKey::Passphrase("passphrase".to_string());
+        let mut store = AccountStore::open(&dir.join("accounts.dat"), &key).unwrap();
+        assert_eq!(store.import_plain_json(&plain).unwrap(), 2);
+        assert_eq!(store.default_account().unwrap().username(), "Notch");
+        assert_eq!(store.accounts(), accounts.as_slice());
+
+        std::fs::write(
+            &plain,
+            r#"{"schema_version": 3, "default": null, "accounts": []}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            store.import_plain_json(&plain),
+            Err(AccountStoreError::UnsupportedSchema(3))
+        ));
+    }
+
+    /// Tests that the C interface lists accounts without their tokens
+    #[test]
+    fn test_ffi_hides_secrets() {
+        let dir = temp_dir("account_store_ffi");
+        let path = dir.join("accounts.dat");
+        let key = StoreKey::Passphrase("passphrase".to_string());
+        let mut store = AccountStore::open(&path, &key).unwrap();
+        store.upsert(StoredAccount::Microsoft(microsoft()));
+        store.save().unwrap();
+
+        let c_path = CString::new(path.to_str().unwrap()).unwrap();
+        let c_passphrase = CString::new("passphrase").unwrap();
+        let handle = unsafe { account_store_open_c(c_path.as_ptr(), c_passphrase.as_ptr()) };
+        assert!(!handle.is_null());
+        let steve = CString::new("Steve").unwrap();
+        let invalid = CString::new("no spaces").unwrap();
+        assert_eq!(
+            unsafe { account_store_add_offline_c(handle, steve.as_ptr()) },
+            0
+        );
+        assert_eq!(
+            unsafe { account_store_add_offline_c(handle, invalid.as_ptr()) },
+            -1
+        );
+        assert_eq!(unsafe { account_store_save_c(handle) }, 0);
+
+        let mut data = std::ptr::null_mut();
+        let mut len = 0;
+        assert_eq!(
+            unsafe { account_store_list_c(handle, &mut data, &mut len) },
+            0
+        );
+        let list = unsafe { CStr::from_ptr(data) }
+            .to_str()
+            .unwrap()
+            .to_string();
+        unsafe { account_store_string_free_c(data) };
+        assert_eq!(list.len(), len);
+        assert!(list.contains("\"username\":\"Notch\""));
+        assert!(list.contains("\"username\":\"Steve\""));
+        assert!(!list.contains("secret"));
+
+        let id = CString::new("069a79f444e94726a5befca90e38aaf5").unwrap();
+        assert_eq!(
+            unsafe { account_store_export_account_c(handle, id.as_ptr(), &mut data, &mut len) },
+            0
+        );
+        let exported = unsafe { CStr::from_ptr(data) }
+            .to_str()
+            .unwrap()
+            .to_string();
+        unsafe { account_store_string_free_c(data) };
+        assert!(exported.contains("secret-refresh-token"));
+        unsafe { account_store_free_c(handle) };
+
+        let wrong = CString::new("wrong").unwrap();
+        assert!(unsafe { account_store_open_c(c_path.as_ptr(), wrong.as_ptr()) }.is_null());
+        assert_eq!(AccountStore::open(&path, &key).unwrap().accounts().len(), 2);
+    }
+}
@@ -0,0 +1,284 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{artifact_library, temp_dir, vanilla_version_json, write_version};
+    use craft_launcher_rust::craft_launcher::core::version::classpath::classpath::{
+        ClasspathOptions, build_classpath,
+    };
+    use craft_launcher_rust::craft_launcher::core::version::library_conflict::library_conflict::ConflictPolicy;
+    use craft_launcher_rust::craft_launcher::core::version::rules::rules::{Platform, RuleContext};
+    use craft_launcher_rust::craft_launcher::core::version::version_resolver::version_resolver::resolve_version;
+    use serde_json::json;
+    use std::path::{Path, PathBuf};
+
+    fn linux_options() -> ClasspathOptions {
+        ClasspathOptions {
+            context: RuleContext::new(Platform::new("linux", "x86_64")),
+            ..ClasspathOptions::default()
+        }
+    }
+
+    fn write_vanilla(root_dir: &Path) {
+        let mut osx_only = artifact_library(
+            "ca.weblit:java-objc-bridge:1.1",
+            "ca/weblit/java-objc-bridge/1.1/java-objc-bridge-1.1.jar",
+        );
+        osx_only["rules"] = json!([{"action": "allow", "os": {"name": "osx"}}]);
+
+        let libraries = json!([
+            artifact_library("org.ow2.asm:asm:9.3", "org/ow2/asm/asm/9.3/asm-9.3.jar"),
+            artifact_library(
+                "com.google.guava:guava:31.1-jre",
+                "com/google/guava/guava/31.1-jre/guava-31.1-jre.jar"
+            ),
+            osx_only,
+            {
+                "name": "org.lwjgl.lwjgl:lwjgl-platform:2.9.4",
+                "natives": {"linux": "natives-linux"},
+                "downloads": {
+                    "classifiers": {
+                        "natives-linux": {
+                            "path": "org/lwjgl/lwjgl/lwjgl-platform/2.9.4/lwjgl-platform-2.9.4-natives-linux.jar",
+                            "sha1": "0000000000000000000000000000000000000000",
+                            "size": 1,
+                            "url": "http://localhost/natives.jar"
+                        }
+                    }
+                }
+            },
+            artifact_library("org.ow2.asm:asm:9.3", "org/ow2/asm/asm/9.3/asm-9.3.jar")
+        ]);
+        write_version(
+            root_dir,
+            "1.20.1",
+            &vanilla_version_json("1.20.1", libraries, json!(["-cp", "${classpath}"])),
+        );
+    }
+
+    fn relative(root_dir: &Path, classpath: &[PathBuf]) -> Vec<String> {
+        classpath
+            .iter()
+            .map(|path| {
+                path.strip_prefix(root_dir)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect()
+    }
+
+    /// Tests rule filtering, native exclusion, deduplication and the client jar position
+    #[test]
+    fn test_vanilla_classpath() {
+        let root_dir = temp_dir("classpath_vanilla");
+        write_vanilla(&root_dir);
+
+        let version = resolve_version(&root_dir, "1.20.1", ConflictPolicy::ChildWins).unwrap();
+        let classpath = build_classpath(&version, &root_dir, &linux_options()).unwrap();
+
+        assert_eq!(
+            relative(&root_dir, &classpath),
+            vec![
+                "libraries/org/ow2/asm/asm/9.3/asm-9.3.jar",
+                "libraries/com/google/guava/guava/31.1-jre/guava-31.1-jre.jar",
+                "versions/1.20.1/1.20.1.jar",
+            ]
+        );
+    }
+
+    /// Tests that a child version overrides libraries and that the ignore list is honoured
+    #[test]
+    fn test_forge_classpath_with_ignore_list() {
+        let root_dir = temp_dir("classpath_forge");
+        write_vanilla(&root_dir);
+        write_version(
+            &root_dir,
+            "1.20.1-forge-47.3.0",
+            &json!({
+                "id": "1.20.1-forge-47.3.0",
+                "time": "2024-01-01T00:00:00+00:00",
+                "releaseTime": "2024-01-01T00:00:00+00:00",
+                "type": "release",
+                "inheritsFrom": "1.20.1",
+                "logging": {},
+                "mainClass": "cpw.mods.bootstraplauncher.BootstrapLauncher",
+                "arguments": {
+                    "game": ["--launchTarget", "forgeclient"],
+                    "jvm": ["-DignoreList=bootstraplauncher,asm,${version_name}.jar"]
+                },
+                "libraries": [
+                    artifact_library(
+                        "cpw.mods:bootstraplauncher:1.1.2",
+                        "cpw/mods/bootstraplauncher/1.1.2/bootstraplauncher-1.1.2.jar"
+                    ),
+                    artifact_library(
+                        "com.google.guava:guava:32.1.2-jre",
+                        "com/google/guava/guava/32.1.2-jre/guava-32.1.2-jre.jar"
+                    ),
+                    artifact_library(
+                        "net.minecraftforge:forge:1.20.1-47.3.0:universal",
+                        "net/minecraftforge/forge/1.20.1-47.3.0/forge-1.20.1-47.3.0-universal.jar"
+                    )
+                ]
+            }),
+        );
+
+        let version =
+            resolve_version(&root_dir, "1.20.1-forge-47.3.0", ConflictPolicy::ChildWins).unwrap();
+        assert_eq!(version.inheritance, vec!["1.20.1-forge-47.3.0", "1.20.1"]);
+        assert_eq!(version.jar, "1.20.1");
+        assert_eq!(version.dropped_libraries.len(), 2);
+
+        let classpath = build_classpath(&version, &root_dir, &linux_options()).unwrap();
+        assert_eq!(
+            relative(&root_dir, &classpath),
+            vec![
+                "libraries/com/google/guava/guava/32.1.2-jre/guava-32.1.2-jre.jar",
+                "libraries/net/minecraftforge/forge/1.20.1-47.3.0/forge-1.20.1-47.3.0-universal.jar",
+            ]
+        );
+
+        let options = ClasspathOptions {
+            respect_ignore_list: false,
+            ..linux_options()
+        };
+        let classpath = build_classpath(&version, &root_dir, &options).unwrap();
+        assert_eq!(classpath.len(), 5);
+        assert!(
+            classpath
+                .last()
+                .unwrap()
+                .ends_with("versions/1.20.1/1.20.1.jar")
+        );
+    }
+
+    /// Tests that only the natives variant for the platform architecture is kept
+    #[test]
+    fn test_native_variants() {
+        let root_dir = temp_dir("classpath_native_variants");
+        let native = |classifier: &str, os: &str| {
+            let mut library = artifact_library(
+                &format!("org.lwjgl:lwjgl:3.3.1:{}", classifier),
+                &format!("org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1-{}.jar", classifier),
+            );
+            library["rules"] = json!([{"action": "allow", "os": {"name": os}}]);
+            library
+        };
+        let libraries = json!([
+            artifact_library(
+                "org.lwjgl:lwjgl:3.3.1",
+                "org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1.jar"
+            ),
+            native("natives-linux", "linux"),
+            native("natives-windows", "windows"),
+            native("natives-windows-x86", "windows"),
+            native("natives-windows-arm64", "windows"),
+        ]);
+        write_version(
+            &root_dir,
+            "1.20.1",
+            &vanilla_version_json("1.20.1", libraries, json!(["-cp", "${classpath}"])),
+        );
+        let version = resolve_version(&root_dir, "1.20.1", ConflictPolicy::ChildWins).unwrap();
+
+        let classpath_for = |arch: &str| {
+            let options = ClasspathOptions {
+                context: RuleContext::new(Platform::new("windows", arch)),
+                ..ClasspathOptions::default()
+            };
+            relative(
+                &root_dir,
+                &build_classpath(&version, &root_dir, &options).unwrap(),
+            )
+        };
+        assert_eq!(
+            classpath_for("x86_64"),
+            vec![
+                "libraries/org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1.jar",
+                "libraries/org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1-natives-windows.jar",
+                "versions/1.20.1/1.20.1.jar",
+            ]
+        );
+        assert_eq!(
+            classpath_for("arm64")[1],
+            "libraries/org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1-natives-windows-arm64.jar"
+        );
+        assert_eq!(classpath_for("x86").len(), 3);
+        assert_eq!(
+            relative(
+                &root_dir,
+                &build_classpath(&version, &root_dir, &linux_options()).unwrap()
+            )[1],
+            "libraries/org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1-natives-linux.jar"
+        );
+    }
+
+    /// Tests the `jar` override of a child version
+    #[test]
+    fn test_jar_override() {
+        let root_dir = temp_dir("classpath_jar_override");
+        write_vanilla(&root_dir);
+        write_version(
+            &root_dir,
+            "fabric-loader-0.16.14-1.20.1",
+            &json!({
+                "id": "fabric-loader-0.16.14-1.20.1",
+                "inheritsFrom": "1.20.1",
+                "jar": "1.20.1-patched",
+                "releaseTime": "2024-01-01T00:00:00+00:00",
+                "time": "2024-01-01T00:00:00+00:00",
+                "type": "release",
+                "mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient",
+                "arguments": {"game": [], "jvm": []},
+                "libraries": [
+                    {"name": "net.fabricmc:fabric-loader:0.16.14", "url": "https://maven.fabricmc.net/"}
+                ]
+            }),
+        );
+
+        let version = resolve_version(
+            &root_dir,
+            "fabric-loader-0.16.14-1.20.1",
+            ConflictPolicy::ChildWins,
+        )
+        .unwrap();
+        let classpath = build_classpath(&version, &root_dir, &linux_options()).unwrap();
+
+        assert_eq!(
+            relative(&root_dir, &classpath).first().unwrap(),
+            "libraries/net/fabricmc/fabric-loader/0.16.14/fabric-loader-0.16.14.jar"
+        );
+        assert_eq!(
+            relative(&root_dir, &classpath).last().unwrap(),
+            "versions/1.20.1-patched/1.20.1-patched.jar"
+        );
+    }
+
+    /// Tests that missing entries are reported when requested
+    #[test]
+    fn test_fail_on_missing() {
+        let root_dir = temp_dir("classpath_missing");
+        write_vanilla(&root_dir);
+
+        let version = resolve_version(&root_dir, "1.20.1", ConflictPolicy::ChildWins).unwrap();
+        let options = ClasspathOptions {
+            fail_on_missing: true,
+            ..linux_options()
+        };
+
+        let error = build_classpath(&version, &root_dir, &options).unwrap_err();
+        assert_eq!(error.missing.len(), 3);
+
+        for path in &error.missing {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"jar").unwrap();
+        }
+        assert_eq!(
+            build_classpath(&version, &root_dir, &options)
+                .unwrap()
+                .len(),
+            3
+        );
+    }
+}
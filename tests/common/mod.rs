@@ -0,0 +1,284 @@
+//! Shared helpers for integration tests.
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use craft_launcher_rust::craft_launcher::core::engine::engine::{Account, LaunchOptions};
+use craft_launcher_rust::craft_launcher::core::version::rules::rules::{Platform, RuleContext};
+use craft_launcher_rust::craft_launcher::java::java_discovery::java_discovery::{
+    JavaSource, inspect_java_home,
+};
+
+/// A canned HTTP response served by the MockServer
+#[derive(Clone)]
+pub struct MockResponse {
+    pub status: u16,
+    pub body: Vec<u8>,
+    pub content_type: String,
+}
+
+impl MockResponse {
+    /// Creates a 200 response with the given body
+    pub fn ok<B: Into<Vec<u8>>>(body: B) -> Self {
+        MockResponse {
+            status: 200,
+            body: body.into(),
+            content_type: "application/octet-stream".to_string(),
+        }
+    }
+
+    /// Creates a 200 response with a JSON body
+    pub fn json(body: &serde_json::Value) -> Self {
+        MockResponse {
+            status: 200,
+            body: body.to_string().into_bytes(),
+            content_type: "application/json".to_string(),
+        }
+    }
+
+    /// Creates a response with an arbitrary status and JSON body
+    pub fn status_json(status: u16, body: &serde_json::Value) -> Self {
+        MockResponse {
+            status,
+            body: body.to_string().into_bytes(),
+            content_type: "application/json".to_string(),
+        }
+    }
+}
+
+/// A request received by the MockServer
+#[derive(Clone, Debug)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub body: String,
+    pub headers: Vec<(String, String)>,
+}
+
+/// A minimal local HTTP server for tests.
+/// Responses are looked up by "METHOD /path" first, then by "/path". Unknown paths return 404.
+pub struct MockServer {
+    pub base_url: String,
+    routes: Arc<Mutex<HashMap<String, MockResponse>>>,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl MockServer {
+    /// Starts a server on a random local port
+    pub fn start() -> Self {
+        let server = tiny_http::Server::http("127.0.0.1:0").expect("Failed to start mock server");
+        let port = server.server_addr().to_ip().unwrap().port();
+        let routes: Arc<Mutex<HashMap<String, MockResponse>>> = Arc::default();
+        let requests: Arc<Mutex<Vec<RecordedRequest>>> = Arc::default();
+
+        let thread_routes = routes.clone();
+        let thread_requests = requests.clone();
+        thread::spawn(move || {
+            for mut request in server.incoming_requests() {
+                let method = request.method().to_string().to_uppercase();
+                let path = request.url().split('?').next().unwrap_or("").to_string();
+                let mut body = String::new();
+                let _ = request.as_reader().read_to_string(&mut body);
+                let headers = request
+                    .headers()
+                    .iter()
+                    .map(|h| (h.field.to_string().to_lowercase(), h.value.to_string()))
+                    .collect();
+                thread_requests.lock().unwrap().push(RecordedRequest {
+                    method: method.clone(),
+                    path: path.clone(),
+                    body,
+                    headers,
+                });
+
+                let response = {
+                    let routes = thread_routes.lock().unwrap();
+                    routes
+                        .get(&format!("{} {}", method, path))
+                        .or_else(|| routes.get(&path))
+                        .cloned()
+                };
+                let response = match response {
+                    Some(r) => tiny_http::Response::from_data(r.body)
+                        .with_status_code(r.status)
+                        .with_header(
+                            tiny_http::Header::from_bytes("Content-Type", r.content_type).unwrap(),
+                        ),
+                    None => {
+                        tiny_http::Response::from_data(b"Not Found".to_vec()).with_status_code(404)
+                    }
+                };
+                let _ = request.respond(response);
+            }
+        });
+
+        MockServer {
+            base_url: format!("http://127.0.0.1:{}", port),
+            routes,
+            requests,
+        }
+    }
+
+    /// Registers a response for a path (any method)
+    pub fn add(&self, path: &str, response: MockResponse) {
+        self.routes
+            .lock()
+            .unwrap()
+            .insert(path.to_string(), response);
+    }
+
+    /// Registers a response for a specific method and path
+    pub fn add_method(&self, method: &str, path: &str, response: MockResponse) {
+        self.routes
+            .lock()
+            .unwrap()
+            .insert(format!("{} {}", method.to_uppercase(), path), response);
+    }
+
+    /// Builds an absolute URL for a path on this server
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// Returns all requests received so far
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Returns the paths of all requests received so far
+    pub fn requested_paths(&self) -> Vec<String> {
+        self.requests().into_iter().map(|r| r.path).collect()
+    }
+}
+
+/// Creates an empty, unique temporary directory for a test
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir()
+        .join("craft_launcher_tests")
+        .join(format!("{}_{}", name, std::process::id()));
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).expect("Failed to clean temporary directory");
+    }
+    std::fs::create_dir_all(&dir).expect("Failed to create temporary directory");
+    dir
+}
+
+/// Calculates the SHA-1 hash of a byte buffer
+pub fn sha1_hex(bytes: &[u8]) -> String {
+    use sha1::{Digest, Sha1};
+    format!("{:x}", Sha1::digest(bytes))
+}
+
+/// Writes a version JSON to `root_dir/versions/<id>/<id>.json`
+pub fn write_version(root_dir: &std::path::Path, id: &str, json: &serde_json::Value) {
+    let dir = root_dir.join("versions").join(id);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join(format!("{}.json", id)),
+        serde_json::to_string_pretty(json).unwrap(),
+    )
+    .unwrap();
+}
+
+/// Builds a minimal modern vanilla version JSON with the given libraries and JVM arguments
+pub fn vanilla_version_json(
+    id: &str,
+    libraries: serde_json::Value,
+    jvm: serde_json::Value,
+) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "time": "2023-06-12T12:00:00+00:00",
+        "releaseTime": "2023-06-12T12:00:00+00:00",
+        "type": "release",
+        "mainClass": "net.minecraft.client.main.Main",
+        "minimumLauncherVersion": 21,
+        "assets": "5",
+        "assetIndex": {
+            "id": "5",
+            "sha1": "0000000000000000000000000000000000000000",
+            "size": 1,
+            "totalSize": 1,
+            "url": "http://localhost/assets/5.json"
+        },
+        "downloads": {
+            "client": {"sha1": "0000000000000000000000000000000000000000", "size": 1, "url": "http://localhost/client.jar"},
+            "server": {"sha1": "0000000000000000000000000000000000000000", "size": 1, "url": "http://localhost/server.jar"}
+        },
+        "javaVersion": {"component": "java-runtime-gamma", "majorVersion": 17},
+        "arguments": {
+            "game": ["--username", "${auth_player_name}", "--version", "${version_name}"],
+            "jvm": jvm
+        },
+        "libraries": libraries
+    })
+}
+
+/// Builds a library entry with a downloadable artifact
+pub fn artifact_library(name: &str, path: &str) -> serde_json::Value {
+    serde_json::json!({
+        "name": name,
+        "downloads": {
+            "artifact": {
+                "path": path,
+                "sha1": "0000000000000000000000000000000000000000",
+                "size": 1,
+                "url": format!("http://localhost/{}", path)
+            }
+        }
+    })
+}
+
+/// Builds a jar (zip) archive in memory from file names and contents
+pub fn jar_bytes(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    use std::io::Write;
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (name, content) in entries {
+        zip.start_file(*name, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(content).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
+
+/// Writes an executable shell script, creating its parent directories
+#[cfg(unix)]
+pub fn write_script(path: &Path, body: &str) -> PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path.to_path_buf()
+}
+
+/// Creates a fake Java 17 home whose `java` runs the given shell script body
+#[cfg(unix)]
+pub fn write_fake_java(home: &Path, body: &str) {
+    std::fs::create_dir_all(home).unwrap();
+    std::fs::write(home.join("release"), "JAVA_VERSION=\"17.0.8\"\n").unwrap();
+    write_script(&home.join("bin").join("java"), body);
+}
+
+/// Builds launch options for Linux x86_64 that use the fake Java in `root_dir/jdk`
+pub fn launch_options(root_dir: &Path) -> LaunchOptions {
+    let runtime = inspect_java_home(&root_dir.join("jdk"), JavaSource::Custom).unwrap();
+    LaunchOptions {
+        context: RuleContext::new(Platform::new("linux", "x86_64")),
+        java_runtimes: Some(vec![runtime]),
+        install_java: false,
+        ..LaunchOptions::default()
+    }
+}
+
+/// Builds the account tests launch as
+pub fn account() -> Account {
+    Account::new(
+        "Steve",
+        "069a79f4-44e9-4726-a5be-fca90e38aaf5",
+        "token",
+        "msa",
+    )
+}
@@ -0,0 +1,140 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::temp_dir;
+    use craft_launcher_rust::craft_launcher::core::crash_analyzer::crash_analyzer::{
+        CrashAnalyzer, CrashCause, EvidenceSource,
+    };
+    use std::path::{Path, PathBuf};
+    use std::time::{Duration, SystemTime};
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("crash")
+            .join(name)
+    }
+
+    fn fixture_lines(name: &str) -> Vec<String> {
+        std::fs::read_to_string(fixture(name))
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn analyze_log(name: &str) -> Vec<CrashCause> {
+        let mut analyzer = CrashAnalyzer::new(temp_dir(&format!("crash_{}", name)));
+        analyzer.add_log_lines(fixture_lines(name));
+        analyzer
+            .analyze(Some(1))
+            .diagnoses
+            .iter()
+            .map(|diagnosis| diagnosis.cause)
+            .collect()
+    }
+
+    /// Tests classifying the captured log of known crashes
+    #[test]
+    fn test_classify_logs() {
+        assert_eq!(
+            analyze_log("fabric_missing_mod.log"),
+            vec![CrashCause::MissingMod]
+        );
+        assert_eq!(
+            analyze_log("out_of_memory.log"),
+            vec![CrashCause::OutOfMemory]
+        );
+        assert_eq!(
+            analyze_log("corrupted_library.log"),
+            vec![CrashCause::CorruptedLibrary]
+        );
+    }
+
+    /// Tests finding new crash reports and JVM error logs in the game directory
+    #[test]
+    fn test_crash_files() {
+        let game_dir = temp_dir("crash_files");
+        let report = game_dir
+            .join("crash-reports")
+            .join("crash-2024-05-01_12.00.00-client.txt");
+        std::fs::create_dir_all(report.parent().unwrap()).unwrap();
+        std::fs::copy(fixture("crash-2024-05-01_12.00.00-client.txt"), &report).unwrap();
+        let hs_err = game_dir.join("hs_err_pid4242.log");
+        std::fs::copy(fixture("hs_err_pid4242.log"), &hs_err).unwrap();
+
+        let analysis = CrashAnalyzer::new(&game_dir)
+            .with_since(SystemTime::now() - Duration::from_secs(60))
+            .analyze(None);
+        assert!(analysis.crashed);
+        assert_eq!(analysis.crash_reports, vec![report.clone()]);
+        assert_eq!(analysis.jvm_error_logs, vec![hs_err.clone()]);
+
+        let causes: Vec<_> = analysis.diagnoses.iter().map(|d| d.cause).collect();
+        assert_eq!(
+            causes,
+            vec![CrashCause::MixinFailure, CrashCause::GraphicsDriver]
+        );
+        let mixin = &analysis.diagnoses[0];
+        assert!(
+            mixin
+                .evidence
+                .iter()
+                .all(|evidence| evidence.source == EvidenceSource::CrashReport(report.clone()))
+        );
+        assert!(
+            mixin
+                .evidence
+                .iter()
+                .any(|evidence| evidence.line.contains("brokenmod.mixins.json"))
+        );
+        assert_eq!(
+            analysis.diagnoses[1].evidence[0].line,
+            "# C  [atio6axx.dll+0x1b2c]"
+        );
+
+        // Files written before the launch belong to an earlier crash
+        let analysis = CrashAnalyzer::new(&game_dir)
+            .with_since(SystemTime::now() + Duration::from_secs(60))
+            .analyze(Some(0));
+        assert!(!analysis.crashed);
+        assert!(analysis.crash_reports.is_empty());
+    }
+
+    /// Tests that a killed game only counts as crashed with evidence of a crash
+    #[test]
+    fn test_killed_game() {
+        let mut analyzer = CrashAnalyzer::new(temp_dir("crash_killed"));
+        analyzer.add_log_lines(["[12:00:00] [Render thread/INFO]: Stopping!"]);
+        let analysis = analyzer.analyze(None);
+        assert!(!analysis.crashed);
+        assert!(analysis.diagnoses.is_empty());
+        assert!(analysis.primary().is_none());
+
+        analyzer.add_log_lines(fixture_lines("out_of_memory.log"));
+        let analysis = analyzer.analyze(None);
+        assert!(analysis.crashed);
+        assert_eq!(analysis.primary().unwrap().cause, CrashCause::OutOfMemory);
+    }
+
+    /// Tests a native JVM crash without a known cause
+    #[test]
+    fn test_jvm_crash() {
+        let game_dir = temp_dir("crash_jvm");
+        std::fs::copy(
+            fixture("hs_err_pid5151.log"),
+            game_dir.join("hs_err_pid5151.log"),
+        )
+        .unwrap();
+
+        let analysis = CrashAnalyzer::new(&game_dir).analyze(Some(134));
+        let diagnosis = analysis.primary().unwrap();
+        assert_eq!(diagnosis.cause, CrashCause::JvmCrash);
+        let lines: Vec<_> = diagnosis.evidence.iter().map(|e| e.line.as_str()).collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("#  SIGSEGV"));
+        assert!(lines[1].contains("libjvm.so"));
+    }
+}
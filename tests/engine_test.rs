@@ -0,0 +1,411 @@
+mod common;
+
+#[cfg(all(test, unix))]
+mod tests {
+    use crate::common::{
+        MockResponse, MockServer, account, artifact_library, launch_options, sha1_hex, temp_dir,
+        vanilla_version_json, write_fake_java, write_version,
+    };
+    use craft_launcher_rust::craft_launcher::core::disposable::Disposable;
+    use craft_launcher_rust::craft_launcher::core::engine::engine::{
+        Instance, LaunchError, LaunchOptions, launch,
+    };
+    use craft_launcher_rust::craft_launcher::core::game_output::game_output::{
+        LogLevel, OutputStream,
+    };
+    use craft_launcher_rust::craft_launcher::core::process_registry::process_registry::{
+        ProcessRegistry, RegistryError,
+    };
+    use serde_json::json;
+    use std::io::Read;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::Path;
+
+    /// Tests installing files, substituting arguments and reading the game's output
+    #[test]
+    fn test_launch_installs_and_spawns() {
+        let server = MockServer::start();
+        let root_dir = temp_dir("engine_launch");
+        write_fake_java(
+            &root_dir.join("jdk"),
+            "pwd; echo \"$GAME_ENV\"; for arg in \"$@\"; do echo \"$arg\"; done",
+        );
+
+        let client = b"client jar".to_vec();
+        let library = b"library jar".to_vec();
+        let asset = b"sound".to_vec();
+        let asset_hash = sha1_hex(&asset);
+        let asset_index = json!({"objects": {"minecraft/sounds/a.ogg": {"hash": asset_hash, "size": asset.len()}}})
+            .to_string()
+            .into_bytes();
+
+        server.add("/client.jar", MockResponse::ok(client.clone()));
+        server.add("/lib.jar", MockResponse::ok(library.clone()));
+        server.add("/assets/5.json", MockResponse::ok(asset_index.clone()));
+        server.add(
+            &format!("/objects/{}/{}", &asset_hash[..2], asset_hash),
+            MockResponse::ok(asset.clone()),
+        );
+
+        let mut lib = artifact_library("org.example:lib:1.0", "org/example/lib/1.0/lib-1.0.jar");
+        lib["downloads"]["artifact"]["sha1"] = json!(sha1_hex(&library));
+        lib["downloads"]["artifact"]["size"] = json!(library.len());
+        lib["downloads"]["artifact"]["url"] = json!(server.url("/lib.jar"));
+        let mut version = vanilla_version_json(
+            "1.20.1",
+            json!([lib]),
+            json!([
+                "-Djava.library.path=${natives_directory}",
+                "-cp",
+                "${classpath}"
+            ]),
+        );
+        version["downloads"]["client"]["sha1"] = json!(sha1_hex(&client));
+        version["downloads"]["client"]["url"] = json!(server.url("/client.jar"));
+        version["assetIndex"]["sha1"] = json!(sha1_hex(&asset_index));
+        version["assetIndex"]["url"] = json!(server.url("/assets/5.json"));
+        write_version(&root_dir, "1.20.1", &version);
+
+        let game_dir = root_dir.join("instances").join("survival");
+        let instance = Instance::new(&root_dir, "1.20.1")
+            .with_game_dir(&game_dir)
+            .with_memory(512, 2048)
+            .add_jvm_args(["-Xmx4096M"])
+            .with_env("GAME_ENV", "from-instance");
+        let options = LaunchOptions {
+            resources_url: server.url("/objects"),
+            ..launch_options(&root_dir)
+        };
+
+        let mut game = launch(&instance, &account(), &options).unwrap();
+        assert!(game.pid() > 0);
+        let mut stdout = String::new();
+        game.take_stdout()
+            .unwrap()
+            .read_to_string(&mut stdout)
+            .unwrap();
+        assert!(game.wait().unwrap().success());
+
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(Path::new(lines[0]), game_dir.canonicalize().unwrap());
+        assert_eq!(lines[1], "from-instance");
+        assert!(lines.contains(&"-Xms512M"));
+        assert!(lines.contains(&"-Xmx4096M"));
+        assert!(!lines.contains(&"-Xmx2048M"));
+        assert!(
+            lines
+                .iter()
+                .any(|line| line.starts_with("-Djava.library.path="))
+        );
+        let cp = lines.iter().position(|line| *line == "-cp").unwrap();
+        assert!(lines[cp + 1].ends_with("versions/1.20.1/1.20.1.jar"));
+        assert!(lines[cp + 1].contains("org/example/lib/1.0/lib-1.0.jar"));
+        assert_eq!(lines[cp + 2], "net.minecraft.client.main.Main");
+        assert_eq!(
+            &lines[cp + 3..],
+            &["--username", "Steve", "--version", "1.20.1"]
+        );
+
+        assert_eq!(
+            std::fs::read(
+                root_dir
+                    .join("assets/objects")
+                    .join(&asset_hash[..2])
+                    .join(&asset_hash)
+            )
+            .unwrap(),
+            asset
+        );
+    }
+
+    /// Tests that pre-1.6 assets are copied to the game's resources directory, and
+    /// that an index naming a file outside of it is rejected
+    #[test]
+    fn test_launch_maps_assets_to_resources() {
+        let server = MockServer::start();
+        let root_dir = temp_dir("engine_resources");
+        write_fake_java(&root_dir.join("jdk"), "exit 0");
+
+        let client = b"client jar".to_vec();
+        let asset = b"step".to_vec();
+        let asset_hash = sha1_hex(&asset);
+        server.add("/client.jar", MockResponse::ok(client.clone()));
+        server.add(
+            &format!("/objects/{}/{}", &asset_hash[..2], asset_hash),
+            MockResponse::ok(asset.clone()),
+        );
+
+        let game_dir = root_dir.join("instances").join("classic");
+        let instance = Instance::new(&root_dir, "1.5.2").with_game_dir(&game_dir);
+        let options = LaunchOptions {
+            resources_url: server.url("/objects"),
+            ..launch_options(&root_dir)
+        };
+        let write_legacy_version = |name: &str| {
+            let asset_index = json!({
+                "map_to_resources": true,
+                "objects": {name: {"hash": asset_hash, "size": asset.len()}}
+            })
+            .to_string()
+            .into_bytes();
+            server.add(
+                "/assets/pre-1.6.json",
+                MockResponse::ok(asset_index.clone()),
+            );
+
+            let mut version =
+                vanilla_version_json("1.5.2", json!([]), json!(["-cp", "${classpath}"]));
+            version["downloads"]["client"]["sha1"] = json!(sha1_hex(&client));
+            version["downloads"]["client"]["url"] = json!(server.url("/client.jar"));
+            version["assetIndex"]["id"] = json!("pre-1.6");
+            version["assetIndex"]["sha1"] = json!(sha1_hex(&asset_index));
+            version["assetIndex"]["url"] = json!(server.url("/assets/pre-1.6.json"));
+            write_version(&root_dir, "1.5.2", &version);
+        };
+
+        write_legacy_version("../../escaped.ogg");
+        assert!(matches!(
+            launch(&instance, &account(), &options),
+            Err(LaunchError::Install(_))
+        ));
+        assert!(!root_dir.join("instances/escaped.ogg").exists());
+        assert!(!root_dir.join("escaped.ogg").exists());
+
+        write_legacy_version("sound/step/grass1.ogg");
+        let mut game = launch(&instance, &account(), &options).unwrap();
+        assert!(game.wait().unwrap().success());
+        assert_eq!(
+            std::fs::read(game_dir.join("resources/sound/step/grass1.ogg")).unwrap(),
+            asset
+        );
+    }
+
+    /// Tests killing a running game through the Disposable trait, and that it cannot
+    /// be launched twice while it runs
+    #[test]
+    fn test_dispose_kills_game() {
+        let root_dir = temp_dir("engine_kill");
+        write_fake_java(&root_dir.join("jdk"), "exec sleep 30");
+        write_version(
+            &root_dir,
+            "1.20.1",
+            &vanilla_version_json("1.20.1", json!([]), json!(["-cp", "${classpath}"])),
+        );
+        let options = LaunchOptions {
+            install_files: false,
+            use_argument_file: true,
+            ..launch_options(&root_dir)
+        };
+
+        // Argument files of launchers that are no longer running are swept
+        let stale = root_dir.join("bin/argfiles/jvm-4194305-1-0.args");
+        std::fs::create_dir_all(stale.parent().unwrap()).unwrap();
+        std::fs::write(&stale, "").unwrap();
+
+        let mut game = launch(&Instance::new(&root_dir, "1.20.1"), &account(), &options).unwrap();
+        assert!(!stale.exists());
+        let argument_file = game.command()[1].trim_start_matches('@').to_string();
+        assert!(Path::new(&argument_file).is_file());
+        assert!(game.try_wait().unwrap().is_none());
+
+        let error = launch(&Instance::new(&root_dir, "1.20.1"), &account(), &options).unwrap_err();
+        assert!(matches!(
+            error,
+            LaunchError::Registry(RegistryError::AlreadyRunning(ref running)) if running.pid == game.pid()
+        ));
+
+        game.dispose();
+        assert!(game.try_wait().unwrap().is_some());
+        assert!(!Path::new(&argument_file).exists());
+        assert!(ProcessRegistry::new(&root_dir).list().unwrap().is_empty());
+    }
+
+    /// Tests that the argument file outlives a dropped handle and is removed once
+    /// `try_wait` sees the game exit
+    #[test]
+    fn test_argument_file_lifetime() {
+        let root_dir = temp_dir("engine_argfile");
+        write_fake_java(&root_dir.join("jdk"), "read line < \"${1#@}\"; exit 0");
+        write_version(
+            &root_dir,
+            "1.20.1",
+            &vanilla_version_json("1.20.1", json!([]), json!(["-cp", "${classpath}"])),
+        );
+        let options = LaunchOptions {
+            install_files: false,
+            use_argument_file: true,
+            use_process_registry: false,
+            ..launch_options(&root_dir)
+        };
+        let instance = Instance::new(&root_dir, "1.20.1");
+
+        let game = launch(&instance, &account(), &options).unwrap();
+        let dropped = game.command()[1].trim_start_matches('@').to_string();
+        drop(game);
+        assert!(Path::new(&dropped).is_file());
+
+        let mut game = launch(&instance, &account(), &options).unwrap();
+        let argument_file = game.command()[1].trim_start_matches('@').to_string();
+        let status = loop {
+            if let Some(status) = game.try_wait().unwrap() {
+                break status;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        };
+        assert!(status.success());
+        assert!(!Path::new(&argument_file).exists());
+    }
+
+    /// Tests downloading the Log4j config and parsing the XML events of the game
+    #[test]
+    fn test_log_config_and_output() {
+        let server = MockServer::start();
+        let root_dir = temp_dir("engine_logging");
+        write_fake_java(
+            &root_dir.join("jdk"),
+            r#"for arg in "$@"; do case "$arg" in -Dlog4j.configurationFile=*) echo "config ${arg#*=}";; esac; done
+echo '<log4j:Event logger="net.minecraft.client.Minecraft" timestamp="1700000000000" level="WARN" thread="Render thread">'
+echo '  <log4j:Message><![CDATA[Skipping bad option]]></log4j:Message>'
+echo '</log4j:Event>'
+echo 'printed on stderr' >&2"#,
+        );
+
+        let client = b"client jar".to_vec();
+        let asset_index = b"{\"objects\": {}}".to_vec();
+        let log_config = b"<Configuration/>".to_vec();
+        server.add("/client.jar", MockResponse::ok(client.clone()));
+        server.add("/assets/5.json", MockResponse::ok(asset_index.clone()));
+        server.add("/client-1.12.xml", MockResponse::ok(log_config.clone()));
+
+        let mut version = vanilla_version_json("1.20.1", json!([]), json!(["-cp", "${classpath}"]));
+        version["downloads"]["client"]["sha1"] = json!(sha1_hex(&client));
+        version["downloads"]["client"]["url"] = json!(server.url("/client.jar"));
+        version["assetIndex"]["sha1"] = json!(sha1_hex(&asset_index));
+        version["assetIndex"]["url"] = json!(server.url("/assets/5.json"));
+        version["logging"] = json!({
+            "client": {
+                "argument": "-Dlog4j.configurationFile=${path}",
+                "file": {
+                    "id": "client-1.12.xml",
+                    "sha1": sha1_hex(&log_config),
+                    "size": log_config.len(),
+                    "url": server.url("/client-1.12.xml")
+                },
+                "type": "log4j2-xml"
+            }
+        });
+        write_version(&root_dir, "1.20.1", &version);
+
+        let mut game = launch(
+            &Instance::new(&root_dir, "1.20.1"),
+            &account(),
+            &launch_options(&root_dir),
+        )
+        .unwrap();
+        let mut records = Vec::new();
+        game.capture_output(|record| records.push(record));
+        assert!(game.wait().unwrap().success());
+
+        let config_path = root_dir.join("assets/log_configs/client-1.12.xml");
+        assert_eq!(std::fs::read(&config_path).unwrap(), log_config);
+
+        let stdout: Vec<_> = records
+            .iter()
+            .filter(|record| record.stream == OutputStream::Stdout)
+            .collect();
+        assert_eq!(stdout.len(), 2);
+        assert_eq!(
+            stdout[0].message,
+            format!("config {}", config_path.to_string_lossy())
+        );
+        assert!(stdout[1].xml);
+        assert_eq!(stdout[1].level, Some(LogLevel::Warn));
+        assert_eq!(stdout[1].thread.as_deref(), Some("Render thread"));
+        assert_eq!(stdout[1].message, "Skipping bad option");
+        assert!(records.iter().any(|record| {
+            record.stream == OutputStream::Stderr && record.message == "printed on stderr"
+        }));
+    }
+
+    /// Tests wrappers, environment overrides and hooks around the game
+    #[test]
+    fn test_wrappers_env_and_hooks() {
+        let root_dir = temp_dir("engine_hooks");
+        write_fake_java(
+            &root_dir.join("jdk"),
+            r#"echo "java $LAUNCHER_MODE ${HOME:-unset}""#,
+        );
+        let wrapper = root_dir.join("wrapper.sh");
+        std::fs::write(&wrapper, "#!/bin/sh\necho \"wrapped $1\"\nexec \"$@\"\n").unwrap();
+        std::fs::set_permissions(&wrapper, std::fs::Permissions::from_mode(0o755)).unwrap();
+        write_version(
+            &root_dir,
+            "1.20.1",
+            &vanilla_version_json("1.20.1", json!([]), json!([])),
+        );
+        let options = LaunchOptions {
+            install_files: false,
+            ..launch_options(&root_dir)
+        };
+
+        let instance = Instance::new(&root_dir, "1.20.1")
+            .add_wrapper([wrapper.to_string_lossy()])
+            .remove_env("LAUNCHER_MODE")
+            .with_env("LAUNCHER_MODE", "wrapped")
+            .with_env("HOME", "/nonexistent")
+            .remove_env("HOME")
+            .add_pre_launch_hook(["sh", "-c", "echo ${version_id} > pre.txt"])
+            .add_post_exit_hook(["sh", "-c", "echo ${exit_code} > ${instance_dir}/post.txt"]);
+        let mut game = launch(&instance, &account(), &options).unwrap();
+        let mut stdout = String::new();
+        game.take_stdout()
+            .unwrap()
+            .read_to_string(&mut stdout)
+            .unwrap();
+
+        // try_wait never runs the post-exit hooks, wait does
+        while game.try_wait().unwrap().is_none() {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert!(!root_dir.join("post.txt").exists());
+        assert!(game.wait().unwrap().success());
+        assert!(game.post_exit_hook_error().is_none());
+
+        let java = root_dir.join("jdk/bin/java");
+        assert_eq!(
+            stdout,
+            format!("wrapped {}\njava wrapped unset\n", java.to_string_lossy())
+        );
+        assert_eq!(
+            std::fs::read_to_string(root_dir.join("pre.txt")).unwrap(),
+            "1.20.1\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(root_dir.join("post.txt")).unwrap(),
+            "0\n"
+        );
+
+        let failing = Instance::new(&root_dir, "1.20.1")
+            .add_pre_launch_hook(["sh", "-c", "exit 3"])
+            .add_post_exit_hook(["touch", "never.txt"]);
+        let error = launch(&failing, &account(), &options).unwrap_err();
+        assert!(matches!(error, LaunchError::Hook(_)));
+        assert!(!root_dir.join("never.txt").exists());
+        assert!(ProcessRegistry::new(&root_dir).list().unwrap().is_empty());
+    }
+
+    /// Tests that a missing version is reported before anything is spawned
+    #[test]
+    fn test_missing_version() {
+        let root_dir = temp_dir("engine_missing");
+        write_fake_java(&root_dir.join("jdk"), "exit 0");
+
+        let error = launch(
+            &Instance::new(&root_dir, "1.20.1"),
+            &account(),
+            &launch_options(&root_dir),
+        )
+        .unwrap_err();
+        assert!(matches!(error, LaunchError::VersionResolution(_)));
+    }
+}
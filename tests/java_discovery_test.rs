@@ -0,0 +1,120 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::temp_dir;
+    use craft_launcher_rust::craft_launcher::java::java_discovery::java_discovery::{
+        JAVA_EXECUTABLE, JavaDiscovery, JavaSource,
+    };
+    use std::path::Path;
+
+    /// Creates a fake Java home with a `release` file
+    fn write_java_home(home: &Path, version: &str, vendor: &str, arch: &str) {
+        std::fs::create_dir_all(home.join("bin")).unwrap();
+        std::fs::write(home.join("bin").join(JAVA_EXECUTABLE), b"").unwrap();
+        std::fs::write(
+            home.join("release"),
+            format!(
+                "IMPLEMENTOR=\"{}\"\nJAVA_VERSION=\"{}\"\nOS_ARCH=\"{}\"\n",
+                vendor, version, arch
+            ),
+        )
+        .unwrap();
+    }
+
+    /// Tests discovery in search directories and launcher managed runtime folders
+    #[test]
+    fn test_discover_search_and_managed_dirs() {
+        let root_dir = temp_dir("java_discovery_dirs");
+        let jvm_dir = root_dir.join("jvm");
+        write_java_home(
+            &jvm_dir.join("temurin-17"),
+            "17.0.8",
+            "Eclipse Adoptium",
+            "amd64",
+        );
+        write_java_home(
+            &jvm_dir.join("zulu-8"),
+            "1.8.0_392",
+            "Azul Systems, Inc.",
+            "aarch64",
+        );
+        write_java_home(
+            &root_dir
+                .join("runtime")
+                .join("java-runtime-delta")
+                .join("linux")
+                .join("java-runtime-delta"),
+            "21.0.3",
+            "Microsoft",
+            "x86_64",
+        );
+        std::fs::create_dir_all(jvm_dir.join("not-a-jdk")).unwrap();
+
+        let mut discovery = JavaDiscovery::empty();
+        discovery
+            .add_search_dir(&jvm_dir, JavaSource::System)
+            .add_managed_dir(&root_dir);
+        let runtimes = discovery.runtimes();
+
+        assert_eq!(runtimes.len(), 3);
+        let java_17 = runtimes.iter().find(|r| r.major_version == 17).unwrap();
+        assert_eq!(java_17.version, "17.0.8");
+        assert_eq!(java_17.vendor.as_deref(), Some("Eclipse Adoptium"));
+        assert_eq!(java_17.arch.as_deref(), Some("x86_64"));
+        assert_eq!(java_17.source, JavaSource::System);
+        assert_eq!(
+            java_17.executable,
+            jvm_dir.join("temurin-17").join("bin").join(JAVA_EXECUTABLE)
+        );
+
+        let java_8 = runtimes.iter().find(|r| r.major_version == 8).unwrap();
+        assert_eq!(java_8.arch.as_deref(), Some("arm64"));
+
+        let java_21 = runtimes.iter().find(|r| r.major_version == 21).unwrap();
+        assert_eq!(java_21.source, JavaSource::Managed);
+    }
+
+    /// Tests that results are cached until refreshed
+    #[test]
+    fn test_cache_and_refresh() {
+        let root_dir = temp_dir("java_discovery_cache");
+        write_java_home(&root_dir.join("jdk-17"), "17.0.8", "Vendor", "x86_64");
+
+        let mut discovery = JavaDiscovery::empty();
+        discovery.add_search_dir(&root_dir, JavaSource::Custom);
+        assert_eq!(discovery.runtimes().len(), 1);
+
+        write_java_home(&root_dir.join("jdk-21"), "21.0.3", "Vendor", "x86_64");
+        assert_eq!(discovery.runtimes().len(), 1);
+        assert_eq!(discovery.refresh().len(), 2);
+    }
+
+    /// Tests falling back to running java when there is no `release` file
+    #[cfg(unix)]
+    #[test]
+    fn test_properties_fallback() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root_dir = temp_dir("java_discovery_properties");
+        let home = root_dir.join("legacy-jre");
+        std::fs::create_dir_all(home.join("bin")).unwrap();
+        let executable = home.join("bin").join("java");
+        std::fs::write(
+            &executable,
+            "#!/bin/sh\necho 'Property settings:' >&2\necho '    java.vendor = Oracle Corporation' >&2\necho '    java.version = 1.8.0_51' >&2\necho '    os.arch = x86' >&2\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&executable, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut discovery = JavaDiscovery::empty();
+        discovery.add_search_dir(&root_dir, JavaSource::Custom);
+        let runtimes = discovery.runtimes();
+
+        assert_eq!(runtimes.len(), 1);
+        assert_eq!(runtimes[0].major_version, 8);
+        assert_eq!(runtimes[0].version, "1.8.0_51");
+        assert_eq!(runtimes[0].vendor.as_deref(), Some("Oracle Corporation"));
+        assert_eq!(runtimes[0].arch.as_deref(), Some("x86"));
+    }
+}
@@ -0,0 +1,180 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{MockResponse, MockServer, sha1_hex, temp_dir};
+    use craft_launcher_rust::craft_launcher::java::runtime_installer::runtime_installer::{
+        JavaRuntimeInstaller, RuntimeInstallError, installed_version,
+    };
+    use serde_json::json;
+    use std::fs;
+
+    const JAVA: &[u8] = b"#!/bin/sh\necho java\n";
+    const RELEASE: &[u8] = b"JAVA_VERSION=\"17.0.8\"\n";
+
+    fn lzma(bytes: &[u8]) -> Vec<u8> {
+        let mut compressed = Vec::new();
+        lzma_rs::lzma_compress(&mut std::io::Cursor::new(bytes), &mut compressed).unwrap();
+        compressed
+    }
+
+    /// Serves an index with one linux component whose java binary is only available as LZMA
+    fn serve_runtime(server: &MockServer, release_sha1: &str) {
+        let java_lzma = lzma(JAVA);
+        server.add("/files/java.lzma", MockResponse::ok(java_lzma.clone()));
+        server.add("/files/java", MockResponse::ok(JAVA));
+        server.add("/files/release", MockResponse::ok(RELEASE));
+
+        let manifest = json!({
+            "files": {
+                "bin": {"type": "directory"},
+                "bin/java": {
+                    "type": "file",
+                    "executable": true,
+                    "downloads": {
+                        "raw": {"sha1": sha1_hex(JAVA), "size": JAVA.len(), "url": server.url("/files/java")},
+                        "lzma": {"sha1": sha1_hex(&java_lzma), "size": java_lzma.len(), "url": server.url("/files/java.lzma")}
+                    }
+                },
+                "release": {
+                    "type": "file",
+                    "executable": false,
+                    "downloads": {
+                        "raw": {"sha1": release_sha1, "size": RELEASE.len(), "url": server.url("/files/release")}
+                    }
+                },
+                "legal": {"type": "directory"},
+                "legal/java": {"type": "link", "target": "../bin/java"}
+            }
+        });
+        let manifest_bytes = manifest.to_string().into_bytes();
+        server.add("/manifest.json", MockResponse::ok(manifest_bytes.clone()));
+
+        server.add(
+            "/all.json",
+            MockResponse::json(&json!({
+                "linux": {
+                    "java-runtime-gamma": [{
+                        "availability": {"group": 1, "progress": 100},
+                        "manifest": {
+                            "sha1": sha1_hex(&manifest_bytes),
+                            "size": manifest_bytes.len(),
+                            "url": server.url("/manifest.json")
+                        },
+                        "version": {"name": "17.0.8", "released": "2023-07-18T00:00:00+00:00"}
+                    }]
+                },
+                "windows-x64": {}
+            })),
+        );
+    }
+
+    /// Tests installing a component with LZMA files, directories, links and executable bits
+    #[test]
+    fn test_install_runtime() {
+        let server = MockServer::start();
+        serve_runtime(&server, &sha1_hex(RELEASE));
+        let root_dir = temp_dir("runtime_install");
+
+        let installer = JavaRuntimeInstaller::new(&root_dir)
+            .with_index_url(&server.url("/all.json"))
+            .with_platform("linux");
+        let runtime = installer.install("java-runtime-gamma").unwrap();
+
+        let directory = root_dir.join("runtime").join("java-runtime-gamma");
+        assert_eq!(runtime.version, "17.0.8");
+        assert_eq!(runtime.directory, directory);
+        assert_eq!(runtime.executable, directory.join("bin").join("java"));
+        assert_eq!(fs::read(directory.join("bin/java")).unwrap(), JAVA);
+        assert_eq!(fs::read(directory.join("release")).unwrap(), RELEASE);
+        assert!(directory.join("legal").is_dir());
+        assert_eq!(installed_version(&directory).as_deref(), Some("17.0.8"));
+
+        let paths = server.requested_paths();
+        assert!(paths.contains(&"/files/java.lzma".to_string()));
+        assert!(!paths.contains(&"/files/java".to_string()));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(directory.join("bin/java"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o111, 0o111);
+            assert_eq!(
+                fs::read_link(directory.join("legal/java")).unwrap(),
+                std::path::Path::new("../bin/java")
+            );
+        }
+    }
+
+    /// Tests that a second install only downloads the metadata
+    #[test]
+    fn test_reinstall_skips_valid_files() {
+        let server = MockServer::start();
+        serve_runtime(&server, &sha1_hex(RELEASE));
+        let root_dir = temp_dir("runtime_reinstall");
+
+        let installer = JavaRuntimeInstaller::new(&root_dir)
+            .with_index_url(&server.url("/all.json"))
+            .with_platform("linux");
+        installer.install("java-runtime-gamma").unwrap();
+        let before = server.requested_paths().len();
+
+        // Corrupt one file; only that one should be downloaded again
+        let release = root_dir.join("runtime/java-runtime-gamma/release");
+        fs::write(&release, b"broken").unwrap();
+        installer.install("java-runtime-gamma").unwrap();
+
+        let paths = server.requested_paths()[before..].to_vec();
+        assert_eq!(paths, vec!["/all.json", "/manifest.json", "/files/release"]);
+        assert_eq!(fs::read(&release).unwrap(), RELEASE);
+    }
+
+    /// Tests that a file with the wrong hash is rejected
+    #[test]
+    fn test_hash_mismatch() {
+        let server = MockServer::start();
+        serve_runtime(&server, "0000000000000000000000000000000000000000");
+        let root_dir = temp_dir("runtime_mismatch");
+
+        let error = JavaRuntimeInstaller::new(&root_dir)
+            .with_index_url(&server.url("/all.json"))
+            .with_platform("linux")
+            .install("java-runtime-gamma")
+            .unwrap_err();
+
+        assert!(matches!(error, RuntimeInstallError::HashMismatch { .. }));
+    }
+
+    /// Tests components and platforms that are not in the index
+    #[test]
+    fn test_component_not_found() {
+        let server = MockServer::start();
+        serve_runtime(&server, &sha1_hex(RELEASE));
+        let root_dir = temp_dir("runtime_not_found");
+
+        let installer =
+            JavaRuntimeInstaller::new(&root_dir).with_index_url(&server.url("/all.json"));
+
+        let error = installer
+            .clone()
+            .with_platform("linux")
+            .install("jre-legacy")
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            RuntimeInstallError::ComponentNotFound { .. }
+        ));
+
+        let error = installer
+            .with_platform("windows-x64")
+            .install("java-runtime-gamma")
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            RuntimeInstallError::ComponentNotFound { ref platform, .. } if platform == "windows-x64"
+        ));
+    }
+}
@@ -0,0 +1,127 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{temp_dir, vanilla_version_json, write_version};
+    use craft_launcher_rust::craft_launcher::core::version::library_conflict::library_conflict::ConflictPolicy;
+    use craft_launcher_rust::craft_launcher::core::version::version_resolver::version_resolver::resolve_version;
+    use craft_launcher_rust::craft_launcher::java::java_discovery::java_discovery::{
+        JAVA_EXECUTABLE, JavaDiscovery, JavaSource,
+    };
+    use craft_launcher_rust::craft_launcher::java::java_selector::java_selector::{
+        Compatibility, JavaSelectionError, JavaSelectionOptions, select_java,
+    };
+    use serde_json::json;
+    use std::path::{Path, PathBuf};
+
+    /// Creates a fake Java home with a `release` file and returns its executable
+    fn write_java_home(home: &Path, version: &str) -> PathBuf {
+        std::fs::create_dir_all(home.join("bin")).unwrap();
+        std::fs::write(home.join("bin").join(JAVA_EXECUTABLE), b"").unwrap();
+        std::fs::write(
+            home.join("release"),
+            format!("JAVA_VERSION=\"{}\"\n", version),
+        )
+        .unwrap();
+        home.join("bin").join(JAVA_EXECUTABLE)
+    }
+
+    /// Writes a 1.12.2 style version without `javaVersion`
+    fn write_legacy_version(root_dir: &Path) {
+        write_version(
+            root_dir,
+            "1.12.2",
+            &json!({
+                "id": "1.12.2",
+                "time": "2017-09-18T08:39:46+00:00",
+                "releaseTime": "2017-09-18T08:39:46+00:00",
+                "type": "release",
+                "mainClass": "net.minecraft.client.main.Main",
+                "minecraftArguments": "--username ${auth_player_name} --version ${version_name}",
+                "assets": "1.12",
+                "libraries": []
+            }),
+        );
+    }
+
+    /// Tests selecting runtimes for a legacy and a modern version from discovered runtimes
+    #[test]
+    fn test_select_for_versions() {
+        let root_dir = temp_dir("java_selector_versions");
+        let jvm_dir = root_dir.join("jvm");
+        write_java_home(&jvm_dir.join("temurin-8"), "1.8.0_392");
+        write_java_home(&jvm_dir.join("temurin-21"), "21.0.3");
+        write_java_home(
+            &root_dir
+                .join("runtime")
+                .join("java-runtime-gamma")
+                .join("linux")
+                .join("java-runtime-gamma"),
+            "17.0.8",
+        );
+        write_legacy_version(&root_dir);
+        write_version(
+            &root_dir,
+            "1.20.1",
+            &vanilla_version_json("1.20.1", json!([]), json!([])),
+        );
+
+        let mut discovery = JavaDiscovery::empty();
+        discovery
+            .add_search_dir(&jvm_dir, JavaSource::System)
+            .add_managed_dir(&root_dir);
+        let runtimes = discovery.runtimes().to_vec();
+        let options = JavaSelectionOptions::default().with_strict(true);
+
+        let legacy = resolve_version(&root_dir, "1.12.2", ConflictPolicy::ChildWins).unwrap();
+        let selection = select_java(&legacy, &runtimes, &options).unwrap();
+        assert_eq!(selection.runtime.major_version, 8);
+        assert!(selection.requirement.inferred);
+        assert_eq!(
+            selection.requirement.component.as_deref(),
+            Some("jre-legacy")
+        );
+
+        let modern = resolve_version(&root_dir, "1.20.1", ConflictPolicy::ChildWins).unwrap();
+        let selection = select_java(&modern, &runtimes, &options).unwrap();
+        assert_eq!(selection.runtime.major_version, 17);
+        assert_eq!(selection.runtime.source, JavaSource::Managed);
+        assert_eq!(selection.compatibility, Compatibility::Exact);
+
+        // A per-instance major version override
+        let selection =
+            select_java(&modern, &runtimes, &options.clone().with_major_version(21)).unwrap();
+        assert_eq!(selection.runtime.major_version, 21);
+    }
+
+    /// Tests the java executable override with and without strict mode
+    #[test]
+    fn test_java_path_override() {
+        let root_dir = temp_dir("java_selector_override");
+        let java_21 = write_java_home(&root_dir.join("jdk-21"), "21.0.3");
+        write_legacy_version(&root_dir);
+        let legacy = resolve_version(&root_dir, "1.12.2", ConflictPolicy::ChildWins).unwrap();
+
+        let options = JavaSelectionOptions::default().with_java_path(&java_21);
+        let selection = select_java(&legacy, &[], &options).unwrap();
+        assert_eq!(selection.runtime.executable, java_21);
+        assert_eq!(selection.compatibility, Compatibility::Incompatible);
+
+        let error = select_java(&legacy, &[], &options.with_strict(true)).unwrap_err();
+        assert_eq!(
+            error,
+            JavaSelectionError::IncompatibleJavaPath {
+                path: java_21,
+                requirement: selection.requirement,
+                found: 21,
+            }
+        );
+
+        let missing =
+            JavaSelectionOptions::default().with_java_path(&root_dir.join("nope/bin/java"));
+        assert!(matches!(
+            select_java(&legacy, &[], &missing).unwrap_err(),
+            JavaSelectionError::InvalidJavaPath(_)
+        ));
+    }
+}
@@ -0,0 +1,394 @@
+mod common;
+
+#[cfg(all(test, unix))]
+mod tests {
+    use crate::common::{
+        account, artifact_library, launch_options, sha1_hex, temp_dir, vanilla_version_json,
+        write_fake_java, write_version,
+    };
+    use craft_launcher_rust::craft_launcher::core::account::offline_account::offline_account::OfflineAccount;
+    use craft_launcher_rust::craft_launcher::core::account::yggdrasil_account::yggdrasil_account::YggdrasilAccount;
+    use craft_launcher_rust::craft_launcher::core::engine::engine::{
+        Instance, LaunchError, LaunchOptions,
+    };
+    use craft_launcher_rust::craft_launcher::core::launch_features::launch_features::{
+        LaunchFeatures, QuickPlay,
+    };
+    use craft_launcher_rust::craft_launcher::core::launch_plan::launch_plan::plan_launch;
+    use serde_json::json;
+    use std::path::Path;
+    use std::process::Command;
+
+    /// Body of a fake `java` that prints its working directory and arguments
+    const PRINT_COMMAND: &str =
+        "pwd\necho \"$GAME_ENV\"\nfor arg in \"$@\"; do echo \"$arg\"; done";
+
+    /// Tests that a dry run lists missing files and leaves the disk untouched
+    #[test]
+    fn test_plan_lists_downloads() {
+        let root_dir = temp_dir("launch_plan_downloads");
+        write_fake_java(&root_dir.join("jdk"), PRINT_COMMAND);
+
+        let library = b"library jar".to_vec();
+        let mut lib = artifact_library("org.example:lib:1.0", "org/example/lib/1.0/lib-1.0.jar");
+        lib["downloads"]["artifact"]["sha1"] = json!(sha1_hex(&library));
+        let version = vanilla_version_json(
+            "1.20.1",
+            json!([lib]),
+            json!([
+                "-Djava.library.path=${natives_directory}",
+                "-cp",
+                "${classpath}"
+            ]),
+        );
+        write_version(&root_dir, "1.20.1", &version);
+
+        // The library is already installed, so only the client jar and asset index are missing
+        let library_path = root_dir.join("libraries/org/example/lib/1.0/lib-1.0.jar");
+        std::fs::create_dir_all(library_path.parent().unwrap()).unwrap();
+        std::fs::write(&library_path, &library).unwrap();
+
+        let instance = Instance::new(&root_dir, "1.20.1");
+        let plan = plan_launch(&instance, &account(), &launch_options(&root_dir)).unwrap();
+
+        let paths: Vec<_> = plan.downloads.iter().map(|d| d.path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                root_dir.join("assets").join("indexes").join("5.json"),
+                root_dir.join("versions").join("1.20.1").join("1.20.1.jar"),
+            ]
+        );
+        assert_eq!(plan.java, root_dir.join("jdk").join("bin").join("java"));
+        assert_eq!(plan.java_major_version, 17);
+        assert!(plan.java_install.is_none());
+        assert!(plan.classpath.contains(&library_path));
+        assert!(!root_dir.join("versions/1.20.1/1.20.1.jar").exists());
+        assert!(!root_dir.join("bin").exists());
+
+        let json = serde_json::to_string(&plan).unwrap();
+        assert_eq!(serde_json::from_str::<_>(&json).ok(), Some(plan));
+    }
+
+    /// Tests that Forge and Fabric libraries are planned with the URL and hash the
+    /// installer would use, and that a corrupt library is downloaded again
+    #[test]
+    fn test_plan_modded_libraries() {
+        let root_dir = temp_dir("launch_plan_modded");
+        write_fake_java(&root_dir.join("jdk"), PRINT_COMMAND);
+        write_version(
+            &root_dir,
+            "1.20.1",
+            &vanilla_version_json("1.20.1", json!([]), json!(["-cp", "${classpath}"])),
+        );
+
+        let forge = b"forge universal".to_vec();
+        let forge_path = "net/minecraftforge/forge/1.20.1-47.3.0/forge-1.20.1-47.3.0-universal.jar";
+        let forge_url = format!("https://maven.minecraftforge.net/{}", forge_path);
+        let mut forge_library = artifact_library(
+            "net.minecraftforge:forge:1.20.1-47.3.0:universal",
+            forge_path,
+        );
+        forge_library["downloads"]["artifact"]["url"] = json!(forge_url);
+        forge_library["downloads"]["artifact"]["sha1"] = json!(sha1_hex(&forge));
+        forge_library["downloads"]["artifact"]["size"] = json!(forge.len());
+        write_version(
+            &root_dir,
+            "1.20.1-forge-47.3.0",
+            &json!({
+                "id": "1.20.1-forge-47.3.0",
+                "time": "2024-01-01T00:00:00+00:00",
+                "releaseTime": "2024-01-01T00:00:00+00:00",
+                "type": "release",
+                "inheritsFrom": "1.20.1",
+                "logging": {},
+                "mainClass": "cpw.mods.bootstraplauncher.BootstrapLauncher",
+                "arguments": {"game": ["--launchTarget", "forgeclient"], "jvm": []},
+                "libraries": [forge_library]
+            }),
+        );
+        write_version(
+            &root_dir,
+            "fabric-loader-0.16.14-1.20.1",
+            &json!({
+                "id": "fabric-loader-0.16.14-1.20.1",
+                "inheritsFrom": "1.20.1",
+                "releaseTime": "2024-01-01T00:00:00+00:00",
+                "time": "2024-01-01T00:00:00+00:00",
+                "type": "release",
+                "mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient",
+                "arguments": {"game": [], "jvm": []},
+                "libraries": [
+                    {"name": "net.fabricmc:fabric-loader:0.16.14", "url": "https://maven.fabricmc.net/"}
+                ]
+            }),
+        );
+
+        // A corrupt Forge jar on disk is not accepted as installed
+        let forge_file = root_dir.join("libraries").join(forge_path);
+        std::fs::create_dir_all(forge_file.parent().unwrap()).unwrap();
+        std::fs::write(&forge_file, b"truncated").unwrap();
+
+        let library_downloads = |version_id: &str| {
+            let instance = Instance::new(&root_dir, version_id);
+            plan_launch(&instance, &account(), &launch_options(&root_dir))
+                .unwrap()
+                .downloads
+                .into_iter()
+                .filter(|download| download.path.starts_with(root_dir.join("libraries")))
+                .collect::<Vec<_>>()
+        };
+
+        let downloads = library_downloads("1.20.1-forge-47.3.0");
+        assert_eq!(downloads.len(), 1);
+        assert_eq!(downloads[0].url, forge_url);
+        assert_eq!(downloads[0].path, forge_file);
+        assert_eq!(downloads[0].sha1, Some(sha1_hex(&forge)));
+        assert_eq!(downloads[0].size, Some(forge.len() as i64));
+
+        std::fs::write(&forge_file, &forge).unwrap();
+        assert!(library_downloads("1.20.1-forge-47.3.0").is_empty());
+
+        let downloads = library_downloads("fabric-loader-0.16.14-1.20.1");
+        assert_eq!(downloads.len(), 1);
+        assert_eq!(
+            downloads[0].url,
+            "https://maven.fabricmc.net/net/fabricmc/fabric-loader/0.16.14/fabric-loader-0.16.14.jar"
+        );
+        assert_eq!(
+            downloads[0].path,
+            root_dir.join("libraries/net/fabricmc/fabric-loader/0.16.14/fabric-loader-0.16.14.jar")
+        );
+        assert_eq!(downloads[0].sha1, None);
+    }
+
+    /// Tests that the exported script runs the planned command
+    #[test]
+    fn test_shell_script_runs_command() {
+        let root_dir = temp_dir("launch_plan_script");
+        write_fake_java(&root_dir.join("jdk"), PRINT_COMMAND);
+        let version = vanilla_version_json("1.20.1", json!([]), json!(["-cp", "${classpath}"]));
+        write_version(&root_dir, "1.20.1", &version);
+
+        let game_dir = root_dir.join("instances").join("it's mine");
+        std::fs::create_dir_all(&game_dir).unwrap();
+        let instance = Instance::new(&root_dir, "1.20.1")
+            .with_game_dir(&game_dir)
+            .with_env("GAME_ENV", "a $value");
+        let plan = plan_launch(&instance, &account(), &launch_options(&root_dir)).unwrap();
+
+        let script = root_dir.join("launch.sh");
+        plan.write_shell_script(&script).unwrap();
+        let output = Command::new(&script).output().unwrap();
+        assert!(output.status.success());
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(Path::new(lines[0]), game_dir.canonicalize().unwrap());
+        assert_eq!(lines[1], "a $value");
+        assert_eq!(lines[2..], plan.argv[1..]);
+    }
+
+    /// Tests Quick Play, resolution and demo on feature-gated and legacy versions
+    #[test]
+    fn test_plan_launch_features() {
+        let root_dir = temp_dir("launch_plan_features");
+        write_fake_java(&root_dir.join("jdk"), PRINT_COMMAND);
+
+        let mut modern = vanilla_version_json("1.20.1", json!([]), json!(["-cp", "${classpath}"]));
+        modern["arguments"]["game"] = json!([
+            "--username",
+            "${auth_player_name}",
+            {"rules": [{"action": "allow", "features": {"is_demo_user": true}}], "value": "--demo"},
+            {
+                "rules": [{"action": "allow", "features": {"has_custom_resolution": true}}],
+                "value": ["--width", "${resolution_width}", "--height", "${resolution_height}"]
+            },
+            {
+                "rules": [{"action": "allow", "features": {"is_quick_play_multiplayer": true}}],
+                "value": ["--quickPlayMultiplayer", "${quickPlayMultiplayer}"]
+            },
+            {
+                "rules": [{"action": "allow", "features": {"is_quick_play_realms": true}}],
+                "value": ["--quickPlayRealms", "${quickPlayRealms}"]
+            }
+        ]);
+        write_version(&root_dir, "1.20.1", &modern);
+
+        let mut legacy = vanilla_version_json("1.12.2", json!([]), json!([]));
+        legacy.as_object_mut().unwrap().remove("arguments");
+        legacy["minecraftArguments"] = json!("--username ${auth_player_name}");
+        write_version(&root_dir, "1.12.2", &legacy);
+
+        let features = LaunchFeatures::new()
+            .with_quick_play(QuickPlay::Multiplayer("mc.example.com:25570".to_string()))
+            .with_resolution(1280, 720)
+            .with_demo(true);
+        let options = LaunchOptions {
+            install_files: false,
+            features: features.clone(),
+            ..launch_options(&root_dir)
+        };
+
+        let plan = plan_launch(&Instance::new(&root_dir, "1.20.1"), &account(), &options).unwrap();
+        let main = plan
+            .argv
+            .iter()
+            .position(|arg| arg == "net.minecraft.client.main.Main")
+            .unwrap();
+        assert_eq!(
+            plan.argv[main + 1..],
+            [
+                "--username",
+                "Steve",
+                "--demo",
+                "--width",
+                "1280",
+                "--height",
+                "720",
+                "--quickPlayMultiplayer",
+                "mc.example.com:25570"
+            ]
+        );
+
+        let plan = plan_launch(&Instance::new(&root_dir, "1.12.2"), &account(), &options).unwrap();
+        let main = plan
+            .argv
+            .iter()
+            .position(|arg| arg == "net.minecraft.client.main.Main")
+            .unwrap();
+        assert_eq!(
+            plan.argv[main + 1..],
+            [
+                "--username",
+                "Steve",
+                "--server",
+                "mc.example.com",
+                "--port",
+                "25570",
+                "--width",
+                "1280",
+                "--height",
+                "720",
+                "--demo"
+            ]
+        );
+
+        let options = LaunchOptions {
+            features: features.with_quick_play(QuickPlay::Singleplayer("New World".to_string())),
+            ..options
+        };
+        let error =
+            plan_launch(&Instance::new(&root_dir, "1.12.2"), &account(), &options).unwrap_err();
+        assert!(matches!(error, LaunchError::UnsupportedFeature(_)));
+    }
+
+    /// Tests the launch values of an offline account on modern and legacy versions
+    #[test]
+    fn test_plan_offline_account() {
+        let root_dir = temp_dir("launch_plan_offline");
+        write_fake_java(&root_dir.join("jdk"), PRINT_COMMAND);
+
+        let mut modern = vanilla_version_json("1.20.1", json!([]), json!(["-cp", "${classpath}"]));
+        modern["arguments"]["game"] = json!([
+            "--username",
+            "${auth_player_name}",
+            "--uuid",
+            "${auth_uuid}",
+            "--userType",
+            "${user_type}"
+        ]);
+        write_version(&root_dir, "1.20.1", &modern);
+
+        let mut legacy = vanilla_version_json("1.12.2", json!([]), json!([]));
+        legacy.as_object_mut().unwrap().remove("arguments");
+        legacy["minecraftArguments"] = json!(
+            "--username ${auth_player_name} --uuid ${auth_uuid} --userProperties ${user_properties} --userType ${user_type}"
+        );
+        write_version(&root_dir, "1.12.2", &legacy);
+
+        let account = OfflineAccount::new("Steve").unwrap().to_account();
+        let options = LaunchOptions {
+            install_files: false,
+            ..launch_options(&root_dir)
+        };
+        let game_args = |version_id: &str| {
+            let plan =
+                plan_launch(&Instance::new(&root_dir, version_id), &account, &options).unwrap();
+            let main = plan
+                .argv
+                .iter()
+                .position(|arg| arg == "net.minecraft.client.main.Main")
+                .unwrap();
+            plan.argv[main + 1..].to_vec()
+        };
+
+        assert_eq!(
+            game_args("1.20.1"),
+            [
+                "--username",
+                "Steve",
+                "--uuid",
+                "5627dd98e6be3c21b8a8e92344183641",
+                "--userType",
+                "msa"
+            ]
+        );
+        assert_eq!(
+            game_args("1.12.2"),
+            [
+                "--username",
+                "Steve",
+                "--uuid",
+                "5627dd98e6be3c21b8a8e92344183641",
+                "--userProperties",
+                "{}",
+                "--userType",
+                "legacy"
+            ]
+        );
+    }
+
+    /// Tests that the authlib-injector agent of a Yggdrasil account reaches the JVM
+    #[test]
+    fn test_plan_yggdrasil_account() {
+        let root_dir = temp_dir("launch_plan_yggdrasil");
+        write_fake_java(&root_dir.join("jdk"), PRINT_COMMAND);
+        write_version(
+            &root_dir,
+            "1.20.1",
+            &vanilla_version_json("1.20.1", json!([]), json!(["-cp", "${classpath}"])),
+        );
+
+        let account = YggdrasilAccount {
+            server_url: "https://auth.example.com/api/yggdrasil".to_string(),
+            username: "Steve".to_string(),
+            uuid: "5627dd98e6be3c21b8a8e92344183641".to_string(),
+            access_token: "token".to_string(),
+            client_token: "client".to_string(),
+            metadata: Some("{}".to_string()),
+            agent_jar: root_dir.join("authlib-injector.jar"),
+        };
+        let options = LaunchOptions {
+            install_files: false,
+            ..launch_options(&root_dir)
+        };
+        let plan = plan_launch(
+            &Instance::new(&root_dir, "1.20.1").add_jvm_args(["-Xss2M"]),
+            &account.to_account(),
+            &options,
+        )
+        .unwrap();
+
+        let agent = format!(
+            "-javaagent:{}=https://auth.example.com/api/yggdrasil",
+            root_dir.join("authlib-injector.jar").display()
+        );
+        let position = |arg: &str| plan.argv.iter().position(|a| a == arg).unwrap();
+        assert!(position(&agent) < position("-Xss2M"));
+        assert!(
+            position("-Dauthlibinjector.yggdrasil.prefetched=e30=")
+                < position("net.minecraft.client.main.Main")
+        );
+    }
+}
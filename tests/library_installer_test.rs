@@ -0,0 +1,195 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{MockResponse, MockServer, sha1_hex, temp_dir};
+    use craft_launcher_rust::craft_launcher::core::version::library_installer::library_installer::{
+        LibraryInstallError, LibraryInstaller,
+    };
+    use craft_launcher_rust::craft_launcher::core::version::library_parser::library_parser::LibraryInfo;
+    use craft_launcher_rust::craft_launcher::utils::file_operations::file_utils;
+
+    /// Tests installing a library from the URL declared in the version JSON
+    #[test]
+    fn test_install_from_declared_url() {
+        let server = MockServer::start();
+        let jar = b"declared jar".to_vec();
+        let path = "org/ow2/asm/asm/9.8/asm-9.8.jar";
+        server.add(
+            &format!("/declared/{}", path),
+            MockResponse::ok(jar.clone()),
+        );
+
+        let root_dir = temp_dir("library_installer_declared");
+        let installer = LibraryInstaller::new(&root_dir).with_repositories(Vec::<String>::new());
+        let library = LibraryInfo::Generic {
+            name: "org.ow2.asm:asm:9.8".to_string(),
+            path: Some(path.to_string()),
+            url: Some(server.url(&format!("/declared/{}", path))),
+            sha1: Some(sha1_hex(&jar)),
+            size: jar.len() as i64,
+        };
+
+        let installed = installer
+            .install(&library)
+            .expect("Failed to install library")
+            .expect("Library should have an artifact");
+
+        assert!(installed.verified);
+        assert_eq!(installed.path, root_dir.join("libraries").join(path));
+        assert_eq!(file_utils::read_binary(&installed.path).unwrap(), jar);
+
+        // A second install reuses the verified file without downloading
+        let request_count = server.requests().len();
+        let again = installer.install(&library).unwrap().unwrap();
+        assert!(again.source_url.is_none());
+        assert_eq!(server.requests().len(), request_count);
+    }
+
+    /// Tests falling back to Maven repositories and using the .sha1 sidecar file
+    #[test]
+    fn test_repository_fallback_with_sidecar() {
+        let server = MockServer::start();
+        let jar = b"fabric loader jar".to_vec();
+        let path = "net/fabricmc/fabric-loader/0.16.14/fabric-loader-0.16.14.jar";
+        server.add(&format!("/second/{}", path), MockResponse::ok(jar.clone()));
+        server.add(
+            &format!("/second/{}.sha1", path),
+            MockResponse::ok(sha1_hex(&jar)),
+        );
+
+        let root_dir = temp_dir("library_installer_fallback");
+        let installer = LibraryInstaller::new(&root_dir)
+            .with_repositories(vec![server.url("/first/"), server.url("/second/")]);
+
+        // Fabric style library: only a repository base URL, no hash
+        let library = LibraryInfo::Generic {
+            name: "net.fabricmc:fabric-loader:0.16.14".to_string(),
+            path: None,
+            url: Some(server.url("/declared/")),
+            sha1: None,
+            size: 0,
+        };
+
+        let installed = installer.install(&library).unwrap().unwrap();
+        assert!(installed.verified);
+        assert_eq!(
+            installed.source_url,
+            Some(server.url(&format!("/second/{}", path)))
+        );
+
+        // The declared repository is tried before the configured ones
+        let paths = server.requested_paths();
+        assert_eq!(paths[0], format!("/declared/{}", path));
+        assert_eq!(paths[1], format!("/first/{}", path));
+
+        // The sidecar is stored next to the library for later verification
+        let sidecar = root_dir.join("libraries").join(format!("{}.sha1", path));
+        assert_eq!(file_utils::read_text(&sidecar).unwrap(), sha1_hex(&jar));
+    }
+
+    /// Tests that a corrupted download is rejected
+    #[test]
+    fn test_hash_mismatch() {
+        let server = MockServer::start();
+        let path = "com/example/lib/1.0/lib-1.0.jar";
+        server.add(&format!("/repo/{}", path), MockResponse::ok("tampered"));
+
+        let root_dir = temp_dir("library_installer_mismatch");
+        let installer =
+            LibraryInstaller::new(&root_dir).with_repositories(vec![server.url("/repo")]);
+        let library = LibraryInfo::Generic {
+            name: "com.example:lib:1.0".to_string(),
+            path: None,
+            url: None,
+            sha1: Some(sha1_hex(b"original")),
+            size: 0,
+        };
+
+        match installer.install(&library) {
+            Err(LibraryInstallError::HashMismatch { expected, .. }) => {
+                assert_eq!(expected, sha1_hex(b"original"))
+            }
+            other => panic!("Expected hash mismatch, got {:?}", other),
+        }
+        assert!(!root_dir.join("libraries").join(path).exists());
+    }
+
+    /// Tests that every attempted URL is reported when a library cannot be found
+    #[test]
+    fn test_download_failed_reports_attempts() {
+        let server = MockServer::start();
+        let root_dir = temp_dir("library_installer_missing");
+        let installer = LibraryInstaller::new(&root_dir)
+            .with_repositories(vec![server.url("/a/"), server.url("/b/")]);
+        let library = LibraryInfo::Generic {
+            name: "com.example:missing:1.0".to_string(),
+            path: None,
+            url: None,
+            sha1: None,
+            size: 0,
+        };
+
+        match installer.install_all(&[library]) {
+            Err(LibraryInstallError::DownloadFailed { attempts, .. }) => {
+                assert_eq!(attempts.len(), 2)
+            }
+            other => panic!("Expected download failure, got {:?}", other),
+        }
+    }
+
+    /// Tests that a mirror serving a corrupt file does not block the next one
+    #[test]
+    fn test_hash_mismatch_falls_back() {
+        let server = MockServer::start();
+        let jar = b"original".to_vec();
+        let path = "com/example/lib/1.0/lib-1.0.jar";
+        server.add(&format!("/bad/{}", path), MockResponse::ok("tampered"));
+        server.add(&format!("/good/{}", path), MockResponse::ok(jar.clone()));
+
+        let root_dir = temp_dir("library_installer_mismatch_fallback");
+        let installer = LibraryInstaller::new(&root_dir)
+            .with_repositories(vec![server.url("/bad"), server.url("/good")]);
+        let library = LibraryInfo::Generic {
+            name: "com.example:lib:1.0".to_string(),
+            path: None,
+            url: None,
+            sha1: Some(sha1_hex(&jar)),
+            size: 0,
+        };
+
+        let installed = installer.install(&library).unwrap().unwrap();
+        assert!(installed.verified);
+        assert_eq!(
+            installed.source_url,
+            Some(server.url(&format!("/good/{}", path)))
+        );
+        assert_eq!(file_utils::read_binary(&installed.path).unwrap(), jar);
+    }
+
+    /// Tests that an installed library without any hash is reused as unverified
+    #[test]
+    fn test_existing_unverifiable_library() {
+        let server = MockServer::start();
+        let root_dir = temp_dir("library_installer_unverified");
+        let path = "com/example/local/1.0/local-1.0.jar";
+        let dest = root_dir.join("libraries").join(path);
+        std::fs::create_dir_all(dest.parent().unwrap()).unwrap();
+        std::fs::write(&dest, "local jar").unwrap();
+
+        let installer =
+            LibraryInstaller::new(&root_dir).with_repositories(vec![server.url("/repo")]);
+        let library = LibraryInfo::Generic {
+            name: "com.example:local:1.0".to_string(),
+            path: None,
+            url: None,
+            sha1: None,
+            size: 0,
+        };
+
+        let installed = installer.install(&library).unwrap().unwrap();
+        assert!(!installed.verified);
+        assert_eq!(installed.sha1, sha1_hex(b"local jar"));
+        assert!(server.requests().is_empty());
+    }
+}
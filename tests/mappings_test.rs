@@ -0,0 +1,79 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{
+        MockResponse, MockServer, sha1_hex, temp_dir, vanilla_version_json, write_version,
+    };
+    use craft_launcher_rust::craft_launcher::core::mappings::mappings::{
+        MappingsCache, MappingsError,
+    };
+    use craft_launcher_rust::craft_launcher::core::version::library_conflict::library_conflict::ConflictPolicy;
+    use craft_launcher_rust::craft_launcher::core::version::version_resolver::version_resolver::resolve_version;
+    use serde_json::json;
+
+    const MAPPINGS: &str = "net.minecraft.client.Minecraft -> enn:
+    100:110:void run():700:710 -> e
+";
+
+    /// Tests downloading mappings once and deobfuscating a crash report with them
+    #[test]
+    fn test_cache_downloads_once() {
+        let server = MockServer::start();
+        let root_dir = temp_dir("mappings_cache");
+        server.add("/client.txt", MockResponse::ok(MAPPINGS));
+
+        let mut version = vanilla_version_json("1.20.1", json!([]), json!([]));
+        version["downloads"]["client_mappings"] = json!({
+            "sha1": sha1_hex(MAPPINGS.as_bytes()),
+            "size": MAPPINGS.len(),
+            "url": server.url("/client.txt")
+        });
+        write_version(&root_dir, "1.20.1", &version);
+        let version = resolve_version(&root_dir, "1.20.1", ConflictPolicy::ChildWins).unwrap();
+
+        let cache = MappingsCache::new(&root_dir);
+        let deobfuscator = cache.deobfuscator(&version).unwrap();
+        assert_eq!(
+            deobfuscator.deobfuscate("\tat enn.e(SourceFile:105)"),
+            "\tat net.minecraft.client.Minecraft.run(Minecraft.java:705)"
+        );
+        cache.load(&version).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(cache.mappings_path(&version)).unwrap(),
+            MAPPINGS
+        );
+
+        // A new cache reads the stored file instead of downloading again
+        MappingsCache::new(&root_dir).load(&version).unwrap();
+        assert_eq!(server.requested_paths(), vec!["/client.txt".to_string()]);
+    }
+
+    /// Tests rejecting mappings with the wrong hash and versions without mappings
+    #[test]
+    fn test_cache_errors() {
+        let server = MockServer::start();
+        let root_dir = temp_dir("mappings_errors");
+        server.add("/client.txt", MockResponse::ok(MAPPINGS));
+
+        let mut version = vanilla_version_json("1.20.1", json!([]), json!([]));
+        write_version(&root_dir, "1.20.1", &version);
+        let resolved = resolve_version(&root_dir, "1.20.1", ConflictPolicy::ChildWins).unwrap();
+        assert!(matches!(
+            MappingsCache::new(&root_dir).load(&resolved),
+            Err(MappingsError::NoMappings(_))
+        ));
+
+        version["downloads"]["client_mappings"] = json!({
+            "sha1": "0000000000000000000000000000000000000000",
+            "size": MAPPINGS.len(),
+            "url": server.url("/client.txt")
+        });
+        write_version(&root_dir, "1.20.1", &version);
+        let resolved = resolve_version(&root_dir, "1.20.1", ConflictPolicy::ChildWins).unwrap();
+        assert!(matches!(
+            MappingsCache::new(&root_dir).load(&resolved),
+            Err(MappingsError::HashMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,260 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{MockResponse, MockServer};
+    use craft_launcher_rust::craft_launcher::core::account::microsoft_account::microsoft_account::{
+        DeviceCodePoll, MicrosoftAuthError, MicrosoftAuthenticator, MicrosoftEndpoints, MicrosoftToken,
+    };
+    use serde_json::json;
+
+    fn endpoints(server: &MockServer) -> MicrosoftEndpoints {
+        MicrosoftEndpoints {
+            device_code_url: server.url("/oauth/devicecode"),
+            token_url: server.url("/oauth/token"),
+            xbox_live_url: server.url("/xbl"),
+            xsts_url: server.url("/xsts"),
+            minecraft_login_url: server.url("/mc/login"),
+            entitlements_url: server.url("/mc/entitlements"),
+            profile_url: server.url("/mc/profile"),
+        }
+    }
+
+    /// Registers successful responses for every service after the Microsoft token
+    fn add_services(server: &MockServer) {
+        server.add(
+            "/xbl",
+            MockResponse::json(&json!({
+                "Token": "xbl-token",
+                "DisplayClaims": {"xui": [{"uhs": "user-hash"}]}
+            })),
+        );
+        server.add(
+            "/xsts",
+            MockResponse::json(&json!({
+                "Token": "xsts-token",
+                "DisplayClaims": {"xui": [{"uhs": "user-hash", "xid": "2535400000000000"}]}
+            })),
+        );
+        server.add(
+            "/mc/login",
+            MockResponse::json(&json!({"access_token": "mc-token", "expires_in": 86400})),
+        );
+        server.add(
+            "/mc/entitlements",
+            MockResponse::json(&json!({
+                "items": [{"name": "product_minecraft"}, {"name": "game_minecraft"}]
+            })),
+        );
+        server.add(
+            "/mc/profile",
+            MockResponse::json(&json!({
+                "id": "069a79f444e94726a5befca90e38aaf5",
+                "name": "Notch"
+            })),
+        );
+    }
+
+    fn token() -> MicrosoftToken {
+        MicrosoftToken {
+            access_token: "ms-token".to_string(),
+            refresh_token: Some("refresh-1".to_string()),
+            expires_in: 3600,
+        }
+    }
+
+    /// Tests the device code flow through to the Minecraft profile
+    #[test]
+    fn test_device_code_flow() {
+        let server = MockServer::start();
+        add_services(&server);
+        server.add(
+            "/oauth/devicecode",
+            MockResponse::json(&json!({
+                "device_code": "device-1",
+                "user_code": "ABCD-EFGH",
+                "verification_uri": "https://www.microsoft.com/link",
+                "expires_in": 900,
+                "interval": 0,
+                "message": "Enter ABCD-EFGH"
+            })),
+        );
+        server.add(
+            "/oauth/token",
+            MockResponse::status_json(400, &json!({"error": "authorization_pending"})),
+        );
+        let authenticator =
+            MicrosoftAuthenticator::new("client-1").with_endpoints(endpoints(&server));
+
+        let code = authenticator.request_device_code().unwrap();
+        assert_eq!(code.user_code, "ABCD-EFGH");
+        assert_eq!(
+            authenticator.poll_device_code(&code).unwrap(),
+            DeviceCodePoll::Pending
+        );
+        server.add(
+            "/oauth/token",
+            MockResponse::status_json(400, &json!({"error": "slow_down"})),
+        );
+        assert_eq!(
+            authenticator.poll_device_code(&code).unwrap(),
+            DeviceCodePoll::SlowDown
+        );
+
+        server.add(
+            "/oauth/token",
+            MockResponse::json(&json!({
+                "access_token": "ms-token",
+                "refresh_token": "refresh-1",
+                "expires_in": 3600
+            })),
+        );
+        let mut shown = None;
+        let account = authenticator
+            .authenticate(|code| shown = Some(code.user_code.clone()))
+            .unwrap();
+        assert_eq!(shown.as_deref(), Some("ABCD-EFGH"));
+        assert_eq!(account.username, "Notch");
+        assert_eq!(account.uuid, "069a79f444e94726a5befca90e38aaf5");
+        assert_eq!(account.access_token, "mc-token");
+        assert_eq!(account.refresh_token.as_deref(), Some("refresh-1"));
+        assert!(!account.is_expired());
+
+        let launch = account.to_account();
+        assert_eq!(launch.user_type, "msa");
+        assert_eq!(launch.xuid.as_deref(), Some("2535400000000000"));
+        assert_eq!(launch.client_id.as_deref(), Some("client-1"));
+
+        let requests = server.requests();
+        let body = |path: &str| {
+            requests
+                .iter()
+                .rev()
+                .find(|request| request.path == path)
+                .unwrap()
+                .body
+                .clone()
+        };
+        assert!(body("/oauth/token").contains("device_code=device-1"));
+        assert!(body("/xbl").contains("\"RpsTicket\":\"d=ms-token\""));
+        assert!(body("/xsts").contains("\"UserTokens\":[\"xbl-token\"]"));
+        assert!(body("/mc/login").contains("XBL3.0 x=user-hash;xsts-token"));
+        let profile = requests
+            .iter()
+            .find(|request| request.path == "/mc/profile")
+            .unwrap();
+        assert!(
+            profile
+                .headers
+                .contains(&("authorization".to_string(), "Bearer mc-token".to_string()))
+        );
+    }
+
+    /// Tests refreshing an account and rejected refresh tokens
+    #[test]
+    fn test_refresh() {
+        let server = MockServer::start();
+        add_services(&server);
+        server.add(
+            "/oauth/token",
+            MockResponse::json(&json!({"access_token": "ms-token-2", "expires_in": 3600})),
+        );
+        let authenticator =
+            MicrosoftAuthenticator::new("client-1").with_endpoints(endpoints(&server));
+
+        let account = authenticator.login(&token()).unwrap();
+        let refreshed = authenticator.refresh(&account).unwrap();
+        assert_eq!(refreshed.refresh_token.as_deref(), Some("refresh-1"));
+        assert!(
+            server
+                .requests()
+                .iter()
+                .any(|request| request.body.contains("grant_type=refresh_token")
+                    && request.body.contains("refresh_token=refresh-1"))
+        );
+
+        server.add(
+            "/oauth/token",
+            MockResponse::status_json(
+                400,
+                &json!({"error": "invalid_grant", "error_description": "expired"}),
+            ),
+        );
+        assert!(matches!(
+            authenticator.refresh(&account),
+            Err(MicrosoftAuthError::InvalidGrant(_))
+        ));
+    }
+
+    /// Tests that account problems are reported as distinct errors
+    #[test]
+    fn test_account_errors() {
+        let server = MockServer::start();
+        let authenticator =
+            MicrosoftAuthenticator::new("client-1").with_endpoints(endpoints(&server));
+        let xsts_error = |code: u64| {
+            add_services(&server);
+            server.add(
+                "/xsts",
+                MockResponse::status_json(401, &json!({"Identity": "0", "XErr": code})),
+            );
+            authenticator.login(&token()).unwrap_err()
+        };
+
+        assert!(matches!(
+            xsts_error(2148916233),
+            MicrosoftAuthError::NoXboxProfile
+        ));
+        assert!(matches!(
+            xsts_error(2148916238),
+            MicrosoftAuthError::ChildAccount
+        ));
+        assert!(matches!(
+            xsts_error(2148916236),
+            MicrosoftAuthError::AdultVerificationRequired
+        ));
+        assert!(matches!(
+            xsts_error(2148916227),
+            MicrosoftAuthError::XboxError(2148916227)
+        ));
+
+        add_services(&server);
+        server.add(
+            "/mc/entitlements",
+            MockResponse::json(&json!({"items": []})),
+        );
+        assert!(matches!(
+            authenticator.login(&token()),
+            Err(MicrosoftAuthError::NotOwned)
+        ));
+
+        add_services(&server);
+        server.add(
+            "/mc/profile",
+            MockResponse::status_json(404, &json!({"error": "NOT_FOUND"})),
+        );
+        assert!(matches!(
+            authenticator.login(&token()),
+            Err(MicrosoftAuthError::NoProfile)
+        ));
+
+        server.add(
+            "/oauth/token",
+            MockResponse::status_json(400, &json!({"error": "authorization_declined"})),
+        );
+        server.add(
+            "/oauth/devicecode",
+            MockResponse::json(&json!({
+                "device_code": "device-1",
+                "user_code": "ABCD-EFGH",
+                "verification_uri": "https://www.microsoft.com/link",
+                "expires_in": 900,
+                "interval": 0
+            })),
+        );
+        assert!(matches!(
+            authenticator.authenticate(|_| {}),
+            Err(MicrosoftAuthError::AuthorizationDeclined)
+        ));
+    }
+}
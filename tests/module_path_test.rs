@@ -0,0 +1,158 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{artifact_library, temp_dir, vanilla_version_json, write_version};
+    use craft_launcher_rust::craft_launcher::core::version::classpath::classpath::{
+        ClasspathOptions, build_classpath,
+    };
+    use craft_launcher_rust::craft_launcher::core::version::launch_arguments::launch_arguments::{
+        ArgumentPlaceholders, flatten_arguments, take_module_path,
+    };
+    use craft_launcher_rust::craft_launcher::core::version::library_conflict::library_conflict::ConflictPolicy;
+    use craft_launcher_rust::craft_launcher::core::version::rules::rules::{Platform, RuleContext};
+    use craft_launcher_rust::craft_launcher::core::version::version_resolver::version_resolver::resolve_version;
+    use craft_launcher_rust::craft_launcher::java::arguments_builder::arguments_builder::java_args;
+    use serde_json::json;
+
+    /// Writes 1.21.1 and a NeoForge version using BootstrapLauncher on the module path
+    fn write_neoforge(root_dir: &std::path::Path) {
+        write_version(
+            root_dir,
+            "1.21.1",
+            &vanilla_version_json(
+                "1.21.1",
+                json!([artifact_library(
+                    "org.ow2.asm:asm:9.7",
+                    "org/ow2/asm/asm/9.7/asm-9.7.jar"
+                )]),
+                json!(["-cp", "${classpath}"]),
+            ),
+        );
+        write_version(
+            root_dir,
+            "neoforge-21.1.77",
+            &json!({
+                "id": "neoforge-21.1.77",
+                "time": "2024-11-20T00:00:00",
+                "releaseTime": "2024-11-20T00:00:00",
+                "type": "release",
+                "mainClass": "cpw.mods.bootstraplauncher.BootstrapLauncher",
+                "inheritsFrom": "1.21.1",
+                "arguments": {
+                    "game": ["--fml.neoForgeVersion", "21.1.77"],
+                    "jvm": [
+                        "-Djava.net.preferIPv6Addresses=system",
+                        "-DignoreList=securejarhandler,asm,bootstraplauncher,${version_name}.jar",
+                        "-DlibraryDirectory=${library_directory}",
+                        "-p",
+                        "${library_directory}/cpw/mods/securejarhandler/3.0.8/securejarhandler-3.0.8.jar${classpath_separator}${library_directory}/cpw/mods/bootstraplauncher/2.0.2/bootstraplauncher-2.0.2.jar",
+                        "--add-modules",
+                        "ALL-MODULE-PATH",
+                        "--add-opens",
+                        "java.base/java.util.jar=cpw.mods.securejarhandler"
+                    ]
+                },
+                "libraries": [
+                    artifact_library(
+                        "cpw.mods:securejarhandler:3.0.8",
+                        "cpw/mods/securejarhandler/3.0.8/securejarhandler-3.0.8.jar"
+                    ),
+                    artifact_library(
+                        "cpw.mods:bootstraplauncher:2.0.2",
+                        "cpw/mods/bootstraplauncher/2.0.2/bootstraplauncher-2.0.2.jar"
+                    ),
+                    artifact_library(
+                        "net.neoforged:neoforge:21.1.77:universal",
+                        "net/neoforged/neoforge/21.1.77/neoforge-21.1.77-universal.jar"
+                    )
+                ]
+            }),
+        );
+    }
+
+    /// Tests building a BootstrapLauncher command with the module path owned by the builder
+    #[test]
+    fn test_neoforge_module_path() {
+        let root_dir = temp_dir("module_path_neoforge");
+        write_neoforge(&root_dir);
+        let libraries = root_dir.join("libraries");
+
+        let version =
+            resolve_version(&root_dir, "neoforge-21.1.77", ConflictPolicy::ChildWins).unwrap();
+        let context = RuleContext::new(Platform::new("linux", "x86_64"));
+        let classpath = build_classpath(
+            &version,
+            &root_dir,
+            &ClasspathOptions {
+                context: context.clone(),
+                ..ClasspathOptions::default()
+            },
+        )
+        .unwrap();
+
+        let placeholders =
+            ArgumentPlaceholders::for_version(&version, &root_dir).with_classpath(&classpath);
+        let mut jvm_args =
+            placeholders.substitute_all(&flatten_arguments(&version.jvm_arguments, &context));
+        let module_path = take_module_path(&mut jvm_args);
+
+        assert_eq!(
+            module_path,
+            vec![
+                libraries.join("cpw/mods/securejarhandler/3.0.8/securejarhandler-3.0.8.jar"),
+                libraries.join("cpw/mods/bootstraplauncher/2.0.2/bootstraplauncher-2.0.2.jar"),
+            ]
+        );
+        assert!(jvm_args.contains(&format!("-DlibraryDirectory={}", libraries.display())));
+        assert!(jvm_args.contains(
+            &"-DignoreList=securejarhandler,asm,bootstraplauncher,neoforge-21.1.77.jar".to_string()
+        ));
+
+        // The vanilla "-cp ${classpath}" is dropped in favour of the builder's classpath
+        let jvm_args: Vec<String> = jvm_args
+            .into_iter()
+            .filter(|arg| {
+                arg != "-cp" && !placeholders.get("classpath").is_some_and(|cp| arg == cp)
+            })
+            .collect();
+
+        let command = java_args()
+            .check_exists(false)
+            .add_jvm_args(jvm_args)
+            .add_module_path_entries(&module_path)
+            .add_classpath_entries(&classpath)
+            .with_main_class(version.main_class.clone())
+            .build();
+
+        let p = command.iter().position(|arg| arg == "-p").unwrap();
+        let cp = command.iter().position(|arg| arg == "-cp").unwrap();
+        assert!(p < cp);
+        assert!(command[p + 1].contains("bootstraplauncher-2.0.2.jar"));
+        assert!(command[cp + 1].contains("neoforge-21.1.77-universal.jar"));
+        assert!(!command[cp + 1].contains("bootstraplauncher"));
+        assert_eq!(
+            command.last().unwrap(),
+            "cpw.mods.bootstraplauncher.BootstrapLauncher"
+        );
+        assert_eq!(command.iter().filter(|arg| *arg == "-cp").count(), 1);
+    }
+
+    /// Tests the `${modulePath}` placeholder used by some installers
+    #[test]
+    fn test_module_path_placeholder() {
+        let placeholders = ArgumentPlaceholders::new().with_module_path(&[
+            std::path::PathBuf::from("a.jar"),
+            std::path::PathBuf::from("b.jar"),
+        ]);
+        let mut jvm_args = placeholders.substitute_all(&[
+            "-p".to_string(),
+            "${modulePath}".to_string(),
+            "--add-modules".to_string(),
+            "ALL-MODULE-PATH".to_string(),
+        ]);
+
+        assert_eq!(take_module_path(&mut jvm_args).len(), 2);
+        assert_eq!(jvm_args, vec!["--add-modules", "ALL-MODULE-PATH"]);
+    }
+}
@@ -0,0 +1,244 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{
+        MockResponse, MockServer, jar_bytes, sha1_hex, temp_dir, vanilla_version_json,
+        write_version,
+    };
+    use craft_launcher_rust::craft_launcher::core::version::library_parser::library_parser::LibraryInfo;
+    use craft_launcher_rust::craft_launcher::core::version::rules::rules::{Platform, RuleContext};
+    use craft_launcher_rust::craft_launcher::java::library_extractor::library_extractor::{
+        extract_native_libraries_for, extract_native_libraries_from, prune_native_directories,
+    };
+    use serde_json::json;
+    use std::io::Write;
+    use std::path::Path;
+    use std::time::{Duration, SystemTime};
+
+    const NATIVE_PATH: &str = "org/lwjgl/lwjgl/3.3.3/lwjgl-3.3.3-natives-linux.jar";
+
+    fn linux() -> RuleContext {
+        RuleContext::new(Platform::new("linux", "x86_64"))
+    }
+
+    /// Writes a native jar containing a shared library and a file that must be skipped
+    fn write_native_jar(root_dir: &Path, content: &[u8]) {
+        let path = root_dir.join("libraries").join(NATIVE_PATH);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file("linux/x64/org/lwjgl/liblwjgl.so", options)
+            .unwrap();
+        zip.write_all(content).unwrap();
+        zip.start_file("META-INF/MANIFEST.MF", options).unwrap();
+        zip.write_all(b"Manifest-Version: 1.0").unwrap();
+        zip.start_file("org/lwjgl/Version.class", options).unwrap();
+        zip.write_all(b"class").unwrap();
+        zip.finish().unwrap();
+    }
+
+    fn write_version_with_natives(root_dir: &Path, id: &str) {
+        write_version_with_native_download(
+            root_dir,
+            id,
+            "http://localhost/natives.jar",
+            "0000000000000000000000000000000000000000",
+        );
+    }
+
+    fn write_version_with_native_download(root_dir: &Path, id: &str, url: &str, sha1: &str) {
+        let libraries = json!([{
+            "name": "org.lwjgl:lwjgl:3.3.3:natives-linux",
+            "downloads": {
+                "artifact": {
+                    "path": NATIVE_PATH,
+                    "sha1": sha1,
+                    "size": 1,
+                    "url": url
+                }
+            },
+            "rules": [{"action": "allow", "os": {"name": "linux"}}]
+        }]);
+        write_version(
+            root_dir,
+            id,
+            &vanilla_version_json(id, libraries, json!([])),
+        );
+    }
+
+    /// Tests that the directory is keyed by content and reused without a debug zip
+    #[test]
+    fn test_extraction_is_reused() {
+        let root_dir = temp_dir("native_cache_reuse");
+        write_native_jar(&root_dir, b"native v1");
+        write_version_with_natives(&root_dir, "1.20.1");
+
+        let first = extract_native_libraries_for(&root_dir, "1.20.1", &linux()).unwrap();
+        assert_eq!(
+            std::fs::read(first.join("liblwjgl.so")).unwrap(),
+            b"native v1"
+        );
+        assert!(!first.join("Version.class").exists());
+        assert!(!first.join("MANIFEST.MF").exists());
+
+        // A file placed in the directory survives, so nothing was re-extracted
+        std::fs::write(first.join("sentinel"), b"").unwrap();
+        let second = extract_native_libraries_for(&root_dir, "1.20.1", &linux()).unwrap();
+        assert_eq!(first, second);
+        assert!(second.join("sentinel").exists());
+
+        let zips: Vec<_> = std::fs::read_dir(root_dir.join("bin"))
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().ends_with(".zip"))
+            .collect();
+        assert!(zips.is_empty());
+
+        // Different jar content produces a different directory
+        write_native_jar(&root_dir, b"native v2");
+        let third = extract_native_libraries_for(&root_dir, "1.20.1", &linux()).unwrap();
+        assert_ne!(first, third);
+        assert_eq!(
+            std::fs::read(third.join("liblwjgl.so")).unwrap(),
+            b"native v2"
+        );
+    }
+
+    /// Tests that unused directories, temporary directories and debug zips are pruned
+    #[cfg(unix)]
+    #[test]
+    fn test_prune_unused_directories() {
+        let root_dir = temp_dir("native_cache_prune");
+        write_native_jar(&root_dir, b"native v1");
+        write_version_with_natives(&root_dir, "1.20.1");
+
+        let old = extract_native_libraries_for(&root_dir, "1.20.1", &linux()).unwrap();
+        write_native_jar(&root_dir, b"native v2");
+        let current = extract_native_libraries_for(&root_dir, "1.20.1", &linux()).unwrap();
+
+        let bin_dir = root_dir.join("bin");
+        let stale_temp = bin_dir.join(format!("{}.tmp-999999-1", "a".repeat(64)));
+        std::fs::create_dir_all(&stale_temp).unwrap();
+        std::fs::write(bin_dir.join("native-libs-1.20.1.zip"), b"zip").unwrap();
+        std::fs::write(bin_dir.join("keep.txt"), b"unrelated").unwrap();
+
+        // A temporary directory of another running process is still in use
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let live_temp = bin_dir.join(format!("{}.tmp-{}-1", "b".repeat(64), child.id()));
+        std::fs::create_dir_all(&live_temp).unwrap();
+        let expired_temp = bin_dir.join(format!("{}.tmp-{}-2", "c".repeat(64), child.id()));
+        std::fs::create_dir_all(&expired_temp).unwrap();
+        std::fs::File::open(&expired_temp)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(2 * 24 * 60 * 60))
+            .unwrap();
+
+        let removed = prune_native_directories(&root_dir, &linux()).unwrap();
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        assert_eq!(removed.len(), 4);
+        assert!(live_temp.exists());
+        assert!(!expired_temp.exists());
+        assert!(!old.exists());
+        assert!(!stale_temp.exists());
+        assert!(!bin_dir.join("native-libs-1.20.1.zip").exists());
+        assert!(current.exists());
+        assert!(bin_dir.join("keep.txt").exists());
+    }
+
+    /// Tests that missing native jars are downloaded only when the hash matches
+    #[test]
+    fn test_native_download_is_verified() {
+        let root_dir = temp_dir("native_cache_download");
+        let jar = jar_bytes(&[("linux/x64/org/lwjgl/liblwjgl.so", b"downloaded")]);
+        let server = MockServer::start();
+        server.add("/natives.jar", MockResponse::ok(b"<html>".to_vec()));
+        write_version_with_native_download(
+            &root_dir,
+            "1.20.1",
+            &server.url("/natives.jar"),
+            &sha1_hex(&jar),
+        );
+        let jar_path = root_dir.join("libraries").join(NATIVE_PATH);
+
+        assert!(extract_native_libraries_for(&root_dir, "1.20.1", &linux()).is_err());
+        assert!(!jar_path.exists());
+        assert_eq!(
+            std::fs::read_dir(jar_path.parent().unwrap())
+                .map(|entries| entries.count())
+                .unwrap_or(0),
+            0
+        );
+
+        server.add("/natives.jar", MockResponse::ok(jar.clone()));
+        let dir = extract_native_libraries_for(&root_dir, "1.20.1", &linux()).unwrap();
+        assert_eq!(std::fs::read(&jar_path).unwrap(), jar);
+        assert_eq!(
+            std::fs::read(dir.join("liblwjgl.so")).unwrap(),
+            b"downloaded"
+        );
+    }
+
+    /// Tests extracting the natives of libraries resolved by the caller
+    #[test]
+    fn test_extract_from_libraries() {
+        let root_dir = temp_dir("native_cache_libraries");
+        write_native_jar(&root_dir, b"resolved");
+        let libraries = vec![LibraryInfo::Base(
+            serde_json::from_value(json!({
+                "name": "org.lwjgl:lwjgl:3.3.3:natives-linux",
+                "downloads": {
+                    "artifact": {
+                        "path": NATIVE_PATH,
+                        "sha1": "0000000000000000000000000000000000000000",
+                        "size": 1,
+                        "url": "http://localhost/natives.jar"
+                    }
+                }
+            }))
+            .unwrap(),
+        )];
+
+        // No version JSON exists, so nothing is resolved again
+        let dir = extract_native_libraries_from(&root_dir, &libraries, &linux()).unwrap();
+        assert_eq!(std::fs::read(dir.join("liblwjgl.so")).unwrap(), b"resolved");
+        assert!(extract_native_libraries_for(&root_dir, "1.20.1", &linux()).is_err());
+    }
+
+    /// Tests that concurrent launches of the same version agree on one directory
+    #[test]
+    fn test_concurrent_extraction() {
+        let root_dir = temp_dir("native_cache_concurrent");
+        write_native_jar(&root_dir, b"native");
+        write_version_with_natives(&root_dir, "1.20.1");
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let root_dir = root_dir.clone();
+                std::thread::spawn(move || {
+                    extract_native_libraries_for(&root_dir, "1.20.1", &linux()).unwrap()
+                })
+            })
+            .collect();
+        let dirs: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+        assert!(dirs.iter().all(|dir| dir == &dirs[0]));
+        assert_eq!(
+            std::fs::read(dirs[0].join("liblwjgl.so")).unwrap(),
+            b"native"
+        );
+
+        let leftovers: Vec<_> = std::fs::read_dir(root_dir.join("bin"))
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().contains(".tmp-"))
+            .collect();
+        assert!(leftovers.is_empty());
+    }
+}
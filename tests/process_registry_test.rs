@@ -0,0 +1,85 @@
+mod common;
+
+#[cfg(all(test, unix))]
+mod tests {
+    use crate::common::{account, temp_dir};
+    use craft_launcher_rust::craft_launcher::core::process_registry::process_registry::{
+        ProcessRegistry, RegistryError, RunningState,
+    };
+    use std::process::Command;
+    use std::time::Duration;
+
+    /// Tests that an instance can only be locked once
+    #[test]
+    fn test_acquire_twice() {
+        let root_dir = temp_dir("registry_acquire");
+        let game_dir = root_dir.join("instances").join("survival");
+        std::fs::create_dir_all(&game_dir).unwrap();
+        let registry = ProcessRegistry::new(&root_dir);
+
+        let lock = registry.acquire(&game_dir, "1.20.1", &account()).unwrap();
+        assert_eq!(lock.entry().state, RunningState::Starting);
+        assert_eq!(lock.entry().pid, std::process::id());
+
+        // A second registry stands in for another launcher process
+        let other = ProcessRegistry::new(&root_dir);
+        match other.acquire(&game_dir, "1.20.1", &account()) {
+            Err(RegistryError::AlreadyRunning(running)) => {
+                assert_eq!(running.username, "Steve");
+                assert_eq!(running.instance, game_dir.canonicalize().unwrap());
+            }
+            other => panic!("expected AlreadyRunning, got {:?}", other.map(|_| ())),
+        }
+        assert_eq!(other.list().unwrap().len(), 1);
+
+        // Dropping a lock that never started a game releases the instance
+        drop(lock);
+        assert!(registry.list().unwrap().is_empty());
+        assert!(registry.acquire(&game_dir, "1.20.1", &account()).is_ok());
+    }
+
+    /// Tests that a lock of an exited process is stale
+    #[test]
+    fn test_stale_lock() {
+        let root_dir = temp_dir("registry_stale");
+        let game_dir = root_dir.join("game");
+        std::fs::create_dir_all(&game_dir).unwrap();
+        let registry = ProcessRegistry::new(&root_dir);
+
+        let mut exited = Command::new("true").spawn().unwrap();
+        let mut lock = registry.acquire(&game_dir, "1.20.1", &account()).unwrap();
+        lock.set_running(exited.id()).unwrap();
+        exited.wait().unwrap();
+        std::mem::forget(lock);
+
+        assert!(registry.get(&game_dir).unwrap().is_none());
+        assert!(!registry.lock_path(&game_dir).exists());
+        assert!(registry.acquire(&game_dir, "1.20.1", &account()).is_ok());
+    }
+
+    /// Tests attaching to and killing a running game
+    #[test]
+    fn test_attach_and_kill() {
+        let root_dir = temp_dir("registry_kill");
+        let game_dir = root_dir.join("game");
+        std::fs::create_dir_all(&game_dir).unwrap();
+        let registry = ProcessRegistry::new(&root_dir);
+
+        let mut game = Command::new("sleep").arg("30").spawn().unwrap();
+        let mut lock = registry.acquire(&game_dir, "1.20.1", &account()).unwrap();
+        lock.set_running(game.id()).unwrap();
+
+        let attached = registry.attach(&game_dir).unwrap().unwrap();
+        assert_eq!(attached.entry().state, RunningState::Running);
+        assert_eq!(attached.entry().pid, game.id());
+        assert!(attached.is_running());
+        assert!(!attached.wait(Some(Duration::from_millis(200))));
+
+        // Reap the child in the background so it does not linger as a zombie
+        let reaper = std::thread::spawn(move || game.wait().unwrap());
+        assert!(registry.kill(&game_dir, Duration::from_secs(5)).unwrap());
+        assert!(!reaper.join().unwrap().success());
+        assert!(registry.get(&game_dir).unwrap().is_none());
+        assert!(!registry.kill(&game_dir, Duration::from_secs(1)).unwrap());
+    }
+}
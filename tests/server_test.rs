@@ -0,0 +1,213 @@
+mod common;
+
+#[cfg(all(test, unix))]
+mod tests {
+    use crate::common::{
+        MockResponse, MockServer, jar_bytes, sha1_hex, temp_dir, vanilla_version_json,
+        write_script, write_version,
+    };
+    use craft_launcher_rust::craft_launcher::core::game_output::game_output::LogLevel;
+    use craft_launcher_rust::craft_launcher::core::server::server::{
+        ServerEntryPoint, ServerError, ServerInstance, ServerKind, ServerLauncher, ServerOptions,
+        ServerProperties,
+    };
+    use serde_json::json;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    /// Tests downloading server.jar, the EULA and seeding server.properties
+    #[test]
+    fn test_install_vanilla_server() {
+        let server = MockServer::start();
+        let root_dir = temp_dir("server_install");
+        let server_dir = root_dir.join("servers/survival");
+
+        let jar = b"server jar".to_vec();
+        server.add("/server.jar", MockResponse::ok(jar.clone()));
+        server.add("/corrupt.jar", MockResponse::ok(b"corrupt".to_vec()));
+        let mut version = vanilla_version_json("1.20.1", json!([]), json!([]));
+        version["downloads"]["server"]["sha1"] = json!(sha1_hex(&jar));
+        version["downloads"]["server"]["url"] = json!(server.url("/server.jar"));
+        write_version(&root_dir, "1.20.1", &version);
+        version["downloads"]["server"]["url"] = json!(server.url("/corrupt.jar"));
+        write_version(&root_dir, "1.20.2", &version);
+
+        let instance = ServerInstance::new(&root_dir, &server_dir);
+        let path = instance.install_vanilla("1.20.1").unwrap();
+        assert_eq!(path, server_dir.join("server.jar"));
+        assert_eq!(std::fs::read(&path).unwrap(), jar);
+        assert!(matches!(
+            ServerInstance::new(&root_dir, &root_dir.join("servers/other"))
+                .install_vanilla("1.20.2"),
+            Err(ServerError::HashMismatch { .. })
+        ));
+
+        assert!(!instance.eula_accepted());
+        assert!(matches!(
+            instance.launch(&ServerOptions::default()),
+            Err(ServerError::EulaNotAccepted(_))
+        ));
+        instance.accept_eula().unwrap();
+        assert!(instance.eula_accepted());
+
+        std::fs::write(instance.properties_path(), "server-port=25570").unwrap();
+        let properties = instance
+            .seed_properties(
+                &ServerProperties::new()
+                    .with("server-port", "25565")
+                    .with("motd", "Team server"),
+            )
+            .unwrap();
+        assert_eq!(properties.get("server-port"), Some("25570"));
+        assert_eq!(properties.get("motd"), Some("Team server"));
+        assert_eq!(
+            std::fs::read_to_string(instance.properties_path()).unwrap(),
+            "server-port=25570\nmotd=Team server\n"
+        );
+    }
+
+    /// Tests the console of a running server and stopping it gracefully
+    #[test]
+    fn test_server_console_and_stop() {
+        let root_dir = temp_dir("server_console");
+        let server_dir = root_dir.join("server");
+        std::fs::create_dir_all(&server_dir).unwrap();
+        std::fs::write(server_dir.join("server.jar"), "jar").unwrap();
+        let java = write_script(
+            &root_dir.join("jdk/bin/java"),
+            r#"echo "[12:00:00] [Server thread/INFO]: Starting with $*"
+while read line; do
+  echo "[12:00:01] [Server thread/INFO]: Received $line"
+  if [ "$line" = stop ]; then echo "[12:00:02] [Server thread/WARN]: Stopping"; exit 0; fi
+done
+exit 1"#,
+        );
+
+        let instance = ServerInstance::new(&root_dir, &server_dir);
+        instance.accept_eula().unwrap();
+        let options = ServerOptions {
+            java,
+            ..ServerOptions::default()
+        };
+        let mut process = instance.launch(&options).unwrap();
+        assert_eq!(process.launcher().kind, ServerKind::Vanilla);
+
+        let output = process.output();
+        process.send_command("say hello").unwrap();
+        assert!(process.stop(Duration::from_secs(10)).unwrap().success());
+
+        let records: Vec<_> = output.iter().collect();
+        let messages: Vec<_> = records
+            .iter()
+            .map(|record| record.message.as_str())
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "Starting with -jar server.jar nogui",
+                "Received say hello",
+                "Received stop",
+                "Stopping"
+            ]
+        );
+        assert_eq!(records[3].level, Some(LogLevel::Warn));
+        assert_eq!(records[3].thread.as_deref(), Some("Server thread"));
+    }
+
+    /// Tests the Fabric launcher download and Forge/NeoForge installer outputs
+    #[test]
+    fn test_modded_servers() {
+        let server = MockServer::start();
+        let launcher = jar_bytes(&[("META-INF/MANIFEST.MF", b"Manifest-Version: 1.0")]);
+        server.add(
+            "/v2/versions/loader/1.20.1/0.16.14/1.0.3/server/jar",
+            MockResponse::ok(launcher.clone()),
+        );
+        server.add(
+            "/v2/versions/loader/1.20.1/0.16.13/1.0.3/server/jar",
+            MockResponse::ok(b"<html>not found</html>".to_vec()),
+        );
+        let root_dir = temp_dir("server_modded");
+        let vanilla_jar = b"server jar".to_vec();
+        server.add("/server.jar", MockResponse::ok(vanilla_jar.clone()));
+        let mut version = vanilla_version_json("1.20.1", json!([]), json!([]));
+        version["downloads"]["server"]["sha1"] = json!(sha1_hex(&vanilla_jar));
+        version["downloads"]["server"]["url"] = json!(server.url("/server.jar"));
+        write_version(&root_dir, "1.20.1", &version);
+
+        let fabric_dir = root_dir.join("fabric");
+        let fabric =
+            ServerInstance::new(&root_dir, &fabric_dir).with_fabric_meta_url(&server.url("/"));
+        fabric.install_fabric("1.20.1", "0.16.14", "1.0.3").unwrap();
+        assert_eq!(
+            std::fs::read(fabric_dir.join("fabric-server-launch.jar")).unwrap(),
+            launcher
+        );
+        assert_eq!(
+            std::fs::read(fabric_dir.join("server.jar")).unwrap(),
+            vanilla_jar
+        );
+        assert!(matches!(
+            fabric.install_fabric("1.20.1", "0.16.13", "1.0.3"),
+            Err(ServerError::InvalidDownload { .. })
+        ));
+        assert_eq!(
+            std::fs::read(fabric_dir.join("fabric-server-launch.jar")).unwrap(),
+            launcher
+        );
+        assert_eq!(
+            ServerLauncher::detect(&fabric_dir),
+            Some(ServerLauncher {
+                kind: ServerKind::Fabric,
+                entry_point: ServerEntryPoint::Jar(PathBuf::from("fabric-server-launch.jar")),
+            })
+        );
+
+        let installer = write_script(
+            &root_dir.join("jdk/bin/java"),
+            r#"[ "$3" = --installServer ] || exit 2
+mkdir -p "$4/libraries/net/neoforged/neoforge/21.1.77"
+touch "$4/libraries/net/neoforged/neoforge/21.1.77/unix_args.txt" "$4/user_jvm_args.txt""#,
+        );
+        let neoforge_dir = root_dir.join("neoforge");
+        let neoforge = ServerInstance::new(&root_dir, &neoforge_dir);
+        neoforge
+            .run_installer(&installer, &root_dir.join("neoforge-installer.jar"))
+            .unwrap();
+        let older = neoforge_dir.join("libraries/net/neoforged/neoforge/21.1.9");
+        std::fs::create_dir_all(&older).unwrap();
+        std::fs::write(older.join("unix_args.txt"), "").unwrap();
+        let launcher = ServerLauncher::detect(&neoforge_dir).unwrap();
+        assert_eq!(launcher.kind, ServerKind::NeoForge);
+        assert_eq!(
+            launcher.command(&neoforge_dir, &ServerOptions::default()),
+            [
+                "java",
+                "@user_jvm_args.txt",
+                "@libraries/net/neoforged/neoforge/21.1.77/unix_args.txt",
+                "nogui"
+            ]
+        );
+
+        let forge_dir = root_dir.join("forge");
+        std::fs::create_dir_all(&forge_dir).unwrap();
+        std::fs::write(
+            forge_dir.join("forge-1.12.2-14.23.5.2859-installer.jar"),
+            "",
+        )
+        .unwrap();
+        std::fs::write(forge_dir.join("forge-1.12.2-14.23.5.2859.jar"), "").unwrap();
+        std::fs::write(forge_dir.join("minecraft_server.1.12.2.jar"), "").unwrap();
+        assert_eq!(
+            ServerLauncher::detect(&forge_dir).unwrap().entry_point,
+            ServerEntryPoint::Jar(PathBuf::from("forge-1.12.2-14.23.5.2859.jar"))
+        );
+
+        let failing = write_script(&root_dir.join("failing/java"), "exit 1");
+        assert!(matches!(
+            ServerInstance::new(&root_dir, &root_dir.join("broken"))
+                .run_installer(&failing, &root_dir.join("forge-installer.jar")),
+            Err(ServerError::InstallerFailed(_))
+        ));
+    }
+}
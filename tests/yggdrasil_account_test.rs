@@ -0,0 +1,190 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{MockResponse, MockServer};
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use craft_launcher_rust::craft_launcher::core::account::yggdrasil_account::yggdrasil_account::{
+        YggdrasilAuthenticator, YggdrasilError,
+    };
+    use serde_json::{Value, json};
+    use std::path::Path;
+
+    const STEVE: &str = "5627dd98e6be3c21b8a8e92344183641";
+    const ALEX: &str = "6ab4317889fd490597f60f67d9d76fd9";
+
+    /// Starts a Yggdrasil server whose API root is `/api/yggdrasil`
+    fn start_server() -> MockServer {
+        let server = MockServer::start();
+        server.add(
+            "/api/yggdrasil",
+            MockResponse::json(&json!({
+                "meta": {"serverName": "Community"},
+                "skinDomains": ["example.com"]
+            })),
+        );
+        server
+    }
+
+    fn session(access_token: &str, selected: Option<(&str, &str)>) -> MockResponse {
+        let profile = selected.map(|(id, name)| json!({"id": id, "name": name}));
+        MockResponse::json(&json!({
+            "accessToken": access_token,
+            "clientToken": "client-1",
+            "availableProfiles": [
+                {"id": STEVE, "name": "Steve"},
+                {"id": ALEX, "name": "Alex"}
+            ],
+            "selectedProfile": profile
+        }))
+    }
+
+    fn no_content() -> MockResponse {
+        MockResponse {
+            status: 204,
+            ..MockResponse::ok(Vec::new())
+        }
+    }
+
+    fn forbidden(message: &str) -> MockResponse {
+        MockResponse::status_json(
+            403,
+            &json!({"error": "ForbiddenOperationException", "errorMessage": message}),
+        )
+    }
+
+    fn last_body(server: &MockServer, path: &str) -> Value {
+        let request = server
+            .requests()
+            .into_iter()
+            .rev()
+            .find(|request| request.path == path)
+            .unwrap();
+        serde_json::from_str(&request.body).unwrap()
+    }
+
+    /// Tests authenticate, validate, refresh and signout
+    #[test]
+    fn test_session_lifecycle() {
+        let server = start_server();
+        server.add(
+            "/api/yggdrasil/authserver/authenticate",
+            session("token-1", Some((STEVE, "Steve"))),
+        );
+        let authenticator = YggdrasilAuthenticator::new(
+            &server.url("/api/yggdrasil/"),
+            Path::new("/launcher/authlib-injector.jar"),
+        )
+        .with_client_token("client-1");
+        assert_eq!(authenticator.server_url(), server.url("/api/yggdrasil"));
+
+        let account = authenticator
+            .authenticate("steve@example.com", "hunter2")
+            .unwrap();
+        assert_eq!(account.username, "Steve");
+        assert_eq!(account.uuid, STEVE);
+        assert_eq!(account.access_token, "token-1");
+        let body = last_body(&server, "/api/yggdrasil/authserver/authenticate");
+        assert_eq!(body["username"], "steve@example.com");
+        assert_eq!(body["password"], "hunter2");
+        assert_eq!(body["clientToken"], "client-1");
+        assert_eq!(body["agent"]["name"], "Minecraft");
+
+        let launch = account.to_account();
+        assert_eq!(launch.user_type, "mojang");
+        assert_eq!(
+            launch.jvm_args[0],
+            format!(
+                "-javaagent:/launcher/authlib-injector.jar={}",
+                server.url("/api/yggdrasil")
+            )
+        );
+        let prefetched = launch.jvm_args[1]
+            .strip_prefix("-Dauthlibinjector.yggdrasil.prefetched=")
+            .unwrap();
+        let metadata: Value =
+            serde_json::from_slice(&STANDARD.decode(prefetched).unwrap()).unwrap();
+        assert_eq!(metadata["meta"]["serverName"], "Community");
+
+        server.add("/api/yggdrasil/authserver/validate", no_content());
+        assert!(authenticator.validate(&account).unwrap());
+        server.add(
+            "/api/yggdrasil/authserver/validate",
+            forbidden("Invalid token."),
+        );
+        assert!(!authenticator.validate(&account).unwrap());
+
+        server.add(
+            "/api/yggdrasil/authserver/refresh",
+            session("token-2", Some((STEVE, "Steve"))),
+        );
+        let refreshed = authenticator.refresh(&account).unwrap();
+        assert_eq!(refreshed.access_token, "token-2");
+        assert_eq!(refreshed.uuid, STEVE);
+        let body = last_body(&server, "/api/yggdrasil/authserver/refresh");
+        assert_eq!(body["accessToken"], "token-1");
+        assert_eq!(body["clientToken"], "client-1");
+
+        server.add("/api/yggdrasil/authserver/signout", no_content());
+        authenticator
+            .signout("steve@example.com", "hunter2")
+            .unwrap();
+        assert_eq!(
+            last_body(&server, "/api/yggdrasil/authserver/signout"),
+            json!({"username": "steve@example.com", "password": "hunter2"})
+        );
+    }
+
+    /// Tests choosing among several profiles and rejected credentials
+    #[test]
+    fn test_profile_selection_and_errors() {
+        let server = start_server();
+        let authenticator = YggdrasilAuthenticator::new(
+            &server.url("/api/yggdrasil"),
+            Path::new("authlib-injector.jar"),
+        );
+        server.add(
+            "/api/yggdrasil/authserver/authenticate",
+            session("token-1", None),
+        );
+        server.add(
+            "/api/yggdrasil/authserver/refresh",
+            session("token-2", Some((ALEX, "Alex"))),
+        );
+
+        match authenticator.authenticate("user", "password") {
+            Err(YggdrasilError::ProfileSelectionRequired(names)) => {
+                assert_eq!(names, ["Steve", "Alex"])
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(matches!(
+            authenticator.authenticate_with_profile("user", "password", Some("Herobrine")),
+            Err(YggdrasilError::ProfileNotFound(_))
+        ));
+
+        let account = authenticator
+            .authenticate_with_profile("user", "password", Some("Alex"))
+            .unwrap();
+        assert_eq!(account.username, "Alex");
+        assert_eq!(account.access_token, "token-2");
+        let body = last_body(&server, "/api/yggdrasil/authserver/refresh");
+        assert_eq!(body["selectedProfile"], json!({"id": ALEX, "name": "Alex"}));
+
+        server.add(
+            "/api/yggdrasil/authserver/authenticate",
+            forbidden("Invalid credentials. Invalid username or password."),
+        );
+        assert!(matches!(
+            authenticator.authenticate("user", "wrong"),
+            Err(YggdrasilError::Forbidden(message)) if message.starts_with("Invalid credentials")
+        ));
+
+        server.add("/api/yggdrasil", MockResponse::status_json(404, &json!({})));
+        assert!(matches!(
+            authenticator.refresh(&account),
+            Err(YggdrasilError::UnexpectedStatus { status: 404, .. })
+        ));
+    }
+}